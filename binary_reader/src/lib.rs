@@ -22,11 +22,15 @@ impl<'a> BinaryReader<'a> {
         }
     }
 
-    fn read_bytes(&mut self, count: usize) -> Result<&'a [u8]> {
+    fn peek_bytes(&self, count: usize) -> Result<&'a [u8]> {
         if self.pos + count > self.buf.len() {
             return Err(Error::new(ErrorKind::UnexpectedEof, "Not enough bytes"));
         }
-        let slice = &self.buf[self.pos..self.pos + count];
+        Ok(&self.buf[self.pos..self.pos + count])
+    }
+
+    fn read_bytes(&mut self, count: usize) -> Result<&'a [u8]> {
+        let slice = self.peek_bytes(count)?;
         self.pos += count;
         Ok(slice)
     }
@@ -75,6 +79,30 @@ impl<'a> BinaryReader<'a> {
         })
     }
 
+    pub fn read_u64(&mut self) -> Result<u64> {
+        let bytes = self.read_array::<8>()?;
+        Ok(match self.endianness {
+            Endianness::Little => u64::from_le_bytes(bytes),
+            Endianness::Big => u64::from_be_bytes(bytes),
+        })
+    }
+
+    pub fn read_i64(&mut self) -> Result<i64> {
+        let bytes = self.read_array::<8>()?;
+        Ok(match self.endianness {
+            Endianness::Little => i64::from_le_bytes(bytes),
+            Endianness::Big => i64::from_be_bytes(bytes),
+        })
+    }
+
+    pub fn read_f32(&mut self) -> Result<f32> {
+        Ok(f32::from_bits(self.read_u32()?))
+    }
+
+    pub fn read_f64(&mut self) -> Result<f64> {
+        Ok(f64::from_bits(self.read_u64()?))
+    }
+
     pub fn read_u8_vec(&mut self, count: usize) -> Result<Vec<u8>> {
         Ok(self.read_bytes(count)?.to_vec())
     }
@@ -87,10 +115,46 @@ impl<'a> BinaryReader<'a> {
         (0..count).map(|_| self.read_i32()).collect()
     }
 
+    /// Returns the next byte without consuming it.
+    pub fn peek_u8(&self) -> Result<u8> {
+        Ok(self.peek_bytes(1)?[0])
+    }
+
+    /// Returns the next two bytes as a u16 without consuming them.
+    pub fn peek_u16(&self) -> Result<u16> {
+        let bytes: [u8; 2] = self.peek_bytes(2)?.try_into().unwrap();
+        Ok(match self.endianness {
+            Endianness::Little => u16::from_le_bytes(bytes),
+            Endianness::Big => u16::from_be_bytes(bytes),
+        })
+    }
+
+    /// Advances past the given number of bytes, failing without moving if there are not enough of them.
+    pub fn skip(&mut self, count: usize) -> Result<()> {
+        self.read_bytes(count).map(|_| ())
+    }
+
+    /// Moves to the given absolute position, which can be at most the length of the buffer.
+    pub fn seek(&mut self, pos: usize) -> Result<()> {
+        if pos > self.buf.len() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Position past the end of the buffer",
+            ));
+        }
+        self.pos = pos;
+        Ok(())
+    }
+
     pub fn position(&self) -> usize {
         self.pos
     }
 
+    /// Returns the number of bytes which have not been read yet.
+    pub fn remaining(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+
     pub fn len(&self) -> usize {
         self.buf.len()
     }
@@ -149,4 +213,179 @@ mod tests {
         let mut reader: BinaryReader = BinaryReader::new(&buffer, Endianness::Little);
         assert_eq!(0x04030201u32, reader.read_u32().unwrap());
     }
+
+    #[test]
+    fn reading_negative_i8() {
+        let buffer: [u8; 2] = [0xff, 0x80];
+        let mut reader: BinaryReader = BinaryReader::new(&buffer, Endianness::Big);
+        assert_eq!(-1i8, reader.read_i8().unwrap());
+        assert_eq!(-128i8, reader.read_i8().unwrap());
+    }
+
+    #[test]
+    fn reading_be_i16() {
+        let buffer: [u8; 2] = [0xff, 0xfe];
+        let mut reader: BinaryReader = BinaryReader::new(&buffer, Endianness::Big);
+        assert_eq!(-2i16, reader.read_i16().unwrap());
+    }
+
+    #[test]
+    fn reading_le_i16() {
+        let buffer: [u8; 2] = [0xfe, 0xff];
+        let mut reader: BinaryReader = BinaryReader::new(&buffer, Endianness::Little);
+        assert_eq!(-2i16, reader.read_i16().unwrap());
+    }
+
+    #[test]
+    fn reading_be_i32() {
+        let buffer: [u8; 4] = [0xca, 0xfe, 0xba, 0xbe];
+        let mut reader: BinaryReader = BinaryReader::new(&buffer, Endianness::Big);
+        assert_eq!(-889275714i32, reader.read_i32().unwrap());
+    }
+
+    #[test]
+    fn reading_le_i32() {
+        let buffer: [u8; 4] = [0xbe, 0xba, 0xfe, 0xca];
+        let mut reader: BinaryReader = BinaryReader::new(&buffer, Endianness::Little);
+        assert_eq!(-889275714i32, reader.read_i32().unwrap());
+    }
+
+    #[test]
+    fn reading_i32_vec() {
+        let buffer: [u8; 8] = [0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x07];
+        let mut reader: BinaryReader = BinaryReader::new(&buffer, Endianness::Big);
+        assert_eq!(vec![-1i32, 7], reader.read_i32_vec(2).unwrap());
+        assert!(reader.read_i32_vec(1).is_err());
+    }
+
+    #[test]
+    fn position_and_length() {
+        let buffer: [u8; 7] = [1, 2, 3, 4, 5, 6, 7];
+        let mut reader: BinaryReader = BinaryReader::new(&buffer, Endianness::Big);
+        assert_eq!(0, reader.position());
+        reader.read_u8().unwrap();
+        assert_eq!(1, reader.position());
+        reader.read_u16().unwrap();
+        assert_eq!(3, reader.position());
+        reader.read_u32().unwrap();
+        assert_eq!(7, reader.position());
+        // the position does not move on failed reads
+        assert!(reader.read_u8().is_err());
+        assert_eq!(7, reader.position());
+        assert_eq!(7, reader.len());
+        assert!(!reader.is_empty());
+        assert!(BinaryReader::new(&[], Endianness::Big).is_empty());
+    }
+
+    #[test]
+    fn reading_be_u64_and_i64() {
+        let buffer: [u8; 16] = [
+            1, 2, 3, 4, 5, 6, 7, 8, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe,
+        ];
+        let mut reader: BinaryReader = BinaryReader::new(&buffer, Endianness::Big);
+        assert_eq!(0x0102030405060708u64, reader.read_u64().unwrap());
+        assert_eq!(-2i64, reader.read_i64().unwrap());
+    }
+
+    #[test]
+    fn reading_le_u64_and_i64() {
+        let buffer: [u8; 16] = [
+            1, 2, 3, 4, 5, 6, 7, 8, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        ];
+        let mut reader: BinaryReader = BinaryReader::new(&buffer, Endianness::Little);
+        assert_eq!(0x0807060504030201u64, reader.read_u64().unwrap());
+        assert_eq!(-2i64, reader.read_i64().unwrap());
+    }
+
+    #[test]
+    fn reading_floats() {
+        for (endianness, values) in [
+            (
+                Endianness::Big,
+                [1.5f32.to_be_bytes(), (-0.25f32).to_be_bytes()],
+            ),
+            (
+                Endianness::Little,
+                [1.5f32.to_le_bytes(), (-0.25f32).to_le_bytes()],
+            ),
+        ] {
+            let buffer: Vec<u8> = values.concat();
+            let mut reader: BinaryReader = BinaryReader::new(&buffer, endianness);
+            assert_eq!(1.5f32, reader.read_f32().unwrap());
+            assert_eq!(-0.25f32, reader.read_f32().unwrap());
+        }
+    }
+
+    #[test]
+    fn reading_doubles() {
+        for (endianness, values) in [
+            (
+                Endianness::Big,
+                [2.5f64.to_be_bytes(), f64::MIN.to_be_bytes()],
+            ),
+            (
+                Endianness::Little,
+                [2.5f64.to_le_bytes(), f64::MIN.to_le_bytes()],
+            ),
+        ] {
+            let buffer: Vec<u8> = values.concat();
+            let mut reader: BinaryReader = BinaryReader::new(&buffer, endianness);
+            assert_eq!(2.5f64, reader.read_f64().unwrap());
+            assert_eq!(f64::MIN, reader.read_f64().unwrap());
+            assert!(reader.read_f64().is_err());
+        }
+    }
+
+    #[test]
+    fn skipping_and_seeking() {
+        let buffer: [u8; 6] = [1, 2, 3, 4, 5, 6];
+        let mut reader: BinaryReader = BinaryReader::new(&buffer, Endianness::Big);
+        reader.skip(2).unwrap();
+        assert_eq!(3, reader.read_u8().unwrap());
+        assert_eq!(3, reader.remaining());
+        assert!(reader.skip(4).is_err());
+        assert_eq!(3, reader.position());
+
+        reader.seek(1).unwrap();
+        assert_eq!(0x0203, reader.read_u16().unwrap());
+        reader.seek(6).unwrap();
+        assert_eq!(0, reader.remaining());
+        assert!(reader.seek(7).is_err());
+        assert_eq!(6, reader.position());
+    }
+
+    #[test]
+    fn short_buffers_are_errors() {
+        let buffer: [u8; 1] = [1];
+        let mut reader: BinaryReader = BinaryReader::new(&buffer, Endianness::Big);
+        let error: Error = reader.read_u16().unwrap_err();
+        assert_eq!(ErrorKind::UnexpectedEof, error.kind());
+        assert_eq!(
+            ErrorKind::UnexpectedEof,
+            reader.read_u32().unwrap_err().kind()
+        );
+        assert_eq!(
+            ErrorKind::UnexpectedEof,
+            reader.read_i32().unwrap_err().kind()
+        );
+        // nothing was consumed by the failed reads
+        assert_eq!(1, reader.read_u8().unwrap());
+    }
+
+    #[test]
+    fn peeking() {
+        let buffer: [u8; 3] = [0x01, 0x02, 0x03];
+        let mut reader: BinaryReader = BinaryReader::new(&buffer, Endianness::Little);
+        assert_eq!(1, reader.peek_u8().unwrap());
+        assert_eq!(0x0201, reader.peek_u16().unwrap());
+        assert_eq!(0, reader.position());
+        assert_eq!(1, reader.read_u8().unwrap());
+        assert_eq!(0x0302, reader.peek_u16().unwrap());
+        reader.skip(2).unwrap();
+        assert_eq!(
+            ErrorKind::UnexpectedEof,
+            reader.peek_u8().unwrap_err().kind()
+        );
+        assert_eq!(3, reader.position());
+    }
 }
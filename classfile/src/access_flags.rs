@@ -1,5 +1,6 @@
 #[repr(u16)]
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ClassAccessFlag {
     Public = 0x0001,
     Final = 0x0010,
@@ -12,7 +13,8 @@ pub enum ClassAccessFlag {
     Module = 0x8000,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ClassAccessFlags(u16);
 
 impl ClassAccessFlags {
@@ -43,24 +45,35 @@ impl ClassAccessFlags {
         parts.join(", ")
     }
 
+    /// Returns the modifiers followed by the keyword of the declaration, like `public final class`.
     pub fn modifier_repr(&self) -> String {
-        let parts: Vec<&str> = [
+        let is_interface: bool = self.contains(ClassAccessFlag::Interface);
+        let mut parts: Vec<&str> = [
             (ClassAccessFlag::Public, "public"),
             (ClassAccessFlag::Final, "final"),
-            (ClassAccessFlag::Super, "class"),
-            (ClassAccessFlag::Interface, "interface"),
-            (ClassAccessFlag::Abstract, ""),
-            (ClassAccessFlag::Synthetic, ""),
-            (ClassAccessFlag::Annotation, "annotation"),
-            (ClassAccessFlag::Enum, ""),
-            (ClassAccessFlag::Module, "module"),
+            (ClassAccessFlag::Abstract, "abstract"),
         ]
         .iter()
-        .filter(|(flag, repr)| self.contains(*flag) && !repr.is_empty())
+        .filter(|(flag, _)| self.contains(*flag))
+        // interfaces are implicitly abstract
+        .filter(|(flag, _)| !(is_interface && *flag == ClassAccessFlag::Abstract))
         .map(|(_, repr)| *repr)
         .collect();
+        parts.push(self.keyword());
         parts.join(" ")
     }
+
+    /// Returns the keyword of the declaration. ACC_SUPER is not considered since it is meaningless in modern class
+    /// files. Annotations are interfaces and both enums and records are classes.
+    pub fn keyword(&self) -> &'static str {
+        if self.contains(ClassAccessFlag::Module) {
+            "module"
+        } else if self.contains(ClassAccessFlag::Interface) {
+            "interface"
+        } else {
+            "class"
+        }
+    }
 }
 
 impl From<u16> for ClassAccessFlags {
@@ -93,7 +106,8 @@ impl From<u16> for ClassAccessFlags {
 }
 
 #[repr(u16)]
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum InnerClassAccessFlag {
     Public = 0x0001,
     Private = 0x0002,
@@ -107,7 +121,8 @@ pub enum InnerClassAccessFlag {
     Enum = 0x4000,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InnerClassAccessFlags(u16);
 
 impl InnerClassAccessFlags {
@@ -129,7 +144,7 @@ impl InnerClassAccessFlags {
             (InnerClassAccessFlag::Interface, ""),
             (InnerClassAccessFlag::Abstract, "abstract"),
             (InnerClassAccessFlag::Synthetic, ""),
-            (InnerClassAccessFlag::Annotation, "annotation"),
+            (InnerClassAccessFlag::Annotation, ""),
             (InnerClassAccessFlag::Enum, ""),
         ]
         .iter()
@@ -159,7 +174,8 @@ impl From<u16> for InnerClassAccessFlags {
 }
 
 #[repr(u16)]
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FieldAccessFlag {
     Public = 0x0001,
     Private = 0x0002,
@@ -172,7 +188,8 @@ pub enum FieldAccessFlag {
     Enum = 0x4000,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FieldAccessFlags(u16);
 
 impl FieldAccessFlags {
@@ -251,7 +268,8 @@ impl From<u16> for FieldAccessFlags {
 }
 
 #[repr(u16)]
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MethodAccessFlag {
     Public = 0x0001,
     Private = 0x0002,
@@ -267,7 +285,8 @@ pub enum MethodAccessFlag {
     Synthetic = 0x1000,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MethodAccessFlags(u16);
 
 impl MethodAccessFlags {
@@ -352,14 +371,16 @@ impl From<u16> for MethodAccessFlags {
 }
 
 #[repr(u16)]
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MethodParameterAccessFlag {
     Final = 0x0010,
     Synthetic = 0x1000,
     Mandated = 0x8000,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MethodParameterAccessFlags(u16);
 
 impl MethodParameterAccessFlags {
@@ -394,3 +415,200 @@ impl From<u16> for MethodParameterAccessFlags {
         MethodParameterAccessFlags(flags)
     }
 }
+
+/// The flags of a module, found in its Module attribute.
+#[repr(u16)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ModuleAccessFlag {
+    Open = 0x0020,
+    Synthetic = 0x1000,
+    Mandated = 0x8000,
+}
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModuleAccessFlags(u16);
+
+impl ModuleAccessFlags {
+    pub fn to_u16(&self) -> u16 {
+        self.0
+    }
+
+    pub fn contains(&self, flag: ModuleAccessFlag) -> bool {
+        (self.0 & flag as u16) != 0
+    }
+}
+
+impl From<u16> for ModuleAccessFlags {
+    fn from(flags: u16) -> Self {
+        assert!(
+            (flags & !0x9020) == 0,
+            "0x{flags:04x} is not a valid combination of module access flags.",
+        );
+        ModuleAccessFlags(flags)
+    }
+}
+
+/// The flags of a dependence of a module, found in the `requires` table of its Module attribute.
+#[repr(u16)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ModuleRequiresAccessFlag {
+    Transitive = 0x0020,
+    StaticPhase = 0x0040,
+    Synthetic = 0x1000,
+    Mandated = 0x8000,
+}
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModuleRequiresAccessFlags(u16);
+
+impl ModuleRequiresAccessFlags {
+    pub fn to_u16(&self) -> u16 {
+        self.0
+    }
+
+    pub fn contains(&self, flag: ModuleRequiresAccessFlag) -> bool {
+        (self.0 & flag as u16) != 0
+    }
+
+    pub fn modifier_repr(&self) -> String {
+        [
+            (ModuleRequiresAccessFlag::StaticPhase, "static"),
+            (ModuleRequiresAccessFlag::Transitive, "transitive"),
+        ]
+        .iter()
+        .filter(|(flag, _)| self.contains(*flag))
+        .map(|(_, repr)| *repr)
+        .collect::<Vec<_>>()
+        .join(" ")
+    }
+}
+
+impl From<u16> for ModuleRequiresAccessFlags {
+    fn from(flags: u16) -> Self {
+        assert!(
+            (flags & !0x9060) == 0,
+            "0x{flags:04x} is not a valid combination of module requires access flags.",
+        );
+        ModuleRequiresAccessFlags(flags)
+    }
+}
+
+/// The flags of a package exported or opened by a module, found in the `exports` and `opens` tables of its Module
+/// attribute.
+#[repr(u16)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ModuleExportsAccessFlag {
+    Synthetic = 0x1000,
+    Mandated = 0x8000,
+}
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModuleExportsAccessFlags(u16);
+
+impl ModuleExportsAccessFlags {
+    pub fn to_u16(&self) -> u16 {
+        self.0
+    }
+
+    pub fn contains(&self, flag: ModuleExportsAccessFlag) -> bool {
+        (self.0 & flag as u16) != 0
+    }
+}
+
+impl From<u16> for ModuleExportsAccessFlags {
+    fn from(flags: u16) -> Self {
+        assert!(
+            (flags & !0x9000) == 0,
+            "0x{flags:04x} is not a valid combination of module exports access flags.",
+        );
+        ModuleExportsAccessFlags(flags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(0x0021, "public class")]
+    #[case(0x0001, "public class")]
+    #[case(0x0020, "class")]
+    #[case(0x0000, "class")]
+    #[case(0x0031, "public final class")]
+    #[case(0x0011, "public final class")]
+    #[case(0x0421, "public abstract class")]
+    #[case(0x0601, "public interface")]
+    #[case(0x2601, "public interface")]
+    #[case(0x4031, "public final class")]
+    #[case(0x8000, "module")]
+    fn class_declaration(#[case] flags: u16, #[case] expected: &str) {
+        assert_eq!(ClassAccessFlags::from(flags).modifier_repr(), expected);
+    }
+
+    #[rstest]
+    #[case(0x0040, "volatile", "ACC_VOLATILE")]
+    #[case(0x0080, "transient", "ACC_TRANSIENT")]
+    #[case(
+        0x00c2,
+        "private volatile transient",
+        "ACC_PRIVATE, ACC_VOLATILE, ACC_TRANSIENT"
+    )]
+    #[case(
+        0x4019,
+        "public static final",
+        "ACC_PUBLIC, ACC_STATIC, ACC_FINAL, ACC_ENUM"
+    )]
+    fn field_modifiers(#[case] flags: u16, #[case] modifiers: &str, #[case] java_repr: &str) {
+        let flags: FieldAccessFlags = FieldAccessFlags::from(flags);
+        assert_eq!(flags.modifier_repr(), modifiers);
+        assert_eq!(flags.java_repr(), java_repr);
+    }
+
+    #[rstest]
+    #[case(0x0020, "synchronized", "ACC_SYNCHRONIZED")]
+    #[case(0x0040, "", "ACC_BRIDGE")]
+    #[case(0x0080, "", "ACC_VARARGS")]
+    #[case(
+        0x0129,
+        "public static synchronized native",
+        "ACC_PUBLIC, ACC_STATIC, ACC_SYNCHRONIZED, ACC_NATIVE"
+    )]
+    #[case(
+        0x0c04,
+        "protected abstract strictfp",
+        "ACC_PROTECTED, ACC_ABSTRACT, ACC_STRICT"
+    )]
+    fn method_modifiers(#[case] flags: u16, #[case] modifiers: &str, #[case] java_repr: &str) {
+        let flags: MethodAccessFlags = MethodAccessFlags::from(flags);
+        assert_eq!(flags.modifier_repr(), modifiers);
+        assert_eq!(flags.java_repr(), java_repr);
+    }
+
+    #[test]
+    fn shared_bits_depend_on_the_context() {
+        // 0x0020 is ACC_SUPER for classes but ACC_SYNCHRONIZED for methods
+        assert!(ClassAccessFlags::from(0x0020).contains(ClassAccessFlag::Super));
+        assert!(MethodAccessFlags::from(0x0020).contains(MethodAccessFlag::Synchronized));
+        // 0x0040 and 0x0080 are ACC_VOLATILE and ACC_TRANSIENT for fields but ACC_BRIDGE and ACC_VARARGS for methods
+        assert!(FieldAccessFlags::from(0x0040).contains(FieldAccessFlag::Volatile));
+        assert!(MethodAccessFlags::from(0x0040).contains(MethodAccessFlag::Bridge));
+        assert!(FieldAccessFlags::from(0x0080).contains(FieldAccessFlag::Transient));
+        assert!(MethodAccessFlags::from(0x0080).contains(MethodAccessFlag::Varargs));
+    }
+
+    #[rstest]
+    #[case(0x0100)]
+    #[case(0x0800)]
+    fn method_only_flags_are_invalid_for_fields(#[case] flags: u16) {
+        let result = std::panic::catch_unwind(|| FieldAccessFlags::from(flags));
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,631 @@
+use binary_writer::{BinaryWriter, Endianness};
+
+use crate::{
+    access_flags::{FieldAccessFlag, MethodAccessFlag},
+    attributes::{AttributeInfo, AttributeKind, ExceptionTableEntry, find_attribute},
+    bytecode::{BytecodeInstruction, write_instruction},
+    classfile::ClassFile,
+    constant_pool::ConstantPoolInfo,
+    methods::MethodInfo,
+    reference_kind::ReferenceKind,
+    remapping::{remap_constant_pool_entry, remap_instruction},
+};
+
+/// The name of the synthetic field javac generates in every class containing an `assert` statement.
+pub const ASSERTIONS_DISABLED_FIELD: &str = "$assertionsDisabled";
+
+/// Returns true if the given constant pool index is a Fieldref to the `$assertionsDisabled` field of this class.
+pub fn is_assertions_disabled_ref(cf: &ClassFile, field_ref_index: u16) -> bool {
+    let cp = &cf.constant_pool;
+    match &cp[field_ref_index - 1] {
+        ConstantPoolInfo::FieldRef {
+            class_index,
+            name_and_type_index,
+        } => {
+            *class_index == cf.this_class
+                && matches!(
+                    &cp[*name_and_type_index - 1],
+                    ConstantPoolInfo::NameAndType { name_index, .. }
+                        if cp.get_utf8_content(*name_index) == ASSERTIONS_DISABLED_FIELD
+                )
+        }
+        _ => false,
+    }
+}
+
+/// Returns the indices (inside the given code, not bytecode offsets) of every `getstatic $assertionsDisabled`
+/// immediately followed by an `ifne`, which is how javac guards the body of an `assert` statement.
+pub fn assertion_guards(cf: &ClassFile, code: &[(u32, BytecodeInstruction)]) -> Vec<usize> {
+    code.windows(2)
+        .enumerate()
+        .filter(|(_, pair)| match pair {
+            [
+                (_, BytecodeInstruction::GetStatic { field_ref_index }),
+                (_, BytecodeInstruction::IfNe { .. }),
+            ] => is_assertions_disabled_ref(cf, *field_ref_index),
+            _ => false,
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Returns true if the given class contains at least one `assert` statement, that is if it declares the synthetic
+/// `static final boolean $assertionsDisabled` field and one of its methods checks it before an assertion.
+pub fn uses_assertions(cf: &ClassFile) -> bool {
+    let has_field: bool = cf.fields.iter().any(|field| {
+        field.access_flags.contains(FieldAccessFlag::Static)
+            && field.access_flags.contains(FieldAccessFlag::Final)
+            && field.access_flags.contains(FieldAccessFlag::Synthetic)
+            && cf.constant_pool.get_utf8_content(field.name_index) == ASSERTIONS_DISABLED_FIELD
+            && cf.constant_pool.get_utf8_content(field.descriptor_index) == "Z"
+    });
+
+    has_field
+        && cf.methods.iter().any(|method| {
+            match find_attribute(&method.attributes, AttributeKind::Code) {
+                Some(AttributeInfo::Code { code, .. }) => !assertion_guards(cf, code).is_empty(),
+                _ => false,
+            }
+        })
+}
+
+/// The prefix of the name of the synthetic methods javac generates to hold the body of each lambda expression.
+pub const LAMBDA_METHOD_PREFIX: &str = "lambda$";
+
+/// Returns every private synthetic method generated by javac for the body of a lambda expression.
+pub fn lambda_methods(cf: &ClassFile) -> Vec<&MethodInfo> {
+    cf.methods
+        .iter()
+        .filter(|method| {
+            method.access_flags.contains(MethodAccessFlag::Private)
+                && method.access_flags.contains(MethodAccessFlag::Synthetic)
+                && cf
+                    .constant_pool
+                    .get_utf8_content(method.name_index)
+                    .starts_with(LAMBDA_METHOD_PREFIX)
+        })
+        .collect()
+}
+
+/// Returns the method of this class implementing the lambda (or method reference) created by the `invokedynamic`
+/// referencing the given constant pool entry, if its bootstrap method is one of `LambdaMetafactory`'s.
+pub fn lambda_target(cf: &ClassFile, invoke_dynamic_index: u16) -> Option<&MethodInfo> {
+    let cp = &cf.constant_pool;
+    let ConstantPoolInfo::InvokeDynamic {
+        bootstrap_method_attr_index,
+        ..
+    } = &cp[invoke_dynamic_index - 1]
+    else {
+        return None;
+    };
+    let Some(AttributeInfo::BootstrapMethods { methods, .. }) =
+        find_attribute(&cf.attributes, AttributeKind::BootstrapMethods)
+    else {
+        panic!("Expected a BootstrapMethods attribute in a class using invokedynamic.");
+    };
+    let bootstrap = &methods[usize::from(*bootstrap_method_attr_index)];
+
+    let handle_target = |cp_index: u16| -> Option<u16> {
+        match &cp[cp_index - 1] {
+            ConstantPoolInfo::MethodHandle {
+                reference_index, ..
+            } => Some(*reference_index),
+            _ => None,
+        }
+    };
+    let metafactory: u16 = handle_target(bootstrap.bootstrap_method_ref)?;
+    if !cp
+        .get_method_ref(metafactory)
+        .starts_with("java/lang/invoke/LambdaMetafactory.")
+    {
+        return None;
+    }
+
+    // the second static argument is the handle to the implementation method
+    let implementation: u16 = handle_target(*bootstrap.bootstrap_arguments.get(1)?)?;
+    let (ConstantPoolInfo::MethodRef {
+        class_index,
+        name_and_type_index,
+    }
+    | ConstantPoolInfo::InterfaceMethodRef {
+        class_index,
+        name_and_type_index,
+    }) = &cp[implementation - 1]
+    else {
+        return None;
+    };
+    if *class_index != cf.this_class {
+        return None;
+    }
+    let ConstantPoolInfo::NameAndType {
+        name_index,
+        descriptor_index,
+    } = &cp[*name_and_type_index - 1]
+    else {
+        panic!("Expected entry #{name_and_type_index} to be of NameAndType type but it wasn't.");
+    };
+    cf.find_method(
+        &cp.get_utf8_content(*name_index),
+        &cp.get_utf8_content(*descriptor_index),
+    )
+}
+
+/// An `invokedynamic` instruction, resolved through the constant pool and the BootstrapMethods attribute.
+pub struct IndyCallSite<'a> {
+    /// The method containing the instruction.
+    pub method: &'a MethodInfo,
+
+    /// The bytecode offset of the instruction inside the code of the method.
+    pub offset: u32,
+
+    /// The kind of the method handle to the bootstrap method.
+    pub bootstrap_kind: ReferenceKind,
+
+    /// The bootstrap method, like `java/lang/invoke/StringConcatFactory.makeConcatWithConstants:(...)...`.
+    pub bootstrap_method: String,
+
+    /// The name and descriptor of the call site, like `makeConcatWithConstants:(I)Ljava/lang/String;`.
+    pub name_and_type: String,
+
+    /// The constant pool indices of the static arguments passed to the bootstrap method.
+    pub bootstrap_arguments: Vec<u16>,
+}
+
+impl ClassFile {
+    /// Returns every `invokedynamic` instruction of this class, in the order of methods and offsets.
+    pub fn indy_call_sites(&self) -> Vec<IndyCallSite<'_>> {
+        let cp = &self.constant_pool;
+        let mut call_sites: Vec<IndyCallSite> = Vec::new();
+        for method in self.methods.iter() {
+            let Some(AttributeInfo::Code { code, .. }) =
+                find_attribute(&method.attributes, AttributeKind::Code)
+            else {
+                continue;
+            };
+            for (offset, instruction) in code.iter() {
+                let BytecodeInstruction::InvokeDynamic {
+                    constant_pool_index,
+                } = instruction
+                else {
+                    continue;
+                };
+                let ConstantPoolInfo::InvokeDynamic {
+                    bootstrap_method_attr_index,
+                    name_and_type_index,
+                } = &cp[*constant_pool_index - 1]
+                else {
+                    panic!(
+                        "Expected entry #{constant_pool_index} to be of InvokeDynamic type but it wasn't."
+                    );
+                };
+                let Some(AttributeInfo::BootstrapMethods { methods, .. }) =
+                    find_attribute(&self.attributes, AttributeKind::BootstrapMethods)
+                else {
+                    panic!("Expected a BootstrapMethods attribute in a class using invokedynamic.");
+                };
+                let bootstrap = &methods[usize::from(*bootstrap_method_attr_index)];
+                let bootstrap_method_ref: u16 = bootstrap.bootstrap_method_ref;
+                let ConstantPoolInfo::MethodHandle {
+                    reference_kind,
+                    reference_index,
+                } = &cp[bootstrap_method_ref - 1]
+                else {
+                    panic!(
+                        "Expected entry #{bootstrap_method_ref} to be of MethodHandle type but it wasn't."
+                    );
+                };
+                call_sites.push(IndyCallSite {
+                    method,
+                    offset: *offset,
+                    bootstrap_kind: *reference_kind,
+                    bootstrap_method: cp.get_method_ref(*reference_index),
+                    name_and_type: cp.get_name_and_type(*name_and_type_index),
+                    bootstrap_arguments: bootstrap.bootstrap_arguments.clone(),
+                });
+            }
+        }
+        call_sites
+    }
+}
+
+/// The bootstrap method javac (since Java 9) uses for string concatenations.
+pub const STRING_CONCAT_BOOTSTRAP_METHOD: &str =
+    "java/lang/invoke/StringConcatFactory.makeConcatWithConstants";
+
+/// A string concatenation compiled to an `invokedynamic` to `StringConcatFactory.makeConcatWithConstants`.
+pub struct StringConcatSite<'a> {
+    /// The method containing the concatenation.
+    pub method: &'a MethodInfo,
+
+    /// The bytecode offset of the `invokedynamic` inside the code of the method.
+    pub offset: u32,
+
+    /// How the result is built: each `\u{1}` is replaced by the next argument found on the stack and each `\u{2}` by
+    /// the next constant, while all other characters are copied as they are.
+    pub recipe: String,
+
+    /// The descriptor of the call site, whose parameters are the types of the arguments, like
+    /// `(Ljava/lang/String;I)Ljava/lang/String;`.
+    pub descriptor: String,
+
+    /// The constant pool indices of the constants referenced by the recipe.
+    pub constants: Vec<u16>,
+}
+
+/// Returns every string concatenation of the given class, in the order of methods and offsets.
+pub fn string_concats(cf: &ClassFile) -> Vec<StringConcatSite<'_>> {
+    let cp = &cf.constant_pool;
+    cf.indy_call_sites()
+        .into_iter()
+        .filter(|call_site| {
+            call_site
+                .bootstrap_method
+                .split_once(':')
+                .is_some_and(|(method, _)| method == STRING_CONCAT_BOOTSTRAP_METHOD)
+        })
+        .map(|call_site| {
+            let (&recipe_index, constants) = call_site
+                .bootstrap_arguments
+                .split_first()
+                .expect("Expected makeConcatWithConstants to receive the recipe.");
+            let ConstantPoolInfo::String { string_index } = &cp[recipe_index - 1] else {
+                panic!("Expected entry #{recipe_index} to be of String type but it wasn't.");
+            };
+            let (_, descriptor) = call_site
+                .name_and_type
+                .split_once(':')
+                .expect("Expected a name and a descriptor.");
+            StringConcatSite {
+                method: call_site.method,
+                offset: call_site.offset,
+                recipe: cp.get_utf8_content(*string_index),
+                descriptor: descriptor.to_owned(),
+                constants: constants.to_vec(),
+            }
+        })
+        .collect()
+}
+
+/**
+ * Describes the constant pool entry at the given index by its contents, with the descriptions of the entries it
+ * references (and of the bootstrap method of InvokeDynamic entries) in place of their indices. Entries which are
+ * equal up to the numbering of the constant pool get the same description.
+ */
+fn describe_constant(cf: &ClassFile, idx: u16) -> String {
+    let cp = &cf.constant_pool;
+    let entry: &ConstantPoolInfo = &cp[idx - 1];
+    let mut out: String = format!("{}(", entry.tag());
+    match entry {
+        ConstantPoolInfo::Utf8 { .. } => out += &format!("{:?}", cp.get_utf8_content(idx)),
+        ConstantPoolInfo::Integer { bytes } | ConstantPoolInfo::Float { bytes } => {
+            out += &bytes.to_string()
+        }
+        ConstantPoolInfo::Long {
+            high_bytes,
+            low_bytes,
+        }
+        | ConstantPoolInfo::Double {
+            high_bytes,
+            low_bytes,
+        } => out += &format!("{high_bytes},{low_bytes}"),
+        ConstantPoolInfo::MethodHandle { reference_kind, .. } => {
+            out += &(*reference_kind as u8).to_string()
+        }
+        ConstantPoolInfo::Dynamic {
+            bootstrap_method_attr_index,
+            ..
+        }
+        | ConstantPoolInfo::InvokeDynamic {
+            bootstrap_method_attr_index,
+            ..
+        } => {
+            let Some(AttributeInfo::BootstrapMethods { methods, .. }) =
+                find_attribute(&cf.attributes, AttributeKind::BootstrapMethods)
+            else {
+                panic!("Expected a BootstrapMethods attribute in a class using dynamic constants.");
+            };
+            let bootstrap = &methods[usize::from(*bootstrap_method_attr_index)];
+            out += &describe_constant(cf, bootstrap.bootstrap_method_ref);
+            for argument in bootstrap.bootstrap_arguments.iter() {
+                out += ",";
+                out += &describe_constant(cf, *argument);
+            }
+        }
+        _ => {}
+    }
+    remap_constant_pool_entry(&mut entry.clone(), &mut |reference| {
+        out += ",";
+        out += &describe_constant(cf, reference);
+        reference
+    });
+    out += ")";
+    out
+}
+
+/// An instruction (or exception handler) with its constant pool operands resolved, to be compared across classes.
+#[derive(PartialEq, Eq)]
+struct ResolvedInstruction {
+    position: u32,
+    /// The encoding of the instruction, with all its constant pool indices set to zero.
+    bytes: Vec<u8>,
+    constants: Vec<String>,
+}
+
+fn resolve_code(
+    cf: &ClassFile,
+    code: &[(u32, BytecodeInstruction)],
+    exception_table: &[ExceptionTableEntry],
+) -> Vec<ResolvedInstruction> {
+    let mut resolved: Vec<ResolvedInstruction> = Vec::new();
+    for (position, instruction) in code.iter() {
+        let mut constants: Vec<String> = Vec::new();
+        let mut instruction: BytecodeInstruction = instruction.clone();
+        remap_instruction(&mut instruction, &mut |idx| {
+            constants.push(describe_constant(cf, idx));
+            0
+        });
+        let mut w: BinaryWriter = BinaryWriter::new(Endianness::Big);
+        write_instruction(&mut w, &instruction);
+        resolved.push(ResolvedInstruction {
+            position: *position,
+            bytes: w.array(),
+            constants,
+        });
+    }
+    for entry in exception_table.iter() {
+        resolved.push(ResolvedInstruction {
+            position: entry.handler_pc.into(),
+            bytes: [entry.start_pc.to_be_bytes(), entry.end_pc.to_be_bytes()].concat(),
+            constants: match entry.catch_type {
+                0 => Vec::new(),
+                catch_type => vec![describe_constant(cf, catch_type)],
+            },
+        });
+    }
+    resolved
+}
+
+/**
+ * Returns true if the given methods, each belonging to the given class, have the same bytecode and exception table
+ * once every constant pool index is replaced by the contents of the entry it references. Methods which differ only in
+ * the numbering of their constant pools are equivalent, while methods without code are equivalent to each other.
+ */
+pub fn instructions_equivalent(
+    a: &ClassFile,
+    am: &MethodInfo,
+    b: &ClassFile,
+    bm: &MethodInfo,
+) -> bool {
+    match (
+        find_attribute(&am.attributes, AttributeKind::Code),
+        find_attribute(&bm.attributes, AttributeKind::Code),
+    ) {
+        (
+            Some(AttributeInfo::Code {
+                code: a_code,
+                exception_table: a_exception_table,
+                ..
+            }),
+            Some(AttributeInfo::Code {
+                code: b_code,
+                exception_table: b_exception_table,
+                ..
+            }),
+        ) => {
+            resolve_code(a, a_code, a_exception_table) == resolve_code(b, b_code, b_exception_table)
+        }
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        classfile::parse_class_file_from_bytes, remapping::reverse_constant_pool,
+        writer::write_class_file,
+    };
+
+    #[test]
+    fn class_with_assertions() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Assertions.class")).unwrap();
+        assert!(uses_assertions(&cf));
+        for (name, guards) in [("<init>", 1), ("getValue", 1), ("main", 0)] {
+            let method = cf.methods_named(name).next().unwrap();
+            match find_attribute(&method.attributes, AttributeKind::Code) {
+                Some(AttributeInfo::Code { code, .. }) => {
+                    assert_eq!(assertion_guards(&cf, code).len(), guards)
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    #[test]
+    fn class_without_assertions() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Employee.class")).unwrap();
+        assert!(!uses_assertions(&cf));
+    }
+
+    #[test]
+    fn lambdas_and_their_call_sites() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Stream.class")).unwrap();
+        let name = |method: &MethodInfo| cf.constant_pool.get_utf8_content(method.name_index);
+        let mut lambdas: Vec<String> = lambda_methods(&cf).into_iter().map(name).collect();
+        lambdas.sort();
+        assert_eq!(
+            lambdas,
+            vec![
+                "lambda$mapMulti$0",
+                "lambda$mapMultiToDouble$0",
+                "lambda$mapMultiToInt$0",
+                "lambda$mapMultiToLong$0"
+            ]
+        );
+
+        // #7 creates the lambda of mapMulti, while #52 is the method reference BaseStream::close
+        assert_eq!(
+            lambda_target(&cf, 7).map(name).as_deref(),
+            Some("lambda$mapMulti$0")
+        );
+        assert!(lambda_target(&cf, 52).is_none());
+    }
+
+    #[test]
+    fn class_without_lambdas() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Employee.class")).unwrap();
+        assert!(lambda_methods(&cf).is_empty());
+    }
+
+    #[test]
+    fn string_concatenations() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Employee.class")).unwrap();
+        let call_sites: Vec<IndyCallSite> = cf.indy_call_sites();
+        assert_eq!(call_sites.len(), 10);
+        for call_site in call_sites.iter() {
+            assert!(call_site.bootstrap_kind == ReferenceKind::InvokeStatic);
+            assert_eq!(
+                call_site.bootstrap_method,
+                "java/lang/invoke/StringConcatFactory.makeConcatWithConstants:(Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;Ljava/lang/invoke/MethodType;Ljava/lang/String;[Ljava/lang/Object;)Ljava/lang/invoke/CallSite;"
+            );
+            assert!(
+                call_site
+                    .name_and_type
+                    .starts_with("makeConcatWithConstants:(")
+            );
+
+            let Some(AttributeInfo::Code { code, .. }) =
+                find_attribute(&call_site.method.attributes, AttributeKind::Code)
+            else {
+                unreachable!();
+            };
+            assert!(
+                code.iter()
+                    .any(|(offset, instruction)| *offset == call_site.offset
+                        && matches!(instruction, BytecodeInstruction::InvokeDynamic { .. }))
+            );
+        }
+    }
+
+    #[test]
+    fn recipes_of_string_concatenations() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Employee.class")).unwrap();
+        let concats: Vec<StringConcatSite> = string_concats(&cf);
+        assert_eq!(
+            concats
+                .iter()
+                .map(|concat| concat.recipe.as_str())
+                .collect::<Vec<&str>>(),
+            [
+                "Employee ID: \u{1}",
+                "Name: \u{1} \u{1}",
+                "Age: \u{1}",
+                "Department: \u{1}",
+                "Job Title: \u{1}",
+                "Salary: $\u{1}",
+                "Full Time: \u{1}",
+                "Email: \u{1}",
+                "Phone: \u{1}",
+                "\u{1} salary: $\u{1}"
+            ]
+        );
+        assert_eq!(concats[0].offset, 7);
+        assert_eq!(concats[0].descriptor, "(I)Ljava/lang/String;");
+        assert_eq!(
+            concats[9].descriptor,
+            "(Ljava/lang/String;D)Ljava/lang/String;"
+        );
+        assert!(concats.iter().all(|concat| concat.constants.is_empty()));
+    }
+
+    #[test]
+    fn lambdas_are_not_string_concatenations() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Stream.class")).unwrap();
+        assert!(!cf.indy_call_sites().is_empty());
+        assert!(string_concats(&cf).is_empty());
+    }
+
+    #[test]
+    fn renumbered_constant_pool_is_equivalent() {
+        for bytes in [
+            include_bytes!("../../e2e/data/Employee.class").as_slice(),
+            include_bytes!("../../e2e/data/Stream.class").as_slice(),
+            include_bytes!("../../e2e/data/Synchronized.class").as_slice(),
+        ] {
+            let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+            let reversed: ClassFile = reverse_constant_pool(&cf);
+            assert_ne!(write_class_file(&reversed), write_class_file(&cf));
+            for (original, renumbered) in cf.methods.iter().zip(reversed.methods.iter()) {
+                assert!(instructions_equivalent(
+                    &cf, original, &reversed, renumbered
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn different_constants_are_not_equivalent() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Employee.class")).unwrap();
+        let find = |name: &str| -> usize {
+            cf.methods
+                .iter()
+                .position(|m| cf.constant_pool.get_utf8_content(m.name_index) == name)
+                .unwrap()
+        };
+        let get_first_name: usize = find("getFirstName");
+        let get_salary: usize = find("getSalary");
+        assert!(!instructions_equivalent(
+            &cf,
+            &cf.methods[get_first_name],
+            &cf,
+            &cf.methods[get_salary]
+        ));
+
+        // make getFirstName read the field of getSalary, which changes only the index of its getfield
+        let Some(AttributeInfo::Code { code, .. }) =
+            find_attribute(&cf.methods[get_salary].attributes, AttributeKind::Code)
+        else {
+            unreachable!();
+        };
+        let BytecodeInstruction::GetField { field_ref_index } = code[1].1 else {
+            unreachable!();
+        };
+        let mut changed: ClassFile = cf.clone();
+        let Some(AttributeInfo::Code { code, .. }) = changed.methods[get_first_name]
+            .attributes
+            .iter_mut()
+            .find(|attribute| attribute.kind() == AttributeKind::Code)
+        else {
+            unreachable!();
+        };
+        code[1].1 = BytecodeInstruction::GetField { field_ref_index };
+        assert!(!instructions_equivalent(
+            &cf,
+            &cf.methods[get_first_name],
+            &changed,
+            &changed.methods[get_first_name]
+        ));
+        assert!(instructions_equivalent(
+            &cf,
+            &cf.methods[get_salary],
+            &changed,
+            &changed.methods[get_salary]
+        ));
+    }
+
+    #[test]
+    fn class_without_call_sites() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/HelloWorld.class")).unwrap();
+        assert!(cf.indy_call_sites().is_empty());
+    }
+}
@@ -1,18 +1,25 @@
 use binary_reader::BinaryReader;
 
-use crate::access_flags::{InnerClassAccessFlags, MethodParameterAccessFlags};
+use crate::access_flags::{
+    InnerClassAccessFlags, MethodParameterAccessFlags, ModuleAccessFlags, ModuleExportsAccessFlags,
+    ModuleRequiresAccessFlags,
+};
 use crate::assert_valid_and_type;
-use crate::bytecode::{BytecodeInstruction, parse_bytecode};
+use crate::bytecode::{BytecodeInstruction, parse_bytecode_with_trailing_bytes};
 use crate::constant_pool::{ConstantPool, ConstantPoolTag};
 use crate::writer::{get_annotation_length, get_stack_map_entry_length};
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AttributeInfo {
     Code {
         name_index: u16,
         max_stack: u16,
         max_locals: u16,
+        #[cfg_attr(feature = "serde", serde(with = "code_by_offset"))]
         code: Vec<(u32, BytecodeInstruction)>,
+        /// The undecodable bytes found after the last reachable instruction, kept as they are.
+        trailing_bytes: Vec<u8>,
         exception_table: Vec<ExceptionTableEntry>,
         attributes: Vec<AttributeInfo>,
     },
@@ -81,6 +88,88 @@ pub enum AttributeInfo {
         name_index: u16,
         host_class_index: u16,
     },
+    PermittedSubclasses {
+        name_index: u16,
+        classes: Vec<u16>,
+    },
+    Module {
+        name_index: u16,
+        module_name_index: u16,
+        module_flags: ModuleAccessFlags,
+        /// Zero if the version of the module is not known.
+        module_version_index: u16,
+        requires: Vec<ModuleRequiresInfo>,
+        exports: Vec<ModuleExportsInfo>,
+        opens: Vec<ModuleOpensInfo>,
+        uses: Vec<u16>,
+        provides: Vec<ModuleProvidesInfo>,
+    },
+    ModulePackages {
+        name_index: u16,
+        packages: Vec<u16>,
+    },
+    ModuleMainClass {
+        name_index: u16,
+        main_class_index: u16,
+    },
+    Deprecated {
+        name_index: u16,
+    },
+    Synthetic {
+        name_index: u16,
+    },
+    /// An attribute not modeled by this crate, kept as raw bytes so that it is written back unchanged.
+    Unknown {
+        name_index: u16,
+        info: Vec<u8>,
+    },
+}
+
+/// Serializes the instructions of a Code attribute as a map from their offset to the instruction, in order.
+#[cfg(feature = "serde")]
+mod code_by_offset {
+    use std::fmt;
+
+    use serde::de::{MapAccess, Visitor};
+    use serde::ser::SerializeMap;
+    use serde::{Deserializer, Serializer};
+
+    use crate::bytecode::BytecodeInstruction;
+
+    pub(super) fn serialize<S: Serializer>(
+        code: &[(u32, BytecodeInstruction)],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(code.len()))?;
+        for (position, instruction) in code.iter() {
+            map.serialize_entry(position, instruction)?;
+        }
+        map.end()
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<(u32, BytecodeInstruction)>, D::Error> {
+        struct CodeVisitor;
+
+        impl<'de> Visitor<'de> for CodeVisitor {
+            type Value = Vec<(u32, BytecodeInstruction)>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a map from bytecode offsets to instructions")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut code: Vec<(u32, BytecodeInstruction)> = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    code.push(entry);
+                }
+                Ok(code)
+            }
+        }
+
+        deserializer.deserialize_map(CodeVisitor)
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -102,6 +191,13 @@ pub enum AttributeKind {
     Exceptions,
     EnclosingMethod,
     NestHost,
+    PermittedSubclasses,
+    Module,
+    ModulePackages,
+    ModuleMainClass,
+    Deprecated,
+    Synthetic,
+    Unknown,
 }
 
 impl std::fmt::Display for AttributeKind {
@@ -132,23 +228,63 @@ impl AttributeInfo {
             AttributeInfo::Exceptions { .. } => AttributeKind::Exceptions,
             AttributeInfo::EnclosingMethod { .. } => AttributeKind::EnclosingMethod,
             AttributeInfo::NestHost { .. } => AttributeKind::NestHost,
+            AttributeInfo::PermittedSubclasses { .. } => AttributeKind::PermittedSubclasses,
+            AttributeInfo::Module { .. } => AttributeKind::Module,
+            AttributeInfo::ModulePackages { .. } => AttributeKind::ModulePackages,
+            AttributeInfo::ModuleMainClass { .. } => AttributeKind::ModuleMainClass,
+            AttributeInfo::Deprecated { .. } => AttributeKind::Deprecated,
+            AttributeInfo::Synthetic { .. } => AttributeKind::Synthetic,
+            AttributeInfo::Unknown { .. } => AttributeKind::Unknown,
+        }
+    }
+
+    /// Returns the index of the Utf8 entry holding the name of this attribute.
+    pub fn name_index(&self) -> u16 {
+        match self {
+            AttributeInfo::Code { name_index, .. }
+            | AttributeInfo::LineNumberTable { name_index, .. }
+            | AttributeInfo::LocalVariableTable { name_index, .. }
+            | AttributeInfo::LocalVariableTypeTable { name_index, .. }
+            | AttributeInfo::StackMapTable { name_index, .. }
+            | AttributeInfo::SourceFile { name_index, .. }
+            | AttributeInfo::BootstrapMethods { name_index, .. }
+            | AttributeInfo::InnerClasses { name_index, .. }
+            | AttributeInfo::MethodParameters { name_index, .. }
+            | AttributeInfo::Record { name_index, .. }
+            | AttributeInfo::Signature { name_index, .. }
+            | AttributeInfo::NestMembers { name_index, .. }
+            | AttributeInfo::RuntimeVisibleAnnotations { name_index, .. }
+            | AttributeInfo::ConstantValue { name_index, .. }
+            | AttributeInfo::Exceptions { name_index, .. }
+            | AttributeInfo::EnclosingMethod { name_index, .. }
+            | AttributeInfo::NestHost { name_index, .. }
+            | AttributeInfo::PermittedSubclasses { name_index, .. }
+            | AttributeInfo::Module { name_index, .. }
+            | AttributeInfo::ModulePackages { name_index, .. }
+            | AttributeInfo::ModuleMainClass { name_index, .. }
+            | AttributeInfo::Deprecated { name_index }
+            | AttributeInfo::Synthetic { name_index }
+            | AttributeInfo::Unknown { name_index, .. } => *name_index,
         }
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Annotation {
     pub type_index: u16,
     pub element_value_pairs: Vec<ElementValuePair>,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ElementValuePair {
     pub element_name_index: u16,
     pub value: ElementValue,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ElementValue {
     Byte {
         const_value_index: u16,
@@ -212,20 +348,23 @@ impl ElementValue {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RecordComponentInfo {
     pub name_index: u16,
     pub descriptor_index: u16,
     pub attributes: Vec<AttributeInfo>,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MethodParameter {
     pub name_index: u16,
     pub access_flags: MethodParameterAccessFlags,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExceptionTableEntry {
     pub start_pc: u16,
     pub end_pc: u16,
@@ -233,13 +372,15 @@ pub struct ExceptionTableEntry {
     pub catch_type: u16,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LineNumberTableEntry {
     pub start_pc: u16,
     pub line_number: u16,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LocalVariableTableEntry {
     pub start_pc: u16,
     pub length: u16,
@@ -248,16 +389,18 @@ pub struct LocalVariableTableEntry {
     pub index: u16,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LocalVariableTypeTableEntry {
     pub start_pc: u16,
     pub length: u16,
     pub name_index: u16,
-    pub descriptor_index: u16,
+    pub signature_index: u16,
     pub index: u16,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StackMapFrame {
     SameFrame {
         frame_type: u8,
@@ -289,7 +432,8 @@ pub enum StackMapFrame {
     },
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VerificationTypeInfo {
     TopVariable,
     IntegerVariable,
@@ -302,13 +446,15 @@ pub enum VerificationTypeInfo {
     UninitializedVariable { offset: u16 },
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BootstrapMethod {
     pub bootstrap_method_ref: u16,
     pub bootstrap_arguments: Vec<u16>,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InnerClassInfo {
     pub inner_class_info_index: u16,
     pub outer_class_info_index: u16,
@@ -333,6 +479,40 @@ impl InnerClassInfo {
     }
 }
 
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModuleRequiresInfo {
+    pub requires_index: u16,
+    pub requires_flags: ModuleRequiresAccessFlags,
+    /// Zero if the version of the required module is not known.
+    pub requires_version_index: u16,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModuleExportsInfo {
+    pub exports_index: u16,
+    pub exports_flags: ModuleExportsAccessFlags,
+    /// The modules the package is exported to, or none if it is exported to every module.
+    pub exports_to_index: Vec<u16>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModuleOpensInfo {
+    pub opens_index: u16,
+    pub opens_flags: ModuleExportsAccessFlags,
+    /// The modules the package is opened to, or none if it is opened to every module.
+    pub opens_to_index: Vec<u16>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModuleProvidesInfo {
+    pub provides_index: u16,
+    pub provides_with_index: Vec<u16>,
+}
+
 pub fn parse_class_attributes(
     reader: &mut BinaryReader,
     cp: &ConstantPool,
@@ -342,8 +522,10 @@ pub fn parse_class_attributes(
     for i in 0..num_attributes {
         attributes.push(parse_classfile_attribute(reader, cp));
         for j in 0..i {
+            // unknown attributes are kept as they are, duplicates included
             assert!(
-                attributes[i].kind() != attributes[j].kind(),
+                attributes[i].kind() == AttributeKind::Unknown
+                    || attributes[i].kind() != attributes[j].kind(),
                 "Found duplicate class attributes with kind {} at indices {} and {}.",
                 attributes[i].kind(),
                 i,
@@ -520,9 +702,182 @@ fn parse_classfile_attribute(reader: &mut BinaryReader, cp: &ConstantPool) -> At
                 host_class_index,
             }
         }
-        _ => panic!(
-            "The name '{attribute_name}' is either not of an attribute or not a class attribute.",
-        ),
+        "PermittedSubclasses" => {
+            let number_of_classes: u16 = reader.read_u16().unwrap();
+            let classes: Vec<u16> = reader.read_u16_vec(number_of_classes.into()).unwrap();
+            for class_index in classes.iter() {
+                assert_valid_and_type!(cp, *class_index, ConstantPoolTag::Class);
+            }
+            AttributeInfo::PermittedSubclasses {
+                name_index: attribute_name_index,
+                classes,
+            }
+        }
+        "Module" => parse_module(reader, cp, attribute_name_index, attribute_length),
+        "ModulePackages" => {
+            let package_count: u16 = reader.read_u16().unwrap();
+            check_attribute_length(
+                2 + 2 * u32::from(package_count),
+                attribute_length,
+                attribute_name,
+            );
+            let packages: Vec<u16> = reader.read_u16_vec(package_count.into()).unwrap();
+            for package_index in packages.iter() {
+                assert_valid_and_type!(cp, *package_index, ConstantPoolTag::Package);
+            }
+            AttributeInfo::ModulePackages {
+                name_index: attribute_name_index,
+                packages,
+            }
+        }
+        "ModuleMainClass" => {
+            check_attribute_length(2, attribute_length, attribute_name);
+            let main_class_index: u16 = reader.read_u16().unwrap();
+            assert_valid_and_type!(cp, main_class_index, ConstantPoolTag::Class);
+            AttributeInfo::ModuleMainClass {
+                name_index: attribute_name_index,
+                main_class_index,
+            }
+        }
+        "RuntimeVisibleAnnotations" => {
+            parse_runtime_visible_annotations(reader, cp, attribute_name_index, attribute_length)
+        }
+        "Deprecated" => {
+            check_attribute_length(0, attribute_length, attribute_name);
+            AttributeInfo::Deprecated {
+                name_index: attribute_name_index,
+            }
+        }
+        "Synthetic" => {
+            check_attribute_length(0, attribute_length, attribute_name);
+            AttributeInfo::Synthetic {
+                name_index: attribute_name_index,
+            }
+        }
+        _ => AttributeInfo::Unknown {
+            name_index: attribute_name_index,
+            info: reader
+                .read_u8_vec(attribute_length.try_into().unwrap())
+                .unwrap(),
+        },
+    }
+}
+
+/// Reads a u16 count followed by that many constant pool indices.
+fn parse_index_table(reader: &mut BinaryReader) -> Vec<u16> {
+    let count: u16 = reader.read_u16().unwrap();
+    reader.read_u16_vec(count.into()).unwrap()
+}
+
+fn parse_module(
+    reader: &mut BinaryReader,
+    cp: &ConstantPool,
+    name_index: u16,
+    attribute_length: u32,
+) -> AttributeInfo {
+    let start: usize = reader.position();
+
+    let module_name_index: u16 = reader.read_u16().unwrap();
+    assert_valid_and_type!(cp, module_name_index, ConstantPoolTag::Module);
+    let module_flags: ModuleAccessFlags = ModuleAccessFlags::from(reader.read_u16().unwrap());
+    let module_version_index: u16 = reader.read_u16().unwrap();
+    if module_version_index != 0 {
+        assert_valid_and_type!(cp, module_version_index, ConstantPoolTag::Utf8);
+    }
+
+    let requires_count: u16 = reader.read_u16().unwrap();
+    let mut requires: Vec<ModuleRequiresInfo> = Vec::with_capacity(requires_count.into());
+    for _ in 0..requires_count {
+        let requires_index: u16 = reader.read_u16().unwrap();
+        assert_valid_and_type!(cp, requires_index, ConstantPoolTag::Module);
+        let requires_flags: ModuleRequiresAccessFlags =
+            ModuleRequiresAccessFlags::from(reader.read_u16().unwrap());
+        let requires_version_index: u16 = reader.read_u16().unwrap();
+        if requires_version_index != 0 {
+            assert_valid_and_type!(cp, requires_version_index, ConstantPoolTag::Utf8);
+        }
+        requires.push(ModuleRequiresInfo {
+            requires_index,
+            requires_flags,
+            requires_version_index,
+        });
+    }
+
+    let exports_count: u16 = reader.read_u16().unwrap();
+    let mut exports: Vec<ModuleExportsInfo> = Vec::with_capacity(exports_count.into());
+    for _ in 0..exports_count {
+        let exports_index: u16 = reader.read_u16().unwrap();
+        assert_valid_and_type!(cp, exports_index, ConstantPoolTag::Package);
+        let exports_flags: ModuleExportsAccessFlags =
+            ModuleExportsAccessFlags::from(reader.read_u16().unwrap());
+        let exports_to_index: Vec<u16> = parse_index_table(reader);
+        for index in exports_to_index.iter() {
+            assert_valid_and_type!(cp, *index, ConstantPoolTag::Module);
+        }
+        exports.push(ModuleExportsInfo {
+            exports_index,
+            exports_flags,
+            exports_to_index,
+        });
+    }
+
+    let opens_count: u16 = reader.read_u16().unwrap();
+    let mut opens: Vec<ModuleOpensInfo> = Vec::with_capacity(opens_count.into());
+    for _ in 0..opens_count {
+        let opens_index: u16 = reader.read_u16().unwrap();
+        assert_valid_and_type!(cp, opens_index, ConstantPoolTag::Package);
+        let opens_flags: ModuleExportsAccessFlags =
+            ModuleExportsAccessFlags::from(reader.read_u16().unwrap());
+        let opens_to_index: Vec<u16> = parse_index_table(reader);
+        for index in opens_to_index.iter() {
+            assert_valid_and_type!(cp, *index, ConstantPoolTag::Module);
+        }
+        opens.push(ModuleOpensInfo {
+            opens_index,
+            opens_flags,
+            opens_to_index,
+        });
+    }
+
+    let uses: Vec<u16> = parse_index_table(reader);
+    for index in uses.iter() {
+        assert_valid_and_type!(cp, *index, ConstantPoolTag::Class);
+    }
+
+    let provides_count: u16 = reader.read_u16().unwrap();
+    let mut provides: Vec<ModuleProvidesInfo> = Vec::with_capacity(provides_count.into());
+    for _ in 0..provides_count {
+        let provides_index: u16 = reader.read_u16().unwrap();
+        assert_valid_and_type!(cp, provides_index, ConstantPoolTag::Class);
+        let provides_with_index: Vec<u16> = parse_index_table(reader);
+        for index in provides_with_index.iter() {
+            assert_valid_and_type!(cp, *index, ConstantPoolTag::Class);
+        }
+        assert!(
+            !provides_with_index.is_empty(),
+            "Expected at least one implementation of service #{provides_index}."
+        );
+        provides.push(ModuleProvidesInfo {
+            provides_index,
+            provides_with_index,
+        });
+    }
+
+    check_attribute_length(
+        attribute_length,
+        (reader.position() - start).try_into().unwrap(),
+        "Module".to_owned(),
+    );
+    AttributeInfo::Module {
+        name_index,
+        module_name_index,
+        module_flags,
+        module_version_index,
+        requires,
+        exports,
+        opens,
+        uses,
+        provides,
     }
 }
 
@@ -535,8 +890,10 @@ pub fn parse_field_attributes(
     for i in 0..num_attributes {
         attributes.push(parse_field_attribute(reader, cp));
         for j in 0..i {
+            // unknown attributes are kept as they are, duplicates included
             assert!(
-                attributes[i].kind() != attributes[j].kind(),
+                attributes[i].kind() == AttributeKind::Unknown
+                    || attributes[i].kind() != attributes[j].kind(),
                 "Found duplicate field attributes with kind {} at indices {} and {}.",
                 attributes[i].kind(),
                 i,
@@ -590,9 +947,27 @@ fn parse_field_attribute(reader: &mut BinaryReader, cp: &ConstantPool) -> Attrib
                 constant_value_index,
             }
         }
-        _ => panic!(
-            "The name '{attribute_name}' is either not of an attribute or not a field attribute.",
-        ),
+        "RuntimeVisibleAnnotations" => {
+            parse_runtime_visible_annotations(reader, cp, attribute_name_index, attribute_length)
+        }
+        "Deprecated" => {
+            check_attribute_length(0, attribute_length, attribute_name);
+            AttributeInfo::Deprecated {
+                name_index: attribute_name_index,
+            }
+        }
+        "Synthetic" => {
+            check_attribute_length(0, attribute_length, attribute_name);
+            AttributeInfo::Synthetic {
+                name_index: attribute_name_index,
+            }
+        }
+        _ => AttributeInfo::Unknown {
+            name_index: attribute_name_index,
+            info: reader
+                .read_u8_vec(attribute_length.try_into().unwrap())
+                .unwrap(),
+        },
     }
 }
 
@@ -605,8 +980,10 @@ pub fn parse_method_attributes(
     for i in 0..num_attributes {
         attributes.push(parse_method_attribute(reader, cp));
         for j in 0..i {
+            // unknown attributes are kept as they are, duplicates included
             assert!(
-                attributes[i].kind() != attributes[j].kind(),
+                attributes[i].kind() == AttributeKind::Unknown
+                    || attributes[i].kind() != attributes[j].kind(),
                 "Found duplicate method attributes with kind {} at indices {} and {}.",
                 attributes[i].kind(),
                 i,
@@ -632,16 +1009,34 @@ fn parse_method_attribute(reader: &mut BinaryReader, cp: &ConstantPool) -> Attri
                 "Invalid code length: must be > 0 and < 65536 but was {code_length}.",
             );
             let code_bytes: Vec<u8> = reader.read_u8_vec(code_length.try_into().unwrap()).unwrap();
-            let code: Vec<(u32, BytecodeInstruction)> = parse_bytecode(
-                &mut BinaryReader::new(&code_bytes, binary_reader::Endianness::Big),
-                cp,
-            );
             let exception_table_length: u16 = reader.read_u16().unwrap();
             let mut exception_table: Vec<ExceptionTableEntry> =
                 Vec::with_capacity(exception_table_length.into());
-            for i in 0..exception_table_length {
-                let start_pc: u16 = reader.read_u16().unwrap();
-                let end_pc: u16 = reader.read_u16().unwrap();
+            for _ in 0..exception_table_length {
+                exception_table.push(ExceptionTableEntry {
+                    start_pc: reader.read_u16().unwrap(),
+                    end_pc: reader.read_u16().unwrap(),
+                    handler_pc: reader.read_u16().unwrap(),
+                    catch_type: reader.read_u16().unwrap(),
+                });
+            }
+            // the handlers are only reachable through the exception table, which comes after the code
+            let handlers: Vec<u32> = exception_table
+                .iter()
+                .map(|entry| entry.handler_pc.into())
+                .collect();
+            let (code, trailing_bytes) = parse_bytecode_with_trailing_bytes(
+                &mut BinaryReader::new(&code_bytes, binary_reader::Endianness::Big),
+                cp,
+                &handlers,
+            );
+            for (i, entry) in exception_table.iter().enumerate() {
+                let &ExceptionTableEntry {
+                    start_pc,
+                    end_pc,
+                    handler_pc,
+                    catch_type,
+                } = entry;
                 assert!(
                     start_pc < end_pc,
                     "Exception {i} has start_pc ({start_pc}) >= end_pc ({end_pc}).",
@@ -657,22 +1052,14 @@ fn parse_method_attribute(reader: &mut BinaryReader, cp: &ConstantPool) -> Attri
                         || (end_pc as u32) == code_length,
                     "Exception {i} has end_pc ({end_pc}) which does not correspond to a valid instruction.",
                 );
-                let handler_pc: u16 = reader.read_u16().unwrap();
                 assert!(
                     code.iter()
                         .any(|(position, _)| *position == (handler_pc as u32)),
                     "Exception {i} has handler_pc ({handler_pc}) which does not correspond to a valid instruction.",
                 );
-                let catch_type: u16 = reader.read_u16().unwrap();
                 if catch_type != 0 {
                     assert_valid_and_type!(cp, catch_type, ConstantPoolTag::Class);
                 }
-                exception_table.push(ExceptionTableEntry {
-                    start_pc,
-                    end_pc,
-                    handler_pc,
-                    catch_type,
-                });
             }
             let attribute_count: u16 = reader.read_u16().unwrap();
             let attributes: Vec<AttributeInfo> =
@@ -682,6 +1069,7 @@ fn parse_method_attribute(reader: &mut BinaryReader, cp: &ConstantPool) -> Attri
                 max_stack,
                 max_locals,
                 code,
+                trailing_bytes,
                 exception_table,
                 attributes,
             }
@@ -718,18 +1106,7 @@ fn parse_method_attribute(reader: &mut BinaryReader, cp: &ConstantPool) -> Attri
             }
         }
         "RuntimeVisibleAnnotations" => {
-            let num_annotations: u16 = reader.read_u16().unwrap();
-            let mut annotations: Vec<Annotation> = Vec::with_capacity(num_annotations.into());
-            for _ in 0..num_annotations {
-                annotations.push(parse_annotation(cp, reader));
-            }
-            let expected_attribute_length =
-                2 + annotations.iter().map(get_annotation_length).sum::<u32>();
-            check_attribute_length(expected_attribute_length, attribute_length, attribute_name);
-            AttributeInfo::RuntimeVisibleAnnotations {
-                name_index: attribute_name_index,
-                annotations,
-            }
+            parse_runtime_visible_annotations(reader, cp, attribute_name_index, attribute_length)
         }
         "Exceptions" => {
             let num_exceptions: u16 = reader.read_u16().unwrap();
@@ -744,9 +1121,47 @@ fn parse_method_attribute(reader: &mut BinaryReader, cp: &ConstantPool) -> Attri
                 exception_indices,
             }
         }
-        _ => panic!(
-            "The name '{attribute_name}' is either not of an attribute or not a method attribute.",
-        ),
+        "Deprecated" => {
+            check_attribute_length(0, attribute_length, attribute_name);
+            AttributeInfo::Deprecated {
+                name_index: attribute_name_index,
+            }
+        }
+        "Synthetic" => {
+            check_attribute_length(0, attribute_length, attribute_name);
+            AttributeInfo::Synthetic {
+                name_index: attribute_name_index,
+            }
+        }
+        _ => AttributeInfo::Unknown {
+            name_index: attribute_name_index,
+            info: reader
+                .read_u8_vec(attribute_length.try_into().unwrap())
+                .unwrap(),
+        },
+    }
+}
+
+fn parse_runtime_visible_annotations(
+    reader: &mut BinaryReader,
+    cp: &ConstantPool,
+    attribute_name_index: u16,
+    attribute_length: u32,
+) -> AttributeInfo {
+    let num_annotations: u16 = reader.read_u16().unwrap();
+    let mut annotations: Vec<Annotation> = Vec::with_capacity(num_annotations.into());
+    for _ in 0..num_annotations {
+        annotations.push(parse_annotation(cp, reader));
+    }
+    let expected_attribute_length = 2 + annotations.iter().map(get_annotation_length).sum::<u32>();
+    check_attribute_length(
+        expected_attribute_length,
+        attribute_length,
+        cp.get_utf8_content(attribute_name_index),
+    );
+    AttributeInfo::RuntimeVisibleAnnotations {
+        name_index: attribute_name_index,
+        annotations,
     }
 }
 
@@ -834,8 +1249,10 @@ fn parse_code_attributes(
     for i in 0..num_attributes {
         attributes.push(parse_code_attribute(cp, reader, code, code_length));
         for j in 0..i {
+            // unknown attributes are kept as they are, duplicates included
             assert!(
-                attributes[i].kind() != attributes[j].kind(),
+                attributes[i].kind() == AttributeKind::Unknown
+                    || attributes[i].kind() != attributes[j].kind(),
                 "Found duplicate code attributes with kind {} at indices {} and {}.",
                 attributes[i].kind(),
                 i,
@@ -942,14 +1359,14 @@ fn parse_code_attribute(
                 );
                 let name_index: u16 = reader.read_u16().unwrap();
                 assert_valid_and_type!(cp, name_index, ConstantPoolTag::Utf8);
-                let descriptor_index: u16 = reader.read_u16().unwrap();
-                assert_valid_and_type!(cp, descriptor_index, ConstantPoolTag::Utf8);
+                let signature_index: u16 = reader.read_u16().unwrap();
+                assert_valid_and_type!(cp, signature_index, ConstantPoolTag::Utf8);
                 let index: u16 = reader.read_u16().unwrap();
                 local_variable_type_table.push(LocalVariableTypeTableEntry {
                     start_pc,
                     length,
                     name_index,
-                    descriptor_index,
+                    signature_index,
                     index,
                 });
             }
@@ -975,9 +1392,12 @@ fn parse_code_attribute(
                 stack_map_table,
             }
         }
-        _ => panic!(
-            "The name '{attribute_name}' is either not of an attribute or not a code attribute."
-        ),
+        _ => AttributeInfo::Unknown {
+            name_index: attribute_name_index,
+            info: reader
+                .read_u8_vec(attribute_length.try_into().unwrap())
+                .unwrap(),
+        },
     }
 }
 
@@ -8,16 +8,24 @@ use binary_writer::BinaryWriter;
 
 use crate::{
     assert_valid_and_type,
+    code_editing::branch_targets,
     constant_pool::{ConstantPool, ConstantPoolTag},
 };
 
 /**
  * Reference available at <https://docs.oracle.com/javase/specs/jvms/se25/html/jvms-6.html#jvms-6.5>
  */
-#[derive(Clone)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BytecodeInstruction {
+    Nop {},
     Dup {},
+    DupX1 {},
+    DupX2 {},
     Dup2 {},
+    Dup2X1 {},
+    Dup2X2 {},
+    Swap {},
     AConstNull {},
     IConst {
         constant: i32,
@@ -41,34 +49,34 @@ pub enum BytecodeInstruction {
         constant_pool_index: u16,
     },
     ALoad {
-        local_variable_index: u8,
+        local_variable_index: u16,
     },
     AStore {
-        local_variable_index: u8,
+        local_variable_index: u16,
     },
     ILoad {
-        local_variable_index: u8,
+        local_variable_index: u16,
     },
     IStore {
-        local_variable_index: u8,
+        local_variable_index: u16,
     },
     LLoad {
-        local_variable_index: u8,
+        local_variable_index: u16,
     },
     LStore {
-        local_variable_index: u8,
+        local_variable_index: u16,
     },
     FLoad {
-        local_variable_index: u8,
+        local_variable_index: u16,
     },
     FStore {
-        local_variable_index: u8,
+        local_variable_index: u16,
     },
     DLoad {
-        local_variable_index: u8,
+        local_variable_index: u16,
     },
     DStore {
-        local_variable_index: u8,
+        local_variable_index: u16,
     },
     IaLoad {},
     LaLoad {},
@@ -92,7 +100,13 @@ pub enum BytecodeInstruction {
     ANewArray {
         constant_pool_index: u16,
     },
+    MultiANewArray {
+        constant_pool_index: u16,
+        dimensions: u8,
+    },
     AThrow {},
+    MonitorEnter {},
+    MonitorExit {},
     New {
         constant_pool_index: u16,
     },
@@ -195,6 +209,18 @@ pub enum BytecodeInstruction {
     GoTo {
         offset: i16,
     },
+    Jsr {
+        offset: i16,
+    },
+    GoToW {
+        offset: i32,
+    },
+    JsrW {
+        offset: i32,
+    },
+    Ret {
+        local_variable_index: u16,
+    },
     TableSwitch {
         num_padding_bytes: u8,
         default: i32,
@@ -212,9 +238,10 @@ pub enum BytecodeInstruction {
     Instanceof {
         constant_pool_index: u16,
     },
+    /// Encoded with the `wide` prefix, as two u16 operands, when the index or the constant do not fit in a byte.
     IInc {
-        index: u8,
-        constant: i8,
+        index: u16,
+        constant: i16,
     },
 
     I2L {},
@@ -272,16 +299,345 @@ pub enum BytecodeInstruction {
     DDiv {},
     DRem {},
     DSub {},
+
+    /// An opcode which is not supported, kept as it is. Its operands, if any, are decoded as the following
+    /// instructions.
+    Unknown {
+        opcode: u8,
+    },
 }
 
-#[derive(Clone)]
+impl BytecodeInstruction {
+    /// Returns the mnemonic of the opcode used to encode this instruction, as defined by the JVM specification.
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            BytecodeInstruction::Nop {} => "nop",
+            BytecodeInstruction::Dup {} => "dup",
+            BytecodeInstruction::DupX1 {} => "dup_x1",
+            BytecodeInstruction::DupX2 {} => "dup_x2",
+            BytecodeInstruction::Dup2 {} => "dup2",
+            BytecodeInstruction::Dup2X1 {} => "dup2_x1",
+            BytecodeInstruction::Dup2X2 {} => "dup2_x2",
+            BytecodeInstruction::Swap {} => "swap",
+            BytecodeInstruction::AConstNull {} => "aconst_null",
+            BytecodeInstruction::IConst { constant } => match constant {
+                -1 => "iconst_m1",
+                0 => "iconst_0",
+                1 => "iconst_1",
+                2 => "iconst_2",
+                3 => "iconst_3",
+                4 => "iconst_4",
+                5 => "iconst_5",
+                _ => panic!("Invalid iconst instruction."),
+            },
+            BytecodeInstruction::LConst { constant } => match constant {
+                0 => "lconst_0",
+                1 => "lconst_1",
+                _ => panic!("Invalid lconst instruction."),
+            },
+            BytecodeInstruction::FConst { constant } => match constant {
+                0.0f32 => "fconst_0",
+                1.0f32 => "fconst_1",
+                2.0f32 => "fconst_2",
+                _ => panic!("Invalid fconst instruction."),
+            },
+            BytecodeInstruction::DConst { constant } => match constant {
+                0.0 => "dconst_0",
+                1.0 => "dconst_1",
+                _ => panic!("Invalid dconst instruction."),
+            },
+            BytecodeInstruction::Ldc { .. } => "ldc",
+            BytecodeInstruction::LdcW { .. } => "ldc_w",
+            BytecodeInstruction::Ldc2W { .. } => "ldc2_w",
+            BytecodeInstruction::ALoad {
+                local_variable_index,
+            } => match local_variable_index {
+                0 => "aload_0",
+                1 => "aload_1",
+                2 => "aload_2",
+                3 => "aload_3",
+                4..=255 => "aload",
+                _ => "aload_w",
+            },
+            BytecodeInstruction::AStore {
+                local_variable_index,
+            } => match local_variable_index {
+                0 => "astore_0",
+                1 => "astore_1",
+                2 => "astore_2",
+                3 => "astore_3",
+                4..=255 => "astore",
+                _ => "astore_w",
+            },
+            BytecodeInstruction::ILoad {
+                local_variable_index,
+            } => match local_variable_index {
+                0 => "iload_0",
+                1 => "iload_1",
+                2 => "iload_2",
+                3 => "iload_3",
+                4..=255 => "iload",
+                _ => "iload_w",
+            },
+            BytecodeInstruction::IStore {
+                local_variable_index,
+            } => match local_variable_index {
+                0 => "istore_0",
+                1 => "istore_1",
+                2 => "istore_2",
+                3 => "istore_3",
+                4..=255 => "istore",
+                _ => "istore_w",
+            },
+            BytecodeInstruction::LLoad {
+                local_variable_index,
+            } => match local_variable_index {
+                0 => "lload_0",
+                1 => "lload_1",
+                2 => "lload_2",
+                3 => "lload_3",
+                4..=255 => "lload",
+                _ => "lload_w",
+            },
+            BytecodeInstruction::LStore {
+                local_variable_index,
+            } => match local_variable_index {
+                0 => "lstore_0",
+                1 => "lstore_1",
+                2 => "lstore_2",
+                3 => "lstore_3",
+                4..=255 => "lstore",
+                _ => "lstore_w",
+            },
+            BytecodeInstruction::FLoad {
+                local_variable_index,
+            } => match local_variable_index {
+                0 => "fload_0",
+                1 => "fload_1",
+                2 => "fload_2",
+                3 => "fload_3",
+                4..=255 => "fload",
+                _ => "fload_w",
+            },
+            BytecodeInstruction::FStore {
+                local_variable_index,
+            } => match local_variable_index {
+                0 => "fstore_0",
+                1 => "fstore_1",
+                2 => "fstore_2",
+                3 => "fstore_3",
+                4..=255 => "fstore",
+                _ => "fstore_w",
+            },
+            BytecodeInstruction::DLoad {
+                local_variable_index,
+            } => match local_variable_index {
+                0 => "dload_0",
+                1 => "dload_1",
+                2 => "dload_2",
+                3 => "dload_3",
+                4..=255 => "dload",
+                _ => "dload_w",
+            },
+            BytecodeInstruction::DStore {
+                local_variable_index,
+            } => match local_variable_index {
+                0 => "dstore_0",
+                1 => "dstore_1",
+                2 => "dstore_2",
+                3 => "dstore_3",
+                4..=255 => "dstore",
+                _ => "dstore_w",
+            },
+            BytecodeInstruction::IaLoad {} => "iaload",
+            BytecodeInstruction::LaLoad {} => "laload",
+            BytecodeInstruction::FaLoad {} => "faload",
+            BytecodeInstruction::DaLoad {} => "daload",
+            BytecodeInstruction::AaLoad {} => "aaload",
+            BytecodeInstruction::BaLoad {} => "baload",
+            BytecodeInstruction::CaLoad {} => "caload",
+            BytecodeInstruction::SaLoad {} => "saload",
+            BytecodeInstruction::IaStore {} => "iastore",
+            BytecodeInstruction::LaStore {} => "lastore",
+            BytecodeInstruction::FaStore {} => "fastore",
+            BytecodeInstruction::DaStore {} => "dastore",
+            BytecodeInstruction::AaStore {} => "aastore",
+            BytecodeInstruction::BaStore {} => "bastore",
+            BytecodeInstruction::CaStore {} => "castore",
+            BytecodeInstruction::SaStore {} => "sastore",
+            BytecodeInstruction::NewArray { .. } => "newarray",
+            BytecodeInstruction::ANewArray { .. } => "anewarray",
+            BytecodeInstruction::MultiANewArray { .. } => "multianewarray",
+            BytecodeInstruction::AThrow {} => "athrow",
+            BytecodeInstruction::MonitorEnter {} => "monitorenter",
+            BytecodeInstruction::MonitorExit {} => "monitorexit",
+            BytecodeInstruction::New { .. } => "new",
+            BytecodeInstruction::BiPush { .. } => "bipush",
+            BytecodeInstruction::SiPush { .. } => "sipush",
+            BytecodeInstruction::Pop {} => "pop",
+            BytecodeInstruction::Pop2 {} => "pop2",
+            BytecodeInstruction::Return {} => "return",
+            BytecodeInstruction::IReturn {} => "ireturn",
+            BytecodeInstruction::LReturn {} => "lreturn",
+            BytecodeInstruction::FReturn {} => "freturn",
+            BytecodeInstruction::DReturn {} => "dreturn",
+            BytecodeInstruction::AReturn {} => "areturn",
+            BytecodeInstruction::GetStatic { .. } => "getstatic",
+            BytecodeInstruction::PutStatic { .. } => "putstatic",
+            BytecodeInstruction::GetField { .. } => "getfield",
+            BytecodeInstruction::PutField { .. } => "putfield",
+            BytecodeInstruction::InvokeSpecial { .. } => "invokespecial",
+            BytecodeInstruction::InvokeStatic { .. } => "invokestatic",
+            BytecodeInstruction::InvokeVirtual { .. } => "invokevirtual",
+            BytecodeInstruction::InvokeDynamic { .. } => "invokedynamic",
+            BytecodeInstruction::InvokeInterface { .. } => "invokeinterface",
+            BytecodeInstruction::ArrayLength {} => "arraylength",
+            BytecodeInstruction::LCmp {} => "lcmp",
+            BytecodeInstruction::FCmpL {} => "fcmpl",
+            BytecodeInstruction::FCmpG {} => "fcmpg",
+            BytecodeInstruction::DCmpL {} => "dcmpl",
+            BytecodeInstruction::DCmpG {} => "dcmpg",
+            BytecodeInstruction::IfAcmpEq { .. } => "if_acmpeq",
+            BytecodeInstruction::IfAcmpNe { .. } => "if_acmpne",
+            BytecodeInstruction::IfIcmpEq { .. } => "if_icmpeq",
+            BytecodeInstruction::IfIcmpNe { .. } => "if_icmpne",
+            BytecodeInstruction::IfIcmpLt { .. } => "if_icmplt",
+            BytecodeInstruction::IfIcmpGe { .. } => "if_icmpge",
+            BytecodeInstruction::IfIcmpGt { .. } => "if_icmpgt",
+            BytecodeInstruction::IfIcmpLe { .. } => "if_icmple",
+            BytecodeInstruction::IfEq { .. } => "ifeq",
+            BytecodeInstruction::IfNe { .. } => "ifne",
+            BytecodeInstruction::IfLt { .. } => "iflt",
+            BytecodeInstruction::IfGe { .. } => "ifge",
+            BytecodeInstruction::IfGt { .. } => "ifgt",
+            BytecodeInstruction::IfLe { .. } => "ifle",
+            BytecodeInstruction::IfNull { .. } => "ifnull",
+            BytecodeInstruction::IfNonNull { .. } => "ifnonnull",
+            BytecodeInstruction::GoTo { .. } => "goto",
+            BytecodeInstruction::Jsr { .. } => "jsr",
+            BytecodeInstruction::GoToW { .. } => "goto_w",
+            BytecodeInstruction::JsrW { .. } => "jsr_w",
+            BytecodeInstruction::Ret {
+                local_variable_index,
+            } => match local_variable_index {
+                0..=255 => "ret",
+                _ => "ret_w",
+            },
+            BytecodeInstruction::TableSwitch { .. } => "tableswitch",
+            BytecodeInstruction::LookupSwitch { .. } => "lookupswitch",
+            BytecodeInstruction::CheckCast { .. } => "checkcast",
+            BytecodeInstruction::Instanceof { .. } => "instanceof",
+            BytecodeInstruction::IInc { .. } if self.is_wide() => "iinc_w",
+            BytecodeInstruction::IInc { .. } => "iinc",
+            BytecodeInstruction::I2L {} => "i2l",
+            BytecodeInstruction::I2F {} => "i2f",
+            BytecodeInstruction::I2D {} => "i2d",
+            BytecodeInstruction::L2I {} => "l2i",
+            BytecodeInstruction::L2F {} => "l2f",
+            BytecodeInstruction::L2D {} => "l2d",
+            BytecodeInstruction::F2I {} => "f2i",
+            BytecodeInstruction::F2L {} => "f2l",
+            BytecodeInstruction::F2D {} => "f2d",
+            BytecodeInstruction::D2I {} => "d2i",
+            BytecodeInstruction::D2L {} => "d2l",
+            BytecodeInstruction::D2F {} => "d2f",
+            BytecodeInstruction::I2B {} => "i2b",
+            BytecodeInstruction::I2C {} => "i2c",
+            BytecodeInstruction::I2S {} => "i2s",
+            BytecodeInstruction::IAdd {} => "iadd",
+            BytecodeInstruction::ISub {} => "isub",
+            BytecodeInstruction::IMul {} => "imul",
+            BytecodeInstruction::IDiv {} => "idiv",
+            BytecodeInstruction::IRem {} => "irem",
+            BytecodeInstruction::IAnd {} => "iand",
+            BytecodeInstruction::IShl {} => "ishl",
+            BytecodeInstruction::IShr {} => "ishr",
+            BytecodeInstruction::IUshr {} => "iushr",
+            BytecodeInstruction::IOr {} => "ior",
+            BytecodeInstruction::IXor {} => "ixor",
+            BytecodeInstruction::INeg {} => "ineg",
+            BytecodeInstruction::LAdd {} => "ladd",
+            BytecodeInstruction::LSub {} => "lsub",
+            BytecodeInstruction::LMul {} => "lmul",
+            BytecodeInstruction::LDiv {} => "ldiv",
+            BytecodeInstruction::LRem {} => "lrem",
+            BytecodeInstruction::LAnd {} => "land",
+            BytecodeInstruction::LOr {} => "lor",
+            BytecodeInstruction::LXor {} => "lxor",
+            BytecodeInstruction::LShl {} => "lshl",
+            BytecodeInstruction::LShr {} => "lshr",
+            BytecodeInstruction::LUshr {} => "lushr",
+            BytecodeInstruction::LNeg {} => "lneg",
+            BytecodeInstruction::FAdd {} => "fadd",
+            BytecodeInstruction::FMul {} => "fmul",
+            BytecodeInstruction::FNeg {} => "fneg",
+            BytecodeInstruction::FDiv {} => "fdiv",
+            BytecodeInstruction::FRem {} => "frem",
+            BytecodeInstruction::FSub {} => "fsub",
+            BytecodeInstruction::DAdd {} => "dadd",
+            BytecodeInstruction::DMul {} => "dmul",
+            BytecodeInstruction::DNeg {} => "dneg",
+            BytecodeInstruction::DDiv {} => "ddiv",
+            BytecodeInstruction::DRem {} => "drem",
+            BytecodeInstruction::DSub {} => "dsub",
+            BytecodeInstruction::Unknown { .. } => "unknown",
+        }
+    }
+
+    /// Returns true if this instruction needs the `wide` prefix because its operands do not fit in a byte.
+    pub fn is_wide(&self) -> bool {
+        match self {
+            BytecodeInstruction::ALoad {
+                local_variable_index,
+            }
+            | BytecodeInstruction::AStore {
+                local_variable_index,
+            }
+            | BytecodeInstruction::ILoad {
+                local_variable_index,
+            }
+            | BytecodeInstruction::IStore {
+                local_variable_index,
+            }
+            | BytecodeInstruction::LLoad {
+                local_variable_index,
+            }
+            | BytecodeInstruction::LStore {
+                local_variable_index,
+            }
+            | BytecodeInstruction::FLoad {
+                local_variable_index,
+            }
+            | BytecodeInstruction::FStore {
+                local_variable_index,
+            }
+            | BytecodeInstruction::DLoad {
+                local_variable_index,
+            }
+            | BytecodeInstruction::DStore {
+                local_variable_index,
+            }
+            | BytecodeInstruction::Ret {
+                local_variable_index,
+            } => *local_variable_index > u16::from(u8::MAX),
+            BytecodeInstruction::IInc { index, constant } => {
+                *index > u16::from(u8::MAX) || i8::try_from(*constant).is_err()
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LookupSwitchPair {
     pub match_value: i32,
     pub offset: i32,
 }
 
 #[repr(u8)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ArrayType {
     Boolean = 4,
     Char = 5,
@@ -335,422 +691,636 @@ pub fn parse_bytecode(
         if tmp.is_err() {
             break;
         }
-        let opcode: u8 = tmp.unwrap();
-        let instruction = match opcode {
-            0x01 => BytecodeInstruction::AConstNull {},
-            0x02 => BytecodeInstruction::IConst { constant: -1 },
-            0x03 => BytecodeInstruction::IConst { constant: 0 },
-            0x04 => BytecodeInstruction::IConst { constant: 1 },
-            0x05 => BytecodeInstruction::IConst { constant: 2 },
-            0x06 => BytecodeInstruction::IConst { constant: 3 },
-            0x07 => BytecodeInstruction::IConst { constant: 4 },
-            0x08 => BytecodeInstruction::IConst { constant: 5 },
-            0x09 => BytecodeInstruction::LConst { constant: 0 },
-            0x0a => BytecodeInstruction::LConst { constant: 1 },
-            0x0b => BytecodeInstruction::FConst { constant: 0.0f32 },
-            0x0c => BytecodeInstruction::FConst { constant: 1.0f32 },
-            0x0d => BytecodeInstruction::FConst { constant: 2.0f32 },
-            0x0e => BytecodeInstruction::DConst { constant: 0.0 },
-            0x0f => BytecodeInstruction::DConst { constant: 1.0 },
-            0x10 => BytecodeInstruction::BiPush {
-                immediate: reader.read_i8().unwrap(),
-            },
-            0x11 => BytecodeInstruction::SiPush {
-                immediate: reader.read_i16().unwrap(),
-            },
-            0x12 => BytecodeInstruction::Ldc {
-                constant_pool_index: reader.read_u8().unwrap(),
-            },
-            0x13 => BytecodeInstruction::LdcW {
-                constant_pool_index: reader.read_u16().unwrap(),
-            },
-            0x14 => BytecodeInstruction::Ldc2W {
-                constant_pool_index: reader.read_u16().unwrap(),
-            },
-            0x15 => BytecodeInstruction::ILoad {
-                local_variable_index: reader.read_u8().unwrap(),
-            },
-            0x16 => BytecodeInstruction::LLoad {
-                local_variable_index: reader.read_u8().unwrap(),
-            },
-            0x17 => BytecodeInstruction::FLoad {
-                local_variable_index: reader.read_u8().unwrap(),
-            },
-            0x18 => BytecodeInstruction::DLoad {
-                local_variable_index: reader.read_u8().unwrap(),
-            },
-            0x19 => BytecodeInstruction::ALoad {
-                local_variable_index: reader.read_u8().unwrap(),
-            },
-            0x1a => BytecodeInstruction::ILoad {
-                local_variable_index: 0,
-            },
-            0x1b => BytecodeInstruction::ILoad {
-                local_variable_index: 1,
-            },
-            0x1c => BytecodeInstruction::ILoad {
-                local_variable_index: 2,
-            },
-            0x1d => BytecodeInstruction::ILoad {
-                local_variable_index: 3,
-            },
-            0x1e => BytecodeInstruction::LLoad {
-                local_variable_index: 0,
-            },
-            0x1f => BytecodeInstruction::LLoad {
-                local_variable_index: 1,
-            },
-            0x20 => BytecodeInstruction::LLoad {
-                local_variable_index: 2,
-            },
-            0x21 => BytecodeInstruction::LLoad {
-                local_variable_index: 3,
-            },
-            0x22 => BytecodeInstruction::FLoad {
-                local_variable_index: 0,
-            },
-            0x23 => BytecodeInstruction::FLoad {
-                local_variable_index: 1,
-            },
-            0x24 => BytecodeInstruction::FLoad {
-                local_variable_index: 2,
-            },
-            0x25 => BytecodeInstruction::FLoad {
-                local_variable_index: 3,
-            },
-            0x26 => BytecodeInstruction::DLoad {
-                local_variable_index: 0,
-            },
-            0x27 => BytecodeInstruction::DLoad {
-                local_variable_index: 1,
-            },
-            0x28 => BytecodeInstruction::DLoad {
-                local_variable_index: 2,
-            },
-            0x29 => BytecodeInstruction::DLoad {
-                local_variable_index: 3,
-            },
-            0x2a => BytecodeInstruction::ALoad {
-                local_variable_index: 0,
-            },
-            0x2b => BytecodeInstruction::ALoad {
-                local_variable_index: 1,
-            },
-            0x2c => BytecodeInstruction::ALoad {
-                local_variable_index: 2,
-            },
-            0x2d => BytecodeInstruction::ALoad {
-                local_variable_index: 3,
-            },
-            0x2e => BytecodeInstruction::IaLoad {},
-            0x2f => BytecodeInstruction::LaLoad {},
-            0x30 => BytecodeInstruction::FaLoad {},
-            0x31 => BytecodeInstruction::DaLoad {},
-            0x32 => BytecodeInstruction::AaLoad {},
-            0x33 => BytecodeInstruction::BaLoad {},
-            0x34 => BytecodeInstruction::CaLoad {},
-            0x35 => BytecodeInstruction::SaLoad {},
-            0x36 => BytecodeInstruction::IStore {
-                local_variable_index: reader.read_u8().unwrap(),
-            },
-            0x37 => BytecodeInstruction::LStore {
-                local_variable_index: reader.read_u8().unwrap(),
-            },
-            0x38 => BytecodeInstruction::FStore {
-                local_variable_index: reader.read_u8().unwrap(),
-            },
-            0x39 => BytecodeInstruction::DStore {
-                local_variable_index: reader.read_u8().unwrap(),
-            },
-            0x3a => BytecodeInstruction::AStore {
-                local_variable_index: reader.read_u8().unwrap(),
-            },
-            0x3b => BytecodeInstruction::IStore {
-                local_variable_index: 0,
-            },
-            0x3c => BytecodeInstruction::IStore {
-                local_variable_index: 1,
-            },
-            0x3d => BytecodeInstruction::IStore {
-                local_variable_index: 2,
-            },
-            0x3e => BytecodeInstruction::IStore {
-                local_variable_index: 3,
-            },
-            0x3f => BytecodeInstruction::LStore {
-                local_variable_index: 0,
-            },
-            0x40 => BytecodeInstruction::LStore {
-                local_variable_index: 1,
-            },
-            0x41 => BytecodeInstruction::LStore {
-                local_variable_index: 2,
-            },
-            0x42 => BytecodeInstruction::LStore {
-                local_variable_index: 3,
-            },
-            0x4b => BytecodeInstruction::AStore {
-                local_variable_index: 0,
-            },
-            0x4c => BytecodeInstruction::AStore {
-                local_variable_index: 1,
-            },
-            0x4d => BytecodeInstruction::AStore {
-                local_variable_index: 2,
-            },
-            0x4e => BytecodeInstruction::AStore {
-                local_variable_index: 3,
-            },
-            0x4f => BytecodeInstruction::IaStore {},
-            0x50 => BytecodeInstruction::LaStore {},
-            0x51 => BytecodeInstruction::FaStore {},
-            0x52 => BytecodeInstruction::DaStore {},
-            0x53 => BytecodeInstruction::AaStore {},
-            0x54 => BytecodeInstruction::BaStore {},
-            0x55 => BytecodeInstruction::CaStore {},
-            0x56 => BytecodeInstruction::SaStore {},
-            0x57 => BytecodeInstruction::Pop {},
-            0x58 => BytecodeInstruction::Pop2 {},
-            0x59 => BytecodeInstruction::Dup {},
-            0x5c => BytecodeInstruction::Dup2 {},
-            0x60 => BytecodeInstruction::IAdd {},
-            0x61 => BytecodeInstruction::LAdd {},
-            0x62 => BytecodeInstruction::FAdd {},
-            0x63 => BytecodeInstruction::DAdd {},
-            0x64 => BytecodeInstruction::ISub {},
-            0x65 => BytecodeInstruction::LSub {},
-            0x66 => BytecodeInstruction::FSub {},
-            0x67 => BytecodeInstruction::DSub {},
-            0x68 => BytecodeInstruction::IMul {},
-            0x69 => BytecodeInstruction::LMul {},
-            0x6a => BytecodeInstruction::FMul {},
-            0x6b => BytecodeInstruction::DMul {},
-            0x6c => BytecodeInstruction::IDiv {},
-            0x6d => BytecodeInstruction::LDiv {},
-            0x6e => BytecodeInstruction::FDiv {},
-            0x6f => BytecodeInstruction::DDiv {},
-            0x70 => BytecodeInstruction::IRem {},
-            0x71 => BytecodeInstruction::LRem {},
-            0x72 => BytecodeInstruction::FRem {},
-            0x73 => BytecodeInstruction::DRem {},
-            0x74 => BytecodeInstruction::INeg {},
-            0x75 => BytecodeInstruction::LNeg {},
-            0x76 => BytecodeInstruction::FNeg {},
-            0x77 => BytecodeInstruction::DNeg {},
-            0x78 => BytecodeInstruction::IShl {},
-            0x79 => BytecodeInstruction::LShl {},
-            0x7a => BytecodeInstruction::IShr {},
-            0x7b => BytecodeInstruction::LShr {},
-            0x7c => BytecodeInstruction::IUshr {},
-            0x7d => BytecodeInstruction::LUshr {},
-            0x7e => BytecodeInstruction::IAnd {},
-            0x7f => BytecodeInstruction::LAnd {},
-            0x80 => BytecodeInstruction::IOr {},
-            0x81 => BytecodeInstruction::LOr {},
-            0x82 => BytecodeInstruction::IXor {},
-            0x83 => BytecodeInstruction::LXor {},
-            0x84 => BytecodeInstruction::IInc {
-                index: reader.read_u8().unwrap(),
-                constant: reader.read_i8().unwrap(),
-            },
-            0x85 => BytecodeInstruction::I2L {},
-            0x86 => BytecodeInstruction::I2F {},
-            0x87 => BytecodeInstruction::I2D {},
-            0x88 => BytecodeInstruction::L2I {},
-            0x89 => BytecodeInstruction::L2F {},
-            0x8a => BytecodeInstruction::L2D {},
-            0x8b => BytecodeInstruction::F2I {},
-            0x8c => BytecodeInstruction::F2L {},
-            0x8d => BytecodeInstruction::F2D {},
-            0x8e => BytecodeInstruction::D2I {},
-            0x8f => BytecodeInstruction::D2L {},
-            0x90 => BytecodeInstruction::D2F {},
-            0x91 => BytecodeInstruction::I2B {},
-            0x92 => BytecodeInstruction::I2C {},
-            0x93 => BytecodeInstruction::I2S {},
-            0x94 => BytecodeInstruction::LCmp {},
-            0x95 => BytecodeInstruction::FCmpL {},
-            0x96 => BytecodeInstruction::FCmpG {},
-            0x97 => BytecodeInstruction::DCmpL {},
-            0x98 => BytecodeInstruction::DCmpG {},
-            0x99 => BytecodeInstruction::IfEq {
-                offset: reader.read_i16().unwrap(),
-            },
-            0x9a => BytecodeInstruction::IfNe {
-                offset: reader.read_i16().unwrap(),
-            },
-            0x9b => BytecodeInstruction::IfLt {
-                offset: reader.read_i16().unwrap(),
-            },
-            0x9c => BytecodeInstruction::IfGe {
-                offset: reader.read_i16().unwrap(),
-            },
-            0x9d => BytecodeInstruction::IfGt {
-                offset: reader.read_i16().unwrap(),
-            },
-            0x9e => BytecodeInstruction::IfLe {
-                offset: reader.read_i16().unwrap(),
-            },
-            0x9f => BytecodeInstruction::IfIcmpEq {
-                offset: reader.read_i16().unwrap(),
-            },
-            0xa0 => BytecodeInstruction::IfIcmpNe {
-                offset: reader.read_i16().unwrap(),
-            },
-            0xa1 => BytecodeInstruction::IfIcmpLt {
-                offset: reader.read_i16().unwrap(),
-            },
-            0xa2 => BytecodeInstruction::IfIcmpGe {
-                offset: reader.read_i16().unwrap(),
-            },
-            0xa3 => BytecodeInstruction::IfIcmpGt {
-                offset: reader.read_i16().unwrap(),
-            },
-            0xa4 => BytecodeInstruction::IfIcmpLe {
-                offset: reader.read_i16().unwrap(),
-            },
-            0xa5 => BytecodeInstruction::IfAcmpEq {
-                offset: reader.read_i16().unwrap(),
-            },
-            0xa6 => BytecodeInstruction::IfAcmpNe {
-                offset: reader.read_i16().unwrap(),
-            },
-            0xa7 => BytecodeInstruction::GoTo {
-                offset: reader.read_i16().unwrap(),
-            },
-            0xaa => {
-                // skip padding
-                let current_position = reader.position();
-                let next_multiple_of_4 = current_position.div_ceil(4) * 4;
-                let num_padding_bytes: u8 = (next_multiple_of_4 - current_position) as u8;
-                for _ in 0..num_padding_bytes {
-                    let pad = reader.read_u8().unwrap();
-                    assert!(pad == 0x00);
-                }
-                let default: i32 = reader.read_i32().unwrap();
-                let low: i32 = reader.read_i32().unwrap();
-                let high: i32 = reader.read_i32().unwrap();
-                assert!(low <= high);
-                let offsets: Vec<i32> = reader
-                    .read_i32_vec((high - low + 1).try_into().unwrap())
-                    .unwrap();
-                BytecodeInstruction::TableSwitch {
-                    num_padding_bytes,
-                    default,
-                    low,
-                    offsets,
-                }
+        instructions.push((
+            position,
+            parse_instruction(reader, cp, tmp.unwrap()).unwrap(),
+        ));
+    }
+    instructions
+}
+
+/**
+ * Parses instructions like `parse_bytecode`, returning separately the bytes which cannot be decoded (an unknown opcode
+ * or an instruction cut short by the end of the code) after an instruction which does not continue to the next one,
+ * like a return or a goto, when neither the instructions parsed so far nor the given entry points (like exception
+ * handlers) jump there or past it. Obfuscators may append such bytes, which need not be valid instructions, after the
+ * last one. Unknown opcodes anywhere else are kept as `Unknown` instructions.
+ */
+pub fn parse_bytecode_with_trailing_bytes(
+    reader: &mut BinaryReader,
+    cp: &ConstantPool,
+    entry_points: &[u32],
+) -> (Vec<(u32, BytecodeInstruction)>, Vec<u8>) {
+    let mut instructions: Vec<(u32, BytecodeInstruction)> = Vec::new();
+    let mut furthest_target: Option<u32> = entry_points.iter().copied().max();
+    while reader.position() < reader.len() {
+        let position: u32 = reader.position().try_into().unwrap();
+        let is_reachable: bool = instructions
+            .last()
+            .is_none_or(|(_, previous)| falls_through(previous))
+            || furthest_target.is_some_and(|target| target >= position);
+        let opcode: u8 = reader.read_u8().unwrap();
+        let instruction: BytecodeInstruction = match parse_instruction(reader, cp, opcode) {
+            Ok(BytecodeInstruction::Unknown { .. }) if !is_reachable => {
+                reader.seek(position as usize).unwrap();
+                break;
             }
-            0xab => {
-                // skip padding
-                let current_position = reader.position();
-                let next_multiple_of_4 = current_position.div_ceil(4) * 4;
-                let num_padding_bytes: u8 = (next_multiple_of_4 - current_position) as u8;
-                for _ in 0..num_padding_bytes {
-                    let pad = reader.read_u8().unwrap();
-                    assert!(pad == 0x00);
-                }
-                let default: i32 = reader.read_i32().unwrap();
-                let npairs: i32 = reader.read_i32().unwrap();
-                assert!(npairs >= 0);
-                let mut pairs: Vec<LookupSwitchPair> =
-                    Vec::with_capacity(npairs.try_into().unwrap());
-                for _ in 0..npairs {
-                    let match_value: i32 = reader.read_i32().unwrap();
-                    let offset: i32 = reader.read_i32().unwrap();
-                    pairs.push(LookupSwitchPair {
-                        match_value,
-                        offset,
-                    });
-                }
-                BytecodeInstruction::LookupSwitch {
-                    num_padding_bytes,
-                    default,
-                    pairs,
-                }
+            Ok(instruction) => instruction,
+            // there is nothing else to do with a truncated instruction
+            Err(_) => {
+                reader.seek(position as usize).unwrap();
+                break;
             }
-            0xac => BytecodeInstruction::IReturn {},
-            0xad => BytecodeInstruction::LReturn {},
-            0xae => BytecodeInstruction::FReturn {},
-            0xaf => BytecodeInstruction::DReturn {},
-            0xb0 => BytecodeInstruction::AReturn {},
-            0xb1 => BytecodeInstruction::Return {},
-            0xb2 => {
-                let field_ref_index: u16 = reader.read_u16().unwrap();
-                assert_valid_and_type!(cp, field_ref_index, ConstantPoolTag::Fieldref);
-                BytecodeInstruction::GetStatic { field_ref_index }
+        };
+        furthest_target = branch_targets(position, &instruction)
+            .into_iter()
+            .chain(furthest_target)
+            .max();
+        instructions.push((position, instruction));
+    }
+    let trailing_bytes: Vec<u8> = reader
+        .read_u8_vec(reader.len() - reader.position())
+        .unwrap();
+    (instructions, trailing_bytes)
+}
+
+/// Returns false if the given instruction never continues to the following one.
+fn falls_through(instruction: &BytecodeInstruction) -> bool {
+    !matches!(
+        instruction,
+        BytecodeInstruction::Return {}
+            | BytecodeInstruction::IReturn {}
+            | BytecodeInstruction::LReturn {}
+            | BytecodeInstruction::FReturn {}
+            | BytecodeInstruction::DReturn {}
+            | BytecodeInstruction::AReturn {}
+            | BytecodeInstruction::AThrow {}
+            | BytecodeInstruction::Ret { .. }
+            | BytecodeInstruction::GoTo { .. }
+            | BytecodeInstruction::GoToW { .. }
+            | BytecodeInstruction::TableSwitch { .. }
+            | BytecodeInstruction::LookupSwitch { .. }
+    )
+}
+
+fn parse_instruction(
+    reader: &mut BinaryReader,
+    cp: &ConstantPool,
+    opcode: u8,
+) -> Result<BytecodeInstruction> {
+    Ok(match opcode {
+        0x00 => BytecodeInstruction::Nop {},
+        0x01 => BytecodeInstruction::AConstNull {},
+        0x02 => BytecodeInstruction::IConst { constant: -1 },
+        0x03 => BytecodeInstruction::IConst { constant: 0 },
+        0x04 => BytecodeInstruction::IConst { constant: 1 },
+        0x05 => BytecodeInstruction::IConst { constant: 2 },
+        0x06 => BytecodeInstruction::IConst { constant: 3 },
+        0x07 => BytecodeInstruction::IConst { constant: 4 },
+        0x08 => BytecodeInstruction::IConst { constant: 5 },
+        0x09 => BytecodeInstruction::LConst { constant: 0 },
+        0x0a => BytecodeInstruction::LConst { constant: 1 },
+        0x0b => BytecodeInstruction::FConst { constant: 0.0f32 },
+        0x0c => BytecodeInstruction::FConst { constant: 1.0f32 },
+        0x0d => BytecodeInstruction::FConst { constant: 2.0f32 },
+        0x0e => BytecodeInstruction::DConst { constant: 0.0 },
+        0x0f => BytecodeInstruction::DConst { constant: 1.0 },
+        0x10 => BytecodeInstruction::BiPush {
+            immediate: reader.read_i8()?,
+        },
+        0x11 => BytecodeInstruction::SiPush {
+            immediate: reader.read_i16()?,
+        },
+        0x12 => BytecodeInstruction::Ldc {
+            constant_pool_index: reader.read_u8()?,
+        },
+        0x13 => BytecodeInstruction::LdcW {
+            constant_pool_index: reader.read_u16()?,
+        },
+        0x14 => BytecodeInstruction::Ldc2W {
+            constant_pool_index: reader.read_u16()?,
+        },
+        0x15 => BytecodeInstruction::ILoad {
+            local_variable_index: u16::from(reader.read_u8()?),
+        },
+        0x16 => BytecodeInstruction::LLoad {
+            local_variable_index: u16::from(reader.read_u8()?),
+        },
+        0x17 => BytecodeInstruction::FLoad {
+            local_variable_index: u16::from(reader.read_u8()?),
+        },
+        0x18 => BytecodeInstruction::DLoad {
+            local_variable_index: u16::from(reader.read_u8()?),
+        },
+        0x19 => BytecodeInstruction::ALoad {
+            local_variable_index: u16::from(reader.read_u8()?),
+        },
+        0x1a => BytecodeInstruction::ILoad {
+            local_variable_index: 0,
+        },
+        0x1b => BytecodeInstruction::ILoad {
+            local_variable_index: 1,
+        },
+        0x1c => BytecodeInstruction::ILoad {
+            local_variable_index: 2,
+        },
+        0x1d => BytecodeInstruction::ILoad {
+            local_variable_index: 3,
+        },
+        0x1e => BytecodeInstruction::LLoad {
+            local_variable_index: 0,
+        },
+        0x1f => BytecodeInstruction::LLoad {
+            local_variable_index: 1,
+        },
+        0x20 => BytecodeInstruction::LLoad {
+            local_variable_index: 2,
+        },
+        0x21 => BytecodeInstruction::LLoad {
+            local_variable_index: 3,
+        },
+        0x22 => BytecodeInstruction::FLoad {
+            local_variable_index: 0,
+        },
+        0x23 => BytecodeInstruction::FLoad {
+            local_variable_index: 1,
+        },
+        0x24 => BytecodeInstruction::FLoad {
+            local_variable_index: 2,
+        },
+        0x25 => BytecodeInstruction::FLoad {
+            local_variable_index: 3,
+        },
+        0x26 => BytecodeInstruction::DLoad {
+            local_variable_index: 0,
+        },
+        0x27 => BytecodeInstruction::DLoad {
+            local_variable_index: 1,
+        },
+        0x28 => BytecodeInstruction::DLoad {
+            local_variable_index: 2,
+        },
+        0x29 => BytecodeInstruction::DLoad {
+            local_variable_index: 3,
+        },
+        0x2a => BytecodeInstruction::ALoad {
+            local_variable_index: 0,
+        },
+        0x2b => BytecodeInstruction::ALoad {
+            local_variable_index: 1,
+        },
+        0x2c => BytecodeInstruction::ALoad {
+            local_variable_index: 2,
+        },
+        0x2d => BytecodeInstruction::ALoad {
+            local_variable_index: 3,
+        },
+        0x2e => BytecodeInstruction::IaLoad {},
+        0x2f => BytecodeInstruction::LaLoad {},
+        0x30 => BytecodeInstruction::FaLoad {},
+        0x31 => BytecodeInstruction::DaLoad {},
+        0x32 => BytecodeInstruction::AaLoad {},
+        0x33 => BytecodeInstruction::BaLoad {},
+        0x34 => BytecodeInstruction::CaLoad {},
+        0x35 => BytecodeInstruction::SaLoad {},
+        0x36 => BytecodeInstruction::IStore {
+            local_variable_index: u16::from(reader.read_u8()?),
+        },
+        0x37 => BytecodeInstruction::LStore {
+            local_variable_index: u16::from(reader.read_u8()?),
+        },
+        0x38 => BytecodeInstruction::FStore {
+            local_variable_index: u16::from(reader.read_u8()?),
+        },
+        0x39 => BytecodeInstruction::DStore {
+            local_variable_index: u16::from(reader.read_u8()?),
+        },
+        0x3a => BytecodeInstruction::AStore {
+            local_variable_index: u16::from(reader.read_u8()?),
+        },
+        0x3b => BytecodeInstruction::IStore {
+            local_variable_index: 0,
+        },
+        0x3c => BytecodeInstruction::IStore {
+            local_variable_index: 1,
+        },
+        0x3d => BytecodeInstruction::IStore {
+            local_variable_index: 2,
+        },
+        0x3e => BytecodeInstruction::IStore {
+            local_variable_index: 3,
+        },
+        0x3f => BytecodeInstruction::LStore {
+            local_variable_index: 0,
+        },
+        0x40 => BytecodeInstruction::LStore {
+            local_variable_index: 1,
+        },
+        0x41 => BytecodeInstruction::LStore {
+            local_variable_index: 2,
+        },
+        0x42 => BytecodeInstruction::LStore {
+            local_variable_index: 3,
+        },
+        0x43 => BytecodeInstruction::FStore {
+            local_variable_index: 0,
+        },
+        0x44 => BytecodeInstruction::FStore {
+            local_variable_index: 1,
+        },
+        0x45 => BytecodeInstruction::FStore {
+            local_variable_index: 2,
+        },
+        0x46 => BytecodeInstruction::FStore {
+            local_variable_index: 3,
+        },
+        0x47 => BytecodeInstruction::DStore {
+            local_variable_index: 0,
+        },
+        0x48 => BytecodeInstruction::DStore {
+            local_variable_index: 1,
+        },
+        0x49 => BytecodeInstruction::DStore {
+            local_variable_index: 2,
+        },
+        0x4a => BytecodeInstruction::DStore {
+            local_variable_index: 3,
+        },
+        0x4b => BytecodeInstruction::AStore {
+            local_variable_index: 0,
+        },
+        0x4c => BytecodeInstruction::AStore {
+            local_variable_index: 1,
+        },
+        0x4d => BytecodeInstruction::AStore {
+            local_variable_index: 2,
+        },
+        0x4e => BytecodeInstruction::AStore {
+            local_variable_index: 3,
+        },
+        0x4f => BytecodeInstruction::IaStore {},
+        0x50 => BytecodeInstruction::LaStore {},
+        0x51 => BytecodeInstruction::FaStore {},
+        0x52 => BytecodeInstruction::DaStore {},
+        0x53 => BytecodeInstruction::AaStore {},
+        0x54 => BytecodeInstruction::BaStore {},
+        0x55 => BytecodeInstruction::CaStore {},
+        0x56 => BytecodeInstruction::SaStore {},
+        0x57 => BytecodeInstruction::Pop {},
+        0x58 => BytecodeInstruction::Pop2 {},
+        0x59 => BytecodeInstruction::Dup {},
+        0x5a => BytecodeInstruction::DupX1 {},
+        0x5b => BytecodeInstruction::DupX2 {},
+        0x5c => BytecodeInstruction::Dup2 {},
+        0x5d => BytecodeInstruction::Dup2X1 {},
+        0x5e => BytecodeInstruction::Dup2X2 {},
+        0x5f => BytecodeInstruction::Swap {},
+        0x60 => BytecodeInstruction::IAdd {},
+        0x61 => BytecodeInstruction::LAdd {},
+        0x62 => BytecodeInstruction::FAdd {},
+        0x63 => BytecodeInstruction::DAdd {},
+        0x64 => BytecodeInstruction::ISub {},
+        0x65 => BytecodeInstruction::LSub {},
+        0x66 => BytecodeInstruction::FSub {},
+        0x67 => BytecodeInstruction::DSub {},
+        0x68 => BytecodeInstruction::IMul {},
+        0x69 => BytecodeInstruction::LMul {},
+        0x6a => BytecodeInstruction::FMul {},
+        0x6b => BytecodeInstruction::DMul {},
+        0x6c => BytecodeInstruction::IDiv {},
+        0x6d => BytecodeInstruction::LDiv {},
+        0x6e => BytecodeInstruction::FDiv {},
+        0x6f => BytecodeInstruction::DDiv {},
+        0x70 => BytecodeInstruction::IRem {},
+        0x71 => BytecodeInstruction::LRem {},
+        0x72 => BytecodeInstruction::FRem {},
+        0x73 => BytecodeInstruction::DRem {},
+        0x74 => BytecodeInstruction::INeg {},
+        0x75 => BytecodeInstruction::LNeg {},
+        0x76 => BytecodeInstruction::FNeg {},
+        0x77 => BytecodeInstruction::DNeg {},
+        0x78 => BytecodeInstruction::IShl {},
+        0x79 => BytecodeInstruction::LShl {},
+        0x7a => BytecodeInstruction::IShr {},
+        0x7b => BytecodeInstruction::LShr {},
+        0x7c => BytecodeInstruction::IUshr {},
+        0x7d => BytecodeInstruction::LUshr {},
+        0x7e => BytecodeInstruction::IAnd {},
+        0x7f => BytecodeInstruction::LAnd {},
+        0x80 => BytecodeInstruction::IOr {},
+        0x81 => BytecodeInstruction::LOr {},
+        0x82 => BytecodeInstruction::IXor {},
+        0x83 => BytecodeInstruction::LXor {},
+        0x84 => BytecodeInstruction::IInc {
+            index: u16::from(reader.read_u8()?),
+            constant: i16::from(reader.read_i8()?),
+        },
+        0x85 => BytecodeInstruction::I2L {},
+        0x86 => BytecodeInstruction::I2F {},
+        0x87 => BytecodeInstruction::I2D {},
+        0x88 => BytecodeInstruction::L2I {},
+        0x89 => BytecodeInstruction::L2F {},
+        0x8a => BytecodeInstruction::L2D {},
+        0x8b => BytecodeInstruction::F2I {},
+        0x8c => BytecodeInstruction::F2L {},
+        0x8d => BytecodeInstruction::F2D {},
+        0x8e => BytecodeInstruction::D2I {},
+        0x8f => BytecodeInstruction::D2L {},
+        0x90 => BytecodeInstruction::D2F {},
+        0x91 => BytecodeInstruction::I2B {},
+        0x92 => BytecodeInstruction::I2C {},
+        0x93 => BytecodeInstruction::I2S {},
+        0x94 => BytecodeInstruction::LCmp {},
+        0x95 => BytecodeInstruction::FCmpL {},
+        0x96 => BytecodeInstruction::FCmpG {},
+        0x97 => BytecodeInstruction::DCmpL {},
+        0x98 => BytecodeInstruction::DCmpG {},
+        0x99 => BytecodeInstruction::IfEq {
+            offset: reader.read_i16()?,
+        },
+        0x9a => BytecodeInstruction::IfNe {
+            offset: reader.read_i16()?,
+        },
+        0x9b => BytecodeInstruction::IfLt {
+            offset: reader.read_i16()?,
+        },
+        0x9c => BytecodeInstruction::IfGe {
+            offset: reader.read_i16()?,
+        },
+        0x9d => BytecodeInstruction::IfGt {
+            offset: reader.read_i16()?,
+        },
+        0x9e => BytecodeInstruction::IfLe {
+            offset: reader.read_i16()?,
+        },
+        0x9f => BytecodeInstruction::IfIcmpEq {
+            offset: reader.read_i16()?,
+        },
+        0xa0 => BytecodeInstruction::IfIcmpNe {
+            offset: reader.read_i16()?,
+        },
+        0xa1 => BytecodeInstruction::IfIcmpLt {
+            offset: reader.read_i16()?,
+        },
+        0xa2 => BytecodeInstruction::IfIcmpGe {
+            offset: reader.read_i16()?,
+        },
+        0xa3 => BytecodeInstruction::IfIcmpGt {
+            offset: reader.read_i16()?,
+        },
+        0xa4 => BytecodeInstruction::IfIcmpLe {
+            offset: reader.read_i16()?,
+        },
+        0xa5 => BytecodeInstruction::IfAcmpEq {
+            offset: reader.read_i16()?,
+        },
+        0xa6 => BytecodeInstruction::IfAcmpNe {
+            offset: reader.read_i16()?,
+        },
+        0xa7 => BytecodeInstruction::GoTo {
+            offset: reader.read_i16()?,
+        },
+        0xa8 => BytecodeInstruction::Jsr {
+            offset: reader.read_i16()?,
+        },
+        0xa9 => BytecodeInstruction::Ret {
+            local_variable_index: u16::from(reader.read_u8()?),
+        },
+        0xaa => {
+            // skip padding
+            let current_position = reader.position();
+            let next_multiple_of_4 = current_position.div_ceil(4) * 4;
+            let num_padding_bytes: u8 = (next_multiple_of_4 - current_position) as u8;
+            for _ in 0..num_padding_bytes {
+                let pad = reader.read_u8()?;
+                assert!(pad == 0x00);
             }
-            0xb3 => BytecodeInstruction::PutStatic {
-                field_ref_index: reader.read_u16().unwrap(),
-            },
-            0xb4 => BytecodeInstruction::GetField {
-                field_ref_index: reader.read_u16().unwrap(),
-            },
-            0xb5 => BytecodeInstruction::PutField {
-                field_ref_index: reader.read_u16().unwrap(),
-            },
-            0xb6 => BytecodeInstruction::InvokeVirtual {
-                method_ref_index: reader.read_u16().unwrap(),
-            },
-            0xb7 => BytecodeInstruction::InvokeSpecial {
-                method_ref_index: reader.read_u16().unwrap(),
-            },
-            0xb8 => BytecodeInstruction::InvokeStatic {
-                method_ref_index: reader.read_u16().unwrap(),
-            },
-            0xb9 => {
-                let constant_pool_index: u16 = reader.read_u16().unwrap();
-                let count: u8 = reader.read_u8().unwrap();
-                // skip one zero byte
-                _ = reader.read_u8().unwrap();
-                BytecodeInstruction::InvokeInterface {
-                    constant_pool_index,
-                    count,
-                }
+            let default: i32 = reader.read_i32()?;
+            let low: i32 = reader.read_i32()?;
+            let high: i32 = reader.read_i32()?;
+            assert!(low <= high);
+            let offsets: Vec<i32> = reader.read_i32_vec((high - low + 1).try_into().unwrap())?;
+            BytecodeInstruction::TableSwitch {
+                num_padding_bytes,
+                default,
+                low,
+                offsets,
             }
-            0xba => {
-                let constant_pool_index: u16 = reader.read_u16().unwrap();
-                // skip two zero bytes
-                _ = reader.read_u8();
-                _ = reader.read_u8();
-                BytecodeInstruction::InvokeDynamic {
-                    constant_pool_index,
-                }
+        }
+        0xab => {
+            // skip padding
+            let current_position = reader.position();
+            let next_multiple_of_4 = current_position.div_ceil(4) * 4;
+            let num_padding_bytes: u8 = (next_multiple_of_4 - current_position) as u8;
+            for _ in 0..num_padding_bytes {
+                let pad = reader.read_u8()?;
+                assert!(pad == 0x00);
             }
-            0xbb => BytecodeInstruction::New {
-                constant_pool_index: reader.read_u16().unwrap(),
-            },
-            0xbc => BytecodeInstruction::NewArray {
-                atype: ArrayType::from(reader.read_u8().unwrap()),
-            },
-            0xbd => BytecodeInstruction::ANewArray {
-                constant_pool_index: reader.read_u16().unwrap(),
-            },
-            0xbe => BytecodeInstruction::ArrayLength {},
-            0xbf => BytecodeInstruction::AThrow {},
-            0xc0 => BytecodeInstruction::CheckCast {
-                constant_pool_index: reader.read_u16().unwrap(),
-            },
-            0xc1 => BytecodeInstruction::Instanceof {
-                constant_pool_index: reader.read_u16().unwrap(),
-            },
-            0xc6 => BytecodeInstruction::IfNull {
-                offset: reader.read_i16().unwrap(),
-            },
-            0xc7 => BytecodeInstruction::IfNonNull {
-                offset: reader.read_i16().unwrap(),
-            },
-            _ => panic!("Unknown bytecode instruction 0x{opcode:02x}"),
-        };
-        instructions.push((position, instruction));
+            let default: i32 = reader.read_i32()?;
+            let npairs: i32 = reader.read_i32()?;
+            assert!(npairs >= 0);
+            let mut pairs: Vec<LookupSwitchPair> = Vec::with_capacity(npairs.try_into().unwrap());
+            for _ in 0..npairs {
+                let match_value: i32 = reader.read_i32()?;
+                let offset: i32 = reader.read_i32()?;
+                pairs.push(LookupSwitchPair {
+                    match_value,
+                    offset,
+                });
+            }
+            BytecodeInstruction::LookupSwitch {
+                num_padding_bytes,
+                default,
+                pairs,
+            }
+        }
+        0xac => BytecodeInstruction::IReturn {},
+        0xad => BytecodeInstruction::LReturn {},
+        0xae => BytecodeInstruction::FReturn {},
+        0xaf => BytecodeInstruction::DReturn {},
+        0xb0 => BytecodeInstruction::AReturn {},
+        0xb1 => BytecodeInstruction::Return {},
+        0xb2 => {
+            let field_ref_index: u16 = reader.read_u16()?;
+            assert_valid_and_type!(cp, field_ref_index, ConstantPoolTag::Fieldref);
+            BytecodeInstruction::GetStatic { field_ref_index }
+        }
+        0xb3 => BytecodeInstruction::PutStatic {
+            field_ref_index: reader.read_u16()?,
+        },
+        0xb4 => BytecodeInstruction::GetField {
+            field_ref_index: reader.read_u16()?,
+        },
+        0xb5 => BytecodeInstruction::PutField {
+            field_ref_index: reader.read_u16()?,
+        },
+        0xb6 => BytecodeInstruction::InvokeVirtual {
+            method_ref_index: reader.read_u16()?,
+        },
+        0xb7 => BytecodeInstruction::InvokeSpecial {
+            method_ref_index: reader.read_u16()?,
+        },
+        0xb8 => BytecodeInstruction::InvokeStatic {
+            method_ref_index: reader.read_u16()?,
+        },
+        0xb9 => {
+            let constant_pool_index: u16 = reader.read_u16()?;
+            let count: u8 = reader.read_u8()?;
+            // skip one zero byte
+            reader.skip(1)?;
+            BytecodeInstruction::InvokeInterface {
+                constant_pool_index,
+                count,
+            }
+        }
+        0xba => {
+            let constant_pool_index: u16 = reader.read_u16()?;
+            // skip two zero bytes
+            reader.skip(2)?;
+            BytecodeInstruction::InvokeDynamic {
+                constant_pool_index,
+            }
+        }
+        0xbb => BytecodeInstruction::New {
+            constant_pool_index: reader.read_u16()?,
+        },
+        0xbc => BytecodeInstruction::NewArray {
+            atype: ArrayType::from(reader.read_u8()?),
+        },
+        0xbd => BytecodeInstruction::ANewArray {
+            constant_pool_index: reader.read_u16()?,
+        },
+        0xbe => BytecodeInstruction::ArrayLength {},
+        0xbf => BytecodeInstruction::AThrow {},
+        0xc0 => BytecodeInstruction::CheckCast {
+            constant_pool_index: reader.read_u16()?,
+        },
+        0xc1 => BytecodeInstruction::Instanceof {
+            constant_pool_index: reader.read_u16()?,
+        },
+        0xc2 => BytecodeInstruction::MonitorEnter {},
+        0xc3 => BytecodeInstruction::MonitorExit {},
+        0xc4 => {
+            // the modified instruction gets a 16-bit index (and, for iinc, a 16-bit constant)
+            let modified_opcode: u8 = reader.read_u8()?;
+            let local_variable_index: u16 = reader.read_u16()?;
+            match modified_opcode {
+                0x15 => BytecodeInstruction::ILoad {
+                    local_variable_index,
+                },
+                0x16 => BytecodeInstruction::LLoad {
+                    local_variable_index,
+                },
+                0x17 => BytecodeInstruction::FLoad {
+                    local_variable_index,
+                },
+                0x18 => BytecodeInstruction::DLoad {
+                    local_variable_index,
+                },
+                0x19 => BytecodeInstruction::ALoad {
+                    local_variable_index,
+                },
+                0x36 => BytecodeInstruction::IStore {
+                    local_variable_index,
+                },
+                0x37 => BytecodeInstruction::LStore {
+                    local_variable_index,
+                },
+                0x38 => BytecodeInstruction::FStore {
+                    local_variable_index,
+                },
+                0x39 => BytecodeInstruction::DStore {
+                    local_variable_index,
+                },
+                0x3a => BytecodeInstruction::AStore {
+                    local_variable_index,
+                },
+                0x84 => BytecodeInstruction::IInc {
+                    index: local_variable_index,
+                    constant: reader.read_i16()?,
+                },
+                0xa9 => BytecodeInstruction::Ret {
+                    local_variable_index,
+                },
+                _ => panic!("Invalid bytecode instruction 0x{modified_opcode:02x} after wide"),
+            }
+        }
+        0xc5 => BytecodeInstruction::MultiANewArray {
+            constant_pool_index: reader.read_u16()?,
+            dimensions: reader.read_u8()?,
+        },
+        0xc6 => BytecodeInstruction::IfNull {
+            offset: reader.read_i16()?,
+        },
+        0xc7 => BytecodeInstruction::IfNonNull {
+            offset: reader.read_i16()?,
+        },
+        0xc8 => BytecodeInstruction::GoToW {
+            offset: reader.read_i32()?,
+        },
+        0xc9 => BytecodeInstruction::JsrW {
+            offset: reader.read_i32()?,
+        },
+        _ => BytecodeInstruction::Unknown { opcode },
+    })
+}
+
+/**
+ * Encodes the given instructions back into bytecode, the inverse of `parse_bytecode`. Each instruction must start at
+ * its recorded position, and the padding of switches must align their operands to four bytes.
+ */
+pub fn encode_bytecode(code: &[(u32, BytecodeInstruction)]) -> Vec<u8> {
+    let mut w: BinaryWriter = BinaryWriter::new(binary_writer::Endianness::Big);
+    let mut position: u32 = 0;
+    for (expected_position, instruction) in code.iter() {
+        assert!(
+            position == *expected_position,
+            "Expected {} at position {expected_position} but it was at {position}.",
+            instruction.mnemonic()
+        );
+        if let BytecodeInstruction::TableSwitch {
+            num_padding_bytes, ..
+        }
+        | BytecodeInstruction::LookupSwitch {
+            num_padding_bytes, ..
+        } = instruction
+        {
+            assert!(
+                (position + 1 + u32::from(*num_padding_bytes)).is_multiple_of(4),
+                "The {} at position {position} has {num_padding_bytes} padding bytes, which do not align its operands.",
+                instruction.mnemonic()
+            );
+        }
+        write_instruction(&mut w, instruction);
+        position += get_instruction_length(instruction);
     }
-    instructions
+    w.array()
+}
+
+/**
+ * Checks whether each instruction starts right where `encode_bytecode` would put it. That is not the case after
+ * parsing code which uses a longer encoding than needed, like a `wide` prefix for a small index or `iload` with an
+ * index which has its own opcode, since such instructions are written back in their shortest form.
+ */
+pub fn has_shortest_layout(code: &[(u32, BytecodeInstruction)]) -> bool {
+    let mut position: u32 = 0;
+    code.iter().all(|(expected_position, instruction)| {
+        let matches: bool = position == *expected_position;
+        position += get_instruction_length(instruction);
+        matches
+    })
 }
 
 pub fn write_instruction(w: &mut BinaryWriter, instruction: &BytecodeInstruction) {
     match instruction {
+        BytecodeInstruction::Nop {} => w.write_u8(0x00),
         BytecodeInstruction::Dup {} => w.write_u8(0x59),
+        BytecodeInstruction::DupX1 {} => w.write_u8(0x5a),
+        BytecodeInstruction::DupX2 {} => w.write_u8(0x5b),
         BytecodeInstruction::Dup2 {} => w.write_u8(0x5c),
+        BytecodeInstruction::Dup2X1 {} => w.write_u8(0x5d),
+        BytecodeInstruction::Dup2X2 {} => w.write_u8(0x5e),
+        BytecodeInstruction::Swap {} => w.write_u8(0x5f),
         BytecodeInstruction::AConstNull {} => w.write_u8(0x01),
         BytecodeInstruction::IConst { constant } => match constant {
             -1 => w.write_u8(0x02),
@@ -803,9 +1373,14 @@ pub fn write_instruction(w: &mut BinaryWriter, instruction: &BytecodeInstruction
             1 => w.write_u8(0x2b),
             2 => w.write_u8(0x2c),
             3 => w.write_u8(0x2d),
+            4..=255 => {
+                w.write_u8(0x19);
+                w.write_u8(*local_variable_index as u8);
+            }
             _ => {
+                w.write_u8(0xc4);
                 w.write_u8(0x19);
-                w.write_u8(*local_variable_index);
+                w.write_u16(*local_variable_index);
             }
         },
         BytecodeInstruction::AStore {
@@ -815,9 +1390,14 @@ pub fn write_instruction(w: &mut BinaryWriter, instruction: &BytecodeInstruction
             1 => w.write_u8(0x4c),
             2 => w.write_u8(0x4d),
             3 => w.write_u8(0x4e),
+            4..=255 => {
+                w.write_u8(0x3a);
+                w.write_u8(*local_variable_index as u8);
+            }
             _ => {
+                w.write_u8(0xc4);
                 w.write_u8(0x3a);
-                w.write_u8(*local_variable_index);
+                w.write_u16(*local_variable_index);
             }
         },
         BytecodeInstruction::ILoad {
@@ -827,9 +1407,14 @@ pub fn write_instruction(w: &mut BinaryWriter, instruction: &BytecodeInstruction
             1 => w.write_u8(0x1b),
             2 => w.write_u8(0x1c),
             3 => w.write_u8(0x1d),
+            4..=255 => {
+                w.write_u8(0x15);
+                w.write_u8(*local_variable_index as u8);
+            }
             _ => {
+                w.write_u8(0xc4);
                 w.write_u8(0x15);
-                w.write_u8(*local_variable_index);
+                w.write_u16(*local_variable_index);
             }
         },
         BytecodeInstruction::IStore {
@@ -839,9 +1424,14 @@ pub fn write_instruction(w: &mut BinaryWriter, instruction: &BytecodeInstruction
             1 => w.write_u8(0x3c),
             2 => w.write_u8(0x3d),
             3 => w.write_u8(0x3e),
+            4..=255 => {
+                w.write_u8(0x36);
+                w.write_u8(*local_variable_index as u8);
+            }
             _ => {
+                w.write_u8(0xc4);
                 w.write_u8(0x36);
-                w.write_u8(*local_variable_index);
+                w.write_u16(*local_variable_index);
             }
         },
         BytecodeInstruction::LLoad {
@@ -851,9 +1441,14 @@ pub fn write_instruction(w: &mut BinaryWriter, instruction: &BytecodeInstruction
             1 => w.write_u8(0x1f),
             2 => w.write_u8(0x20),
             3 => w.write_u8(0x21),
+            4..=255 => {
+                w.write_u8(0x16);
+                w.write_u8(*local_variable_index as u8);
+            }
             _ => {
+                w.write_u8(0xc4);
                 w.write_u8(0x16);
-                w.write_u8(*local_variable_index);
+                w.write_u16(*local_variable_index);
             }
         },
         BytecodeInstruction::LStore {
@@ -863,9 +1458,14 @@ pub fn write_instruction(w: &mut BinaryWriter, instruction: &BytecodeInstruction
             1 => w.write_u8(0x40),
             2 => w.write_u8(0x41),
             3 => w.write_u8(0x42),
+            4..=255 => {
+                w.write_u8(0x37);
+                w.write_u8(*local_variable_index as u8);
+            }
             _ => {
+                w.write_u8(0xc4);
                 w.write_u8(0x37);
-                w.write_u8(*local_variable_index);
+                w.write_u16(*local_variable_index);
             }
         },
         BytecodeInstruction::FLoad {
@@ -875,9 +1475,14 @@ pub fn write_instruction(w: &mut BinaryWriter, instruction: &BytecodeInstruction
             1 => w.write_u8(0x23),
             2 => w.write_u8(0x24),
             3 => w.write_u8(0x25),
+            4..=255 => {
+                w.write_u8(0x17);
+                w.write_u8(*local_variable_index as u8);
+            }
             _ => {
+                w.write_u8(0xc4);
                 w.write_u8(0x17);
-                w.write_u8(*local_variable_index);
+                w.write_u16(*local_variable_index);
             }
         },
         BytecodeInstruction::FStore {
@@ -887,9 +1492,14 @@ pub fn write_instruction(w: &mut BinaryWriter, instruction: &BytecodeInstruction
             1 => w.write_u8(0x44),
             2 => w.write_u8(0x45),
             3 => w.write_u8(0x46),
+            4..=255 => {
+                w.write_u8(0x38);
+                w.write_u8(*local_variable_index as u8);
+            }
             _ => {
+                w.write_u8(0xc4);
                 w.write_u8(0x38);
-                w.write_u8(*local_variable_index);
+                w.write_u16(*local_variable_index);
             }
         },
         BytecodeInstruction::DLoad {
@@ -899,17 +1509,33 @@ pub fn write_instruction(w: &mut BinaryWriter, instruction: &BytecodeInstruction
             1 => w.write_u8(0x27),
             2 => w.write_u8(0x28),
             3 => w.write_u8(0x29),
+            4..=255 => {
+                w.write_u8(0x18);
+                w.write_u8(*local_variable_index as u8);
+            }
             _ => {
+                w.write_u8(0xc4);
                 w.write_u8(0x18);
-                w.write_u8(*local_variable_index);
+                w.write_u16(*local_variable_index);
             }
         },
         BytecodeInstruction::DStore {
             local_variable_index,
-        } => {
-            w.write_u8(0x39);
-            w.write_u8(*local_variable_index);
-        }
+        } => match local_variable_index {
+            0 => w.write_u8(0x47),
+            1 => w.write_u8(0x48),
+            2 => w.write_u8(0x49),
+            3 => w.write_u8(0x4a),
+            4..=255 => {
+                w.write_u8(0x39);
+                w.write_u8(*local_variable_index as u8);
+            }
+            _ => {
+                w.write_u8(0xc4);
+                w.write_u8(0x39);
+                w.write_u16(*local_variable_index);
+            }
+        },
         BytecodeInstruction::IaLoad {} => w.write_u8(0x2e),
         BytecodeInstruction::LaLoad {} => w.write_u8(0x2f),
         BytecodeInstruction::FaLoad {} => w.write_u8(0x30),
@@ -936,7 +1562,17 @@ pub fn write_instruction(w: &mut BinaryWriter, instruction: &BytecodeInstruction
             w.write_u8(0xbd);
             w.write_u16(*constant_pool_index);
         }
+        BytecodeInstruction::MultiANewArray {
+            constant_pool_index,
+            dimensions,
+        } => {
+            w.write_u8(0xc5);
+            w.write_u16(*constant_pool_index);
+            w.write_u8(*dimensions);
+        }
         BytecodeInstruction::AThrow {} => w.write_u8(0xbf),
+        BytecodeInstruction::MonitorEnter {} => w.write_u8(0xc2),
+        BytecodeInstruction::MonitorExit {} => w.write_u8(0xc3),
         BytecodeInstruction::New {
             constant_pool_index,
         } => {
@@ -1079,6 +1715,31 @@ pub fn write_instruction(w: &mut BinaryWriter, instruction: &BytecodeInstruction
             w.write_u8(0xa7);
             w.write_i16(*offset);
         }
+        BytecodeInstruction::Jsr { offset } => {
+            w.write_u8(0xa8);
+            w.write_i16(*offset);
+        }
+        BytecodeInstruction::GoToW { offset } => {
+            w.write_u8(0xc8);
+            w.write_i32(*offset);
+        }
+        BytecodeInstruction::JsrW { offset } => {
+            w.write_u8(0xc9);
+            w.write_i32(*offset);
+        }
+        BytecodeInstruction::Ret {
+            local_variable_index,
+        } => match local_variable_index {
+            0..=255 => {
+                w.write_u8(0xa9);
+                w.write_u8(*local_variable_index as u8);
+            }
+            _ => {
+                w.write_u8(0xc4);
+                w.write_u8(0xa9);
+                w.write_u16(*local_variable_index);
+            }
+        },
         BytecodeInstruction::TableSwitch {
             num_padding_bytes,
             default,
@@ -1124,9 +1785,16 @@ pub fn write_instruction(w: &mut BinaryWriter, instruction: &BytecodeInstruction
             w.write_u16(*constant_pool_index);
         }
         BytecodeInstruction::IInc { index, constant } => {
-            w.write_u8(0x84);
-            w.write_u8(*index);
-            w.write_i8(*constant);
+            if instruction.is_wide() {
+                w.write_u8(0xc4);
+                w.write_u8(0x84);
+                w.write_u16(*index);
+                w.write_i16(*constant);
+            } else {
+                w.write_u8(0x84);
+                w.write_u8(*index as u8);
+                w.write_i8(*constant as i8);
+            }
         }
         BytecodeInstruction::I2L {} => w.write_u8(0x85),
         BytecodeInstruction::I2F {} => w.write_u8(0x86),
@@ -1179,14 +1847,21 @@ pub fn write_instruction(w: &mut BinaryWriter, instruction: &BytecodeInstruction
         BytecodeInstruction::DDiv {} => w.write_u8(0x6f),
         BytecodeInstruction::DRem {} => w.write_u8(0x73),
         BytecodeInstruction::DSub {} => w.write_u8(0x67),
+        BytecodeInstruction::Unknown { opcode } => w.write_u8(*opcode),
     }
 }
 
 /// Returns the number of bytes required to fully encode (opcode and padding included) the given instruction.
 pub fn get_instruction_length(instruction: &BytecodeInstruction) -> u32 {
     match instruction {
+        BytecodeInstruction::Nop {} => 1,
         BytecodeInstruction::Dup {} => 1,
+        BytecodeInstruction::DupX1 {} => 1,
+        BytecodeInstruction::DupX2 {} => 1,
         BytecodeInstruction::Dup2 {} => 1,
+        BytecodeInstruction::Dup2X1 {} => 1,
+        BytecodeInstruction::Dup2X2 {} => 1,
+        BytecodeInstruction::Swap {} => 1,
         BytecodeInstruction::AConstNull {} => 1,
         BytecodeInstruction::IConst { .. } => 1,
         BytecodeInstruction::LConst { .. } => 1,
@@ -1199,57 +1874,72 @@ pub fn get_instruction_length(instruction: &BytecodeInstruction) -> u32 {
             local_variable_index,
         } => match local_variable_index {
             0..=3 => 1,
-            _ => 2,
+            4..=255 => 2,
+            _ => 4,
         },
         BytecodeInstruction::AStore {
             local_variable_index,
         } => match local_variable_index {
             0..=3 => 1,
-            _ => 2,
+            4..=255 => 2,
+            _ => 4,
         },
         BytecodeInstruction::ILoad {
             local_variable_index,
         } => match local_variable_index {
             0..=3 => 1,
-            _ => 2,
+            4..=255 => 2,
+            _ => 4,
         },
         BytecodeInstruction::IStore {
             local_variable_index,
         } => match local_variable_index {
             0..=3 => 1,
-            _ => 2,
+            4..=255 => 2,
+            _ => 4,
         },
         BytecodeInstruction::LLoad {
             local_variable_index,
         } => match local_variable_index {
             0..=3 => 1,
-            _ => 2,
+            4..=255 => 2,
+            _ => 4,
         },
         BytecodeInstruction::LStore {
             local_variable_index,
         } => match local_variable_index {
             0..=3 => 1,
-            _ => 2,
+            4..=255 => 2,
+            _ => 4,
         },
         BytecodeInstruction::FLoad {
             local_variable_index,
         } => match local_variable_index {
             0..=3 => 1,
-            _ => 2,
+            4..=255 => 2,
+            _ => 4,
         },
         BytecodeInstruction::FStore {
             local_variable_index,
         } => match local_variable_index {
             0..=3 => 1,
-            _ => 2,
+            4..=255 => 2,
+            _ => 4,
         },
         BytecodeInstruction::DLoad {
             local_variable_index,
         } => match local_variable_index {
             0..=3 => 1,
-            _ => 2,
+            4..=255 => 2,
+            _ => 4,
+        },
+        BytecodeInstruction::DStore {
+            local_variable_index,
+        } => match local_variable_index {
+            0..=3 => 1,
+            4..=255 => 2,
+            _ => 4,
         },
-        BytecodeInstruction::DStore { .. } => 2,
         BytecodeInstruction::IaLoad {} => 1,
         BytecodeInstruction::LaLoad {} => 1,
         BytecodeInstruction::FaLoad {} => 1,
@@ -1268,7 +1958,10 @@ pub fn get_instruction_length(instruction: &BytecodeInstruction) -> u32 {
         BytecodeInstruction::SaStore {} => 1,
         BytecodeInstruction::NewArray { .. } => 2,
         BytecodeInstruction::ANewArray { .. } => 3,
+        BytecodeInstruction::MultiANewArray { .. } => 4,
         BytecodeInstruction::AThrow {} => 1,
+        BytecodeInstruction::MonitorEnter {} => 1,
+        BytecodeInstruction::MonitorExit {} => 1,
         BytecodeInstruction::New { .. } => 3,
         BytecodeInstruction::BiPush { .. } => 2,
         BytecodeInstruction::SiPush { .. } => 3,
@@ -1312,6 +2005,15 @@ pub fn get_instruction_length(instruction: &BytecodeInstruction) -> u32 {
         BytecodeInstruction::IfNull { .. } => 3,
         BytecodeInstruction::IfNonNull { .. } => 3,
         BytecodeInstruction::GoTo { .. } => 3,
+        BytecodeInstruction::Jsr { .. } => 3,
+        BytecodeInstruction::GoToW { .. } => 5,
+        BytecodeInstruction::JsrW { .. } => 5,
+        BytecodeInstruction::Ret {
+            local_variable_index,
+        } => match local_variable_index {
+            0..=255 => 2,
+            _ => 4,
+        },
         BytecodeInstruction::TableSwitch {
             num_padding_bytes,
             offsets,
@@ -1324,7 +2026,13 @@ pub fn get_instruction_length(instruction: &BytecodeInstruction) -> u32 {
         } => 1 + (*num_padding_bytes as u32) + 4 + 4 + (2 * 4) * (pairs.len() as u32),
         BytecodeInstruction::CheckCast { .. } => 3,
         BytecodeInstruction::Instanceof { .. } => 3,
-        BytecodeInstruction::IInc { .. } => 3,
+        BytecodeInstruction::IInc { .. } => {
+            if instruction.is_wide() {
+                6
+            } else {
+                3
+            }
+        }
         BytecodeInstruction::I2L {} => 1,
         BytecodeInstruction::I2F {} => 1,
         BytecodeInstruction::I2D {} => 1,
@@ -1376,5 +2084,467 @@ pub fn get_instruction_length(instruction: &BytecodeInstruction) -> u32 {
         BytecodeInstruction::DDiv {} => 1,
         BytecodeInstruction::DRem {} => 1,
         BytecodeInstruction::DSub {} => 1,
+        BytecodeInstruction::Unknown { .. } => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use binary_reader::Endianness;
+
+    use super::*;
+
+    fn parse(bytes: &[u8], entry_points: &[u32]) -> (Vec<(u32, BytecodeInstruction)>, Vec<u8>) {
+        let cp: ConstantPool = ConstantPool {
+            entries: Vec::new(),
+        };
+        parse_bytecode_with_trailing_bytes(
+            &mut BinaryReader::new(bytes, Endianness::Big),
+            &cp,
+            entry_points,
+        )
+    }
+
+    fn mnemonics(code: &[(u32, BytecodeInstruction)]) -> Vec<(u32, &'static str)> {
+        code.iter()
+            .map(|(position, instruction)| (*position, instruction.mnemonic()))
+            .collect()
+    }
+
+    /// Parses the given code, checks that writing it back gives the same bytes and returns its mnemonics in order.
+    fn roundtrip(bytes: &[u8]) -> Vec<&'static str> {
+        let cp: ConstantPool = ConstantPool {
+            entries: Vec::new(),
+        };
+        let code = parse_bytecode(&mut BinaryReader::new(bytes, Endianness::Big), &cp);
+        assert_eq!(encode_bytecode(&code), bytes);
+        code.iter()
+            .map(|(_, instruction)| instruction.mnemonic())
+            .collect()
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected return at position 1 but it was at 2.")]
+    fn encoding_checks_the_positions() {
+        encode_bytecode(&[
+            (
+                0,
+                BytecodeInstruction::ILoad {
+                    local_variable_index: 4,
+                },
+            ),
+            (1, BytecodeInstruction::Return {}),
+        ]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "The tableswitch at position 1 has 3 padding bytes, which do not align its operands."
+    )]
+    fn encoding_checks_the_switch_padding() {
+        encode_bytecode(&[
+            (0, BytecodeInstruction::IConst { constant: 0 }),
+            (
+                1,
+                BytecodeInstruction::TableSwitch {
+                    num_padding_bytes: 3,
+                    default: 16,
+                    low: 0,
+                    offsets: vec![],
+                },
+            ),
+        ]);
+    }
+
+    #[test]
+    fn float_and_double_constants() {
+        let bytes: [u8; 6] = [0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0xb1];
+        let cp: ConstantPool = ConstantPool {
+            entries: Vec::new(),
+        };
+        let code = parse_bytecode(&mut BinaryReader::new(&bytes, Endianness::Big), &cp);
+        assert_eq!(
+            mnemonics(&code),
+            [
+                (0, "fconst_0"),
+                (1, "fconst_1"),
+                (2, "fconst_2"),
+                (3, "dconst_0"),
+                (4, "dconst_1"),
+                (5, "return")
+            ]
+        );
+        assert!(matches!(code[2].1, BytecodeInstruction::FConst { constant } if constant == 2.0));
+        assert!(matches!(code[4].1, BytecodeInstruction::DConst { constant } if constant == 1.0));
+
+        let mut w: BinaryWriter = BinaryWriter::new(binary_writer::Endianness::Big);
+        for (_, instruction) in code.iter() {
+            write_instruction(&mut w, instruction);
+        }
+        assert_eq!(w.array(), bytes);
+    }
+
+    #[test]
+    fn float_and_double_locals() {
+        let mut bytes: Vec<u8> = Vec::new();
+        // the forms with an operand (fload, dload, fstore, dstore) followed by the short ones of each
+        for (opcode, first_short_opcode) in [(0x17, 0x22), (0x18, 0x26), (0x38, 0x43), (0x39, 0x47)]
+        {
+            bytes.extend([opcode, 5]);
+            bytes.extend(first_short_opcode..first_short_opcode + 4);
+        }
+        let cp: ConstantPool = ConstantPool {
+            entries: Vec::new(),
+        };
+        let code = parse_bytecode(&mut BinaryReader::new(&bytes, Endianness::Big), &cp);
+
+        let mut expected: Vec<(u32, String)> = Vec::new();
+        let mut position: u32 = 0;
+        for kind in ["fload", "dload", "fstore", "dstore"] {
+            expected.push((position, kind.to_owned()));
+            position += 2;
+            for i in 0..4 {
+                expected.push((position, format!("{kind}_{i}")));
+                position += 1;
+            }
+        }
+        assert_eq!(
+            code.iter()
+                .map(|(position, instruction)| (*position, instruction.mnemonic().to_owned()))
+                .collect::<Vec<(u32, String)>>(),
+            expected
+        );
+
+        let mut w: BinaryWriter = BinaryWriter::new(binary_writer::Endianness::Big);
+        for (position, instruction) in code.iter() {
+            let start: usize = w.array().len();
+            write_instruction(&mut w, instruction);
+            assert_eq!(
+                (w.array().len() - start) as u32,
+                get_instruction_length(instruction),
+                "Wrong length at {position}."
+            );
+        }
+        assert_eq!(w.array(), bytes);
+    }
+
+    #[test]
+    fn short_immediates() {
+        // sipush 200; sipush -32768; sipush 32767; bipush -1
+        let bytes: [u8; 11] = [
+            0x11, 0x00, 0xc8, 0x11, 0x80, 0x00, 0x11, 0x7f, 0xff, 0x10, 0xff,
+        ];
+        let cp: ConstantPool = ConstantPool {
+            entries: Vec::new(),
+        };
+        let code = parse_bytecode(&mut BinaryReader::new(&bytes, Endianness::Big), &cp);
+        let immediates: Vec<(u32, i16)> = code
+            .iter()
+            .map(|(position, instruction)| match instruction {
+                BytecodeInstruction::SiPush { immediate } => (*position, *immediate),
+                BytecodeInstruction::BiPush { immediate } => (*position, i16::from(*immediate)),
+                _ => panic!("Expected only pushes of immediates."),
+            })
+            .collect();
+        assert_eq!(immediates, [(0, 200), (3, -32768), (6, 32767), (9, -1)]);
+
+        let mut w: BinaryWriter = BinaryWriter::new(binary_writer::Endianness::Big);
+        for (_, instruction) in code.iter() {
+            write_instruction(&mut w, instruction);
+        }
+        assert_eq!(w.array(), bytes);
+    }
+
+    #[test]
+    fn stack_manipulation() {
+        let bytes: Vec<u8> = (0x57..=0x5f).collect();
+        assert_eq!(
+            roundtrip(&bytes),
+            [
+                "pop", "pop2", "dup", "dup_x1", "dup_x2", "dup2", "dup2_x1", "dup2_x2", "swap"
+            ]
+        );
+    }
+
+    #[test]
+    fn arithmetic() {
+        let bytes: Vec<u8> = (0x60..=0x77).collect();
+        let expected: Vec<String> = ["add", "sub", "mul", "div", "rem", "neg"]
+            .iter()
+            .flat_map(|operation| ["i", "l", "f", "d"].map(|kind| format!("{kind}{operation}")))
+            .collect();
+        assert_eq!(roundtrip(&bytes), expected);
+    }
+
+    #[test]
+    fn shifts() {
+        let bytes: Vec<u8> = (0x78..=0x7d).collect();
+        assert_eq!(
+            roundtrip(&bytes),
+            ["ishl", "lshl", "ishr", "lshr", "iushr", "lushr"]
+        );
+        // iinc 1, -1; ishl
+        assert_eq!(roundtrip(&[0x84, 0x01, 0xff, 0x78]), ["iinc", "ishl"]);
+    }
+
+    #[test]
+    fn conversions() {
+        let bytes: Vec<u8> = (0x85..=0x93).collect();
+        assert_eq!(
+            roundtrip(&bytes),
+            [
+                "i2l", "i2f", "i2d", "l2i", "l2f", "l2d", "f2i", "f2l", "f2d", "d2i", "d2l", "d2f",
+                "i2b", "i2c", "i2s"
+            ]
+        );
+    }
+
+    #[test]
+    fn array_loads_and_stores() {
+        let loads: Vec<u8> = (0x2e..=0x35).collect();
+        assert_eq!(
+            roundtrip(&loads),
+            [
+                "iaload", "laload", "faload", "daload", "aaload", "baload", "caload", "saload"
+            ]
+        );
+        let stores: Vec<u8> = (0x4f..=0x56).collect();
+        assert_eq!(
+            roundtrip(&stores),
+            [
+                "iastore", "lastore", "fastore", "dastore", "aastore", "bastore", "castore",
+                "sastore"
+            ]
+        );
+    }
+
+    #[test]
+    fn primitive_arrays() {
+        let bytes: Vec<u8> = (4..=11).flat_map(|atype| [0xbc, atype]).collect();
+        assert_eq!(roundtrip(&bytes), ["newarray"; 8]);
+
+        let cp: ConstantPool = ConstantPool {
+            entries: Vec::new(),
+        };
+        let types: Vec<String> =
+            parse_bytecode(&mut BinaryReader::new(&bytes, Endianness::Big), &cp)
+                .iter()
+                .map(|(_, instruction)| match instruction {
+                    BytecodeInstruction::NewArray { atype } => atype.to_string(),
+                    _ => panic!("Expected only newarray instructions."),
+                })
+                .collect();
+        assert_eq!(
+            types,
+            [
+                "boolean", "char", "float", "double", "byte", "short", "int", "long"
+            ]
+        );
+    }
+
+    #[test]
+    fn field_access() {
+        // getfield #7; putfield #7; putfield #65535
+        let bytes: [u8; 9] = [0xb4, 0x00, 0x07, 0xb5, 0x00, 0x07, 0xb5, 0xff, 0xff];
+        assert_eq!(roundtrip(&bytes), ["getfield", "putfield", "putfield"]);
+    }
+
+    #[test]
+    fn type_checks() {
+        // checkcast #7; instanceof #7; instanceof #65535
+        let bytes: [u8; 9] = [0xc0, 0x00, 0x07, 0xc1, 0x00, 0x07, 0xc1, 0xff, 0xff];
+        assert_eq!(roundtrip(&bytes), ["checkcast", "instanceof", "instanceof"]);
+    }
+
+    #[test]
+    fn monitors() {
+        assert_eq!(
+            roundtrip(&[0xc2, 0xc3, 0xc2, 0xc3]),
+            ["monitorenter", "monitorexit", "monitorenter", "monitorexit"]
+        );
+    }
+
+    #[test]
+    fn wide_operands() {
+        // wide iload 300; wide astore 65535; wide iinc 1, 1000; wide iinc 256, -1; wide ret 256
+        let bytes: [u8; 24] = [
+            0xc4, 0x15, 0x01, 0x2c, 0xc4, 0x3a, 0xff, 0xff, 0xc4, 0x84, 0x00, 0x01, 0x03, 0xe8,
+            0xc4, 0x84, 0x01, 0x00, 0xff, 0xff, 0xc4, 0xa9, 0x01, 0x00,
+        ];
+        assert_eq!(
+            roundtrip(&bytes),
+            ["iload_w", "astore_w", "iinc_w", "iinc_w", "ret_w"]
+        );
+
+        let cp: ConstantPool = ConstantPool {
+            entries: Vec::new(),
+        };
+        let code = parse_bytecode(&mut BinaryReader::new(&bytes, Endianness::Big), &cp);
+        assert!(matches!(
+            code[2],
+            (
+                8,
+                BytecodeInstruction::IInc {
+                    index: 1,
+                    constant: 1000
+                }
+            )
+        ));
+    }
+
+    #[test]
+    fn narrow_operands_do_not_need_wide() {
+        // iload 255; iinc 255, -128
+        let bytes: [u8; 5] = [0x15, 0xff, 0x84, 0xff, 0x80];
+        assert_eq!(roundtrip(&bytes), ["iload", "iinc"]);
+        let instruction = BytecodeInstruction::IInc {
+            index: 255,
+            constant: -128,
+        };
+        assert!(!instruction.is_wide());
+    }
+
+    #[test]
+    fn returns() {
+        assert_eq!(
+            roundtrip(&[0xac, 0xad, 0xae, 0xaf, 0xb0, 0xb1]),
+            [
+                "ireturn", "lreturn", "freturn", "dreturn", "areturn", "return"
+            ]
+        );
+    }
+
+    #[test]
+    fn comparisons() {
+        assert_eq!(
+            roundtrip(&[0x94, 0x95, 0x96, 0x97, 0x98]),
+            ["lcmp", "fcmpl", "fcmpg", "dcmpl", "dcmpg"]
+        );
+    }
+
+    #[test]
+    fn null_checks() {
+        // ifnull +6; ifnonnull -3
+        assert_eq!(
+            roundtrip(&[0xc6, 0x00, 0x06, 0xc7, 0xff, 0xfd]),
+            ["ifnull", "ifnonnull"]
+        );
+    }
+
+    #[test]
+    fn wide_branches() {
+        // goto_w +70000; jsr_w -5
+        let bytes: [u8; 10] = [0xc8, 0x00, 0x01, 0x11, 0x70, 0xc9, 0xff, 0xff, 0xff, 0xfb];
+        assert_eq!(roundtrip(&bytes), ["goto_w", "jsr_w"]);
+
+        let cp: ConstantPool = ConstantPool {
+            entries: Vec::new(),
+        };
+        let code = parse_bytecode(&mut BinaryReader::new(&bytes, Endianness::Big), &cp);
+        assert_eq!(branch_targets(code[0].0, &code[0].1), [70000]);
+        assert_eq!(branch_targets(code[1].0, &code[1].1), [0]);
+    }
+
+    #[test]
+    fn padding_with_nop() {
+        assert_eq!(
+            roundtrip(&[0x00, 0x00, 0x00, 0xb1]),
+            ["nop", "nop", "nop", "return"]
+        );
+    }
+
+    #[test]
+    fn unknown_opcodes_are_kept() {
+        // breakpoint, impdep1, impdep2 and an unassigned opcode
+        assert_eq!(
+            roundtrip(&[0xca, 0xb1, 0xfe, 0xff, 0xd0]),
+            ["unknown", "return", "unknown", "unknown", "unknown"]
+        );
+    }
+
+    #[test]
+    fn multi_dimensional_arrays() {
+        // multianewarray #7, 2; multianewarray #300, 255
+        let bytes: [u8; 8] = [0xc5, 0x00, 0x07, 0x02, 0xc5, 0x01, 0x2c, 0xff];
+        assert_eq!(roundtrip(&bytes), ["multianewarray"; 2]);
+
+        let cp: ConstantPool = ConstantPool {
+            entries: Vec::new(),
+        };
+        let code = parse_bytecode(&mut BinaryReader::new(&bytes, Endianness::Big), &cp);
+        assert!(matches!(
+            code[1],
+            (
+                4,
+                BytecodeInstruction::MultiANewArray {
+                    constant_pool_index: 300,
+                    dimensions: 255
+                }
+            )
+        ));
+    }
+
+    #[test]
+    fn bitwise_operations() {
+        let bytes: Vec<u8> = (0x7e..=0x83).collect();
+        assert_eq!(
+            roundtrip(&bytes),
+            ["iand", "land", "ior", "lor", "ixor", "lxor"]
+        );
+    }
+
+    #[test]
+    fn junk_after_the_last_return() {
+        // iconst_0; ifeq 5; return; return; followed by bytes which are not valid opcodes
+        let (code, trailing_bytes) =
+            parse(&[0x03, 0x99, 0x00, 0x04, 0xb1, 0xb1, 0xff, 0xfe, 0xca], &[]);
+        assert_eq!(
+            mnemonics(&code),
+            [(0, "iconst_0"), (1, "ifeq"), (4, "return"), (5, "return")]
+        );
+        assert_eq!(trailing_bytes, [0xff, 0xfe, 0xca]);
+    }
+
+    #[test]
+    fn truncated_last_instruction() {
+        // return; sipush without its second byte
+        let (code, trailing_bytes) = parse(&[0xb1, 0x11, 0x00], &[]);
+        assert_eq!(mnemonics(&code), [(0, "return")]);
+        assert_eq!(trailing_bytes, [0x11, 0x00]);
+    }
+
+    #[test]
+    fn unreachable_code_is_decoded() {
+        // areturn; return; the second one cannot be reached but is still a valid instruction
+        let (code, trailing_bytes) = parse(&[0xb0, 0xb1], &[]);
+        assert_eq!(mnemonics(&code), [(0, "areturn"), (1, "return")]);
+        assert!(trailing_bytes.is_empty());
+    }
+
+    #[test]
+    fn unknown_opcodes_in_reachable_code_are_kept() {
+        // nop; an unknown opcode; return
+        let (code, trailing_bytes) = parse(&[0x00, 0xca, 0xb1], &[]);
+        assert_eq!(
+            mnemonics(&code),
+            [(0, "nop"), (1, "unknown"), (2, "return")]
+        );
+        assert!(trailing_bytes.is_empty());
+
+        // return; an unknown opcode reachable only from the given entry point; return
+        let (code, trailing_bytes) = parse(&[0xb1, 0xca, 0xb1], &[1]);
+        assert_eq!(
+            mnemonics(&code),
+            [(0, "return"), (1, "unknown"), (2, "return")]
+        );
+        assert!(trailing_bytes.is_empty());
+
+        // goto 4; return; an unknown opcode which the goto jumps past; return
+        let (code, trailing_bytes) = parse(&[0xa7, 0x00, 0x05, 0xb1, 0xca, 0xb1], &[]);
+        assert_eq!(
+            mnemonics(&code),
+            [(0, "goto"), (3, "return"), (4, "unknown"), (5, "return")]
+        );
+        assert!(trailing_bytes.is_empty());
     }
 }
@@ -1,8 +1,9 @@
-use binary_reader::BinaryReader;
+use std::fmt;
+
+use binary_reader::{BinaryReader, Endianness};
 
 use crate::access_flags::ClassAccessFlags;
-use crate::assert_valid_and_type;
-use crate::attributes::{AttributeInfo, parse_class_attributes};
+use crate::attributes::{AttributeInfo, AttributeKind, find_attribute, parse_class_attributes};
 use crate::constant_pool::{
     ConstantPool, ConstantPoolTag, check_constant_pool, parse_constant_pool,
 };
@@ -12,7 +13,8 @@ use crate::methods::{MethodInfo, parse_methods};
 /**
  * Specification available at <https://docs.oracle.com/javase/specs/jvms/se25/html/jvms-4.html>
  */
-#[derive(Clone)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ClassFile {
     pub minor_version: u16,
     pub major_version: u16,
@@ -26,62 +28,252 @@ pub struct ClassFile {
     pub attributes: Vec<AttributeInfo>,
 }
 
-pub fn parse_class_file(reader: &mut BinaryReader) -> ClassFile {
-    let actual_magic_number: u32 = reader.read_u32().unwrap();
+impl ClassFile {
+    /// Returns the binary name of this class, like `java/lang/Object`.
+    pub fn class_name(&self) -> String {
+        self.constant_pool.get_class_name(self.this_class)
+    }
+
+    /// Returns the name of the source file this class was compiled from, if the SourceFile attribute is present.
+    pub fn source_file(&self) -> Option<String> {
+        match find_attribute(&self.attributes, AttributeKind::SourceFile) {
+            Some(AttributeInfo::SourceFile {
+                source_file_index, ..
+            }) => Some(self.constant_pool.get_utf8_content(*source_file_index)),
+            _ => None,
+        }
+    }
+
+    /// Returns the field of this class with the given name and descriptor, like `count` and `I`.
+    pub fn find_field(&self, name: &str, descriptor: &str) -> Option<&FieldInfo> {
+        self.fields.iter().find(|field| {
+            self.constant_pool.get_utf8_content(field.name_index) == name
+                && self.constant_pool.get_utf8_content(field.descriptor_index) == descriptor
+        })
+    }
+
+    /// Returns the method of this class with the given name and descriptor, like `main` and `([Ljava/lang/String;)V`.
+    pub fn find_method(&self, name: &str, descriptor: &str) -> Option<&MethodInfo> {
+        self.methods.iter().find(|method| {
+            self.constant_pool.get_utf8_content(method.name_index) == name
+                && self.constant_pool.get_utf8_content(method.descriptor_index) == descriptor
+        })
+    }
+
+    /// Returns the methods of this class with the given name, in the order they are declared. Overloads and the
+    /// bridge methods generated by javac share the name of another method.
+    pub fn methods_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a MethodInfo> {
+        self.methods
+            .iter()
+            .filter(move |method| self.constant_pool.get_utf8_content(method.name_index) == name)
+    }
+}
+
+/**
+ * Prints the header of the class like `javap -v` does, from its declaration to the number of its members and
+ * attributes. The declaration shows only the modifiers and the name of the class, without looking at its Signature
+ * attribute.
+ */
+impl fmt::Display for ClassFile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // the comments are aligned to the same column as in javap
+        let with_comment = |text: String, class_index: u16| -> String {
+            format!(
+                "{text:<40}// {}",
+                self.constant_pool.get_class_name(class_index)
+            )
+        };
+
+        writeln!(
+            f,
+            "{} {}",
+            self.access_flags.modifier_repr(),
+            self.class_name().replace('/', ".")
+        )?;
+        writeln!(f, "  minor version: {}", self.minor_version)?;
+        writeln!(f, "  major version: {}", self.major_version)?;
+        writeln!(
+            f,
+            "  flags: (0x{:04x}) {}",
+            self.access_flags.to_u16(),
+            self.access_flags.java_repr()
+        )?;
+        writeln!(
+            f,
+            "  {}",
+            with_comment(format!("this_class: #{}", self.this_class), self.this_class)
+        )?;
+        if self.super_class == 0 {
+            writeln!(f, "  super_class: #0")?;
+        } else {
+            writeln!(
+                f,
+                "  {}",
+                with_comment(
+                    format!("super_class: #{}", self.super_class),
+                    self.super_class
+                )
+            )?;
+        }
+        writeln!(
+            f,
+            "  interfaces: {}, fields: {}, methods: {}, attributes: {}",
+            self.interfaces.len(),
+            self.fields.len(),
+            self.methods.len(),
+            self.attributes.len()
+        )
+    }
+}
+
+/// The error returned when the given bytes are not a valid class file, or one of an unsupported version.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ClassFileError {
+    pub message: String,
+}
+
+impl ClassFileError {
+    pub(crate) fn new(message: String) -> ClassFileError {
+        ClassFileError { message }
+    }
+}
+
+impl fmt::Display for ClassFileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ClassFileError {}
+
+impl From<std::io::Error> for ClassFileError {
+    fn from(error: std::io::Error) -> ClassFileError {
+        ClassFileError::new(format!("Could not read the class file: {error}."))
+    }
+}
+
+/**
+ * Reads the magic number and the version of the class file, checking that it is supported.
+ */
+fn parse_version(reader: &mut BinaryReader) -> Result<(u16, u16), ClassFileError> {
+    let actual_magic_number: u32 = reader.read_u32()?;
     const EXPECTED_MAGIC_NUMBER: u32 = 0xcafebabe;
-    assert!(
-        actual_magic_number == EXPECTED_MAGIC_NUMBER,
-        "Wrong magic number: expected 0x{EXPECTED_MAGIC_NUMBER:08x} but was 0x{actual_magic_number:08x}."
-    );
+    if actual_magic_number != EXPECTED_MAGIC_NUMBER {
+        return Err(ClassFileError::new(format!(
+            "Wrong magic number: expected 0x{EXPECTED_MAGIC_NUMBER:08x} but was 0x{actual_magic_number:08x}."
+        )));
+    }
 
-    let minor_version: u16 = reader.read_u16().unwrap();
-    let major_version: u16 = reader.read_u16().unwrap();
+    let minor_version: u16 = reader.read_u16()?;
+    let major_version: u16 = reader.read_u16()?;
     {
         // class file format version of java 1.0
         const OLDEST_MAJOR_VERSION: u16 = 45;
-        assert!(
-            major_version >= OLDEST_MAJOR_VERSION,
-            "Invalid class file version {major_version}.{minor_version} (0x{major_version:04x}.{minor_version:04x}) is older than the oldest supported java class file version {OLDEST_MAJOR_VERSION}.0.",
-        );
+        if major_version < OLDEST_MAJOR_VERSION {
+            return Err(ClassFileError::new(format!(
+                "Invalid class file version {major_version}.{minor_version} (0x{major_version:04x}.{minor_version:04x}) is older than the oldest supported java class file version {OLDEST_MAJOR_VERSION}.0.",
+            )));
+        }
 
         // class file format version of java 25
         const LATEST_MAJOR_VERSION: u16 = 69;
-        assert!(
-            major_version <= LATEST_MAJOR_VERSION,
-            "Class file version {major_version}.{minor_version} is greater than the latest supported version {LATEST_MAJOR_VERSION}.0.",
-        );
+        if major_version > LATEST_MAJOR_VERSION {
+            return Err(ClassFileError::new(format!(
+                "Class file version {major_version}.{minor_version} is greater than the latest supported version {LATEST_MAJOR_VERSION}.0.",
+            )));
+        }
+    }
+
+    Ok((minor_version, major_version))
+}
+
+/// Checks that the given index, read from the header of the class file, points to a Class entry.
+fn check_class_index(cp: &ConstantPool, cp_index: u16, name: &str) -> Result<(), ClassFileError> {
+    if cp_index == 0 || usize::from(cp_index) > cp.len() {
+        return Err(ClassFileError::new(format!(
+            "The {name} index {cp_index} is outside of the constant pool, which has {} entries.",
+            cp.len()
+        )));
     }
+    let tag: ConstantPoolTag = cp[cp_index - 1].tag();
+    if tag != ConstantPoolTag::Class {
+        return Err(ClassFileError::new(format!(
+            "The {name} index {cp_index} should point to a Class entry but points to a {tag:?} entry."
+        )));
+    }
+    Ok(())
+}
+
+/**
+ * Returns the name of the Java release which introduced the given major version of the class file format, like `"8"`
+ * for 52 or `"21"` for 65. Releases before Java 5 are named with their `1.x` version number.
+ */
+pub fn java_version_name(major_version: u16) -> Option<&'static str> {
+    const NAMES: [&str; 25] = [
+        "1.1", "1.2", "1.3", "1.4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15",
+        "16", "17", "18", "19", "20", "21", "22", "23", "24", "25",
+    ];
+    NAMES
+        .get(usize::from(major_version.checked_sub(45)?))
+        .copied()
+}
+
+/**
+ * Returns the name of the class contained in the given bytes, without parsing anything after the `this_class`
+ * index. This is much cheaper than a full parse when only the name is needed, like when indexing a jar.
+ */
+pub fn peek_class_name(bytes: &[u8]) -> Result<String, ClassFileError> {
+    let mut reader: BinaryReader = BinaryReader::new(bytes, Endianness::Big);
+    parse_version(&mut reader)?;
+
+    let cp_count: u16 = reader.read_u16()?;
+    let constant_pool: ConstantPool =
+        parse_constant_pool(&mut reader, cp_count.saturating_sub(1).into())?;
+
+    let _access_flags: u16 = reader.read_u16()?;
+    let this_class: u16 = reader.read_u16()?;
+    check_class_index(&constant_pool, this_class, "this_class")?;
 
-    let cp_count: u16 = reader.read_u16().unwrap();
-    let constant_pool: ConstantPool = parse_constant_pool(reader, (cp_count - 1).into());
+    Ok(constant_pool.get_class_name(this_class))
+}
+
+pub fn parse_class_file(reader: &mut BinaryReader) -> Result<ClassFile, ClassFileError> {
+    let (minor_version, major_version) = parse_version(reader)?;
 
-    let access_flags: ClassAccessFlags = ClassAccessFlags::from(reader.read_u16().unwrap());
+    let cp_count: u16 = reader.read_u16()?;
+    let constant_pool: ConstantPool =
+        parse_constant_pool(reader, cp_count.saturating_sub(1).into())?;
 
-    let this_class: u16 = reader.read_u16().unwrap();
-    assert_valid_and_type!(&constant_pool, this_class, ConstantPoolTag::Class);
+    let access_flags: ClassAccessFlags = ClassAccessFlags::from(reader.read_u16()?);
 
-    let super_class: u16 = reader.read_u16().unwrap();
-    assert_valid_and_type!(&constant_pool, super_class, ConstantPoolTag::Class);
+    let this_class: u16 = reader.read_u16()?;
+    check_class_index(&constant_pool, this_class, "this_class")?;
+
+    let super_class: u16 = reader.read_u16()?;
+    // only java/lang/Object has no superclass
+    if super_class != 0 {
+        check_class_index(&constant_pool, super_class, "super_class")?;
+    }
 
-    let interfaces_count: u16 = reader.read_u16().unwrap();
-    let interfaces: Vec<u16> = reader.read_u16_vec(interfaces_count.into()).unwrap();
+    let interfaces_count: u16 = reader.read_u16()?;
+    let interfaces: Vec<u16> = reader.read_u16_vec(interfaces_count.into())?;
     for interface_idx in interfaces.iter() {
-        assert_valid_and_type!(&constant_pool, *interface_idx, ConstantPoolTag::Class);
+        check_class_index(&constant_pool, *interface_idx, "interface")?;
     }
 
-    let fields_count: u16 = reader.read_u16().unwrap();
+    let fields_count: u16 = reader.read_u16()?;
     let fields: Vec<FieldInfo> = parse_fields(reader, &constant_pool, fields_count.into());
 
-    let methods_count: u16 = reader.read_u16().unwrap();
+    let methods_count: u16 = reader.read_u16()?;
     let methods: Vec<MethodInfo> = parse_methods(reader, &constant_pool, methods_count.into());
 
-    let attributes_count: u16 = reader.read_u16().unwrap();
+    let attributes_count: u16 = reader.read_u16()?;
     let attributes: Vec<AttributeInfo> =
         parse_class_attributes(reader, &constant_pool, attributes_count.into());
 
     check_constant_pool(&constant_pool, &attributes);
 
-    ClassFile {
+    Ok(ClassFile {
         minor_version,
         major_version,
         constant_pool,
@@ -92,5 +284,352 @@ pub fn parse_class_file(reader: &mut BinaryReader) -> ClassFile {
         fields,
         methods,
         attributes,
+    })
+}
+
+/// Parses a whole class file held in memory, failing if any byte is left after its end.
+pub fn parse_class_file_from_bytes(bytes: &[u8]) -> Result<ClassFile, ClassFileError> {
+    let mut reader: BinaryReader = BinaryReader::new(bytes, Endianness::Big);
+    let cf: ClassFile = parse_class_file(&mut reader)?;
+    if reader.remaining() != 0 {
+        return Err(ClassFileError::new(format!(
+            "Found {} unexpected bytes after the end of the class file.",
+            reader.remaining()
+        )));
+    }
+    Ok(cf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::access_flags::{ClassAccessFlag, MethodAccessFlag};
+    use crate::writer::write_class_file;
+
+    #[test]
+    fn interface_with_abstract_methods() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Shape.class")).unwrap();
+        assert!(cf.access_flags.contains(ClassAccessFlag::Interface));
+        assert_eq!(cf.methods.len(), 3);
+        for method in cf.methods.iter() {
+            assert!(method.access_flags.contains(MethodAccessFlag::Abstract));
+            assert!(find_attribute(&method.attributes, AttributeKind::Code).is_none());
+        }
+        assert!(find_attribute(&cf.methods[0].attributes, AttributeKind::Exceptions).is_some());
+        assert!(find_attribute(&cf.methods[1].attributes, AttributeKind::Signature).is_some());
+    }
+
+    #[test]
+    fn display_header() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/HelloWorld.class")).unwrap();
+        assert_eq!(
+            cf.to_string(),
+            [
+                "public final class HelloWorld",
+                "  minor version: 0",
+                "  major version: 69",
+                "  flags: (0x0031) ACC_PUBLIC, ACC_FINAL, ACC_SUPER",
+                "  this_class: #21                         // HelloWorld",
+                "  super_class: #2                         // java/lang/Object",
+                "  interfaces: 0, fields: 0, methods: 2, attributes: 1",
+                ""
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn debug() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/HelloWorld.class")).unwrap();
+        let repr: String = format!("{cf:?}");
+        assert!(repr.starts_with("ClassFile { minor_version: 0, major_version: 69, "));
+        assert!(repr.contains("Utf8 { bytes: "));
+        assert!(repr.contains("Code { name_index: "));
+        assert!(repr.contains("InvokeVirtual { method_ref_index: "));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_roundtrip() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/HelloWorld.class");
+        let json: String =
+            serde_json::to_string(&parse_class_file_from_bytes(bytes).unwrap()).unwrap();
+        assert!(json.starts_with("{\"minor_version\":0,\"major_version\":69,"));
+        let cf: ClassFile = serde_json::from_str(&json).unwrap();
+        assert_eq!(write_class_file(&cf), bytes);
+    }
+
+    #[test]
+    fn find_members() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Modifiers.class")).unwrap();
+        let cp: &ConstantPool = &cf.constant_pool;
+
+        let field: &FieldInfo = cf.find_field("cache", "Ljava/lang/String;").unwrap();
+        assert_eq!(cp.get_utf8_content(field.name_index), "cache");
+        assert!(cf.find_field("cache", "I").is_none());
+        assert!(cf.find_field("missing", "I").is_none());
+
+        let bridge: &MethodInfo = cf
+            .find_method("compareTo", "(Ljava/lang/Object;)I")
+            .unwrap();
+        assert!(bridge.access_flags.contains(MethodAccessFlag::Bridge));
+        assert!(cf.find_method("compareTo", "()I").is_none());
+
+        let descriptors: Vec<String> = cf
+            .methods_named("compareTo")
+            .map(|method| cp.get_utf8_content(method.descriptor_index))
+            .collect();
+        assert_eq!(descriptors, ["(LModifiers;)I", "(Ljava/lang/Object;)I"]);
+        assert_eq!(cf.methods_named("missing").count(), 0);
+    }
+
+    #[test]
+    fn source_file() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Shape.class")).unwrap();
+        assert_eq!(cf.source_file(), Some("Shape.java".to_owned()));
+    }
+
+    #[test]
+    fn no_source_file() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Stripped.class")).unwrap();
+        assert!(find_attribute(&cf.attributes, AttributeKind::SourceFile).is_none());
+        assert_eq!(cf.source_file(), None);
+    }
+
+    #[test]
+    fn peeked_name_matches_full_parse() {
+        let fixtures: [&[u8]; 4] = [
+            include_bytes!("../../e2e/data/Employee.class"),
+            include_bytes!("../../e2e/data/SecuritySettings$1.class"),
+            include_bytes!("../../e2e/data/Shape.class"),
+            include_bytes!("../../e2e/data/TimeUnit.class"),
+        ];
+        for bytes in fixtures {
+            assert_eq!(
+                peek_class_name(bytes),
+                Ok(parse_class_file_from_bytes(bytes).unwrap().class_name())
+            );
+        }
+    }
+
+    #[test]
+    fn peeking_invalid_class_files() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Employee.class");
+        assert_eq!(
+            peek_class_name(&bytes[..20]).unwrap_err().message,
+            "Could not read the class file: Not enough bytes."
+        );
+        assert_eq!(
+            peek_class_name(&[0xca, 0xfe, 0xd0, 0x0d])
+                .unwrap_err()
+                .message,
+            "Wrong magic number: expected 0xcafebabe but was 0xcafed00d."
+        );
+        let mut unknown_tag: Vec<u8> = bytes.to_vec();
+        unknown_tag[10] = 2;
+        assert_eq!(
+            peek_class_name(&unknown_tag).unwrap_err().message,
+            "Unknown constant pool tag 2 at entry #1."
+        );
+        let mut future: Vec<u8> = bytes.to_vec();
+        future[6..8].copy_from_slice(&70u16.to_be_bytes());
+        assert_eq!(
+            peek_class_name(&future).unwrap_err().message,
+            "Class file version 70.0 is greater than the latest supported version 69.0."
+        );
+        let wrong_this_class: Vec<u8> = with_header(|cf| cf.this_class = first_utf8(cf));
+        assert_eq!(
+            peek_class_name(&wrong_this_class).unwrap_err().message,
+            format!(
+                "The this_class index {} should point to a Class entry but points to a Utf8 entry.",
+                first_utf8(&parse_class_file_from_bytes(bytes).unwrap())
+            )
+        );
+    }
+
+    #[test]
+    fn java_version_names() {
+        assert_eq!(java_version_name(44), None);
+        assert_eq!(java_version_name(45), Some("1.1"));
+        assert_eq!(java_version_name(48), Some("1.4"));
+        assert_eq!(java_version_name(49), Some("5"));
+        assert_eq!(java_version_name(52), Some("8"));
+        assert_eq!(java_version_name(61), Some("17"));
+        assert_eq!(java_version_name(65), Some("21"));
+        assert_eq!(java_version_name(69), Some("25"));
+        assert_eq!(java_version_name(70), None);
+    }
+
+    #[test]
+    fn old_class_file_with_subroutines() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/OldSubroutine.class"))
+                .unwrap();
+        assert_eq!(cf.major_version, 49);
+        let Some(AttributeInfo::Code {
+            code, attributes, ..
+        }) = find_attribute(&cf.methods[0].attributes, AttributeKind::Code)
+        else {
+            unreachable!()
+        };
+        assert!(find_attribute(attributes, AttributeKind::StackMapTable).is_none());
+        let mnemonics: Vec<&str> = code.iter().map(|(_, i)| i.mnemonic()).collect();
+        assert_eq!(
+            mnemonics,
+            vec![
+                "jsr",
+                "jsr",
+                "return",
+                "astore_1",
+                "getstatic",
+                "ldc",
+                "invokevirtual",
+                "ret"
+            ]
+        );
+    }
+
+    #[test]
+    fn sealed_class_and_its_nest() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Sealed.class")).unwrap();
+        let class_names = |classes: &[u16]| -> Vec<String> {
+            classes
+                .iter()
+                .map(|idx| cf.constant_pool.get_class_name(*idx))
+                .collect()
+        };
+        let Some(AttributeInfo::PermittedSubclasses { classes, .. }) =
+            find_attribute(&cf.attributes, AttributeKind::PermittedSubclasses)
+        else {
+            unreachable!()
+        };
+        assert_eq!(class_names(classes), vec!["Sealed$Circle", "Sealed$Square"]);
+        let Some(AttributeInfo::NestMembers { classes, .. }) =
+            find_attribute(&cf.attributes, AttributeKind::NestMembers)
+        else {
+            unreachable!()
+        };
+        assert_eq!(class_names(classes), vec!["Sealed$Square", "Sealed$Circle"]);
+
+        let nested: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Sealed$Circle.class"))
+                .unwrap();
+        let Some(AttributeInfo::NestHost {
+            host_class_index, ..
+        }) = find_attribute(&nested.attributes, AttributeKind::NestHost)
+        else {
+            unreachable!()
+        };
+        assert_eq!(
+            nested.constant_pool.get_class_name(*host_class_index),
+            "Sealed"
+        );
+    }
+
+    fn with_header(edit: impl FnOnce(&mut ClassFile)) -> Vec<u8> {
+        let mut cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Employee.class")).unwrap();
+        edit(&mut cf);
+        write_class_file(&cf)
+    }
+
+    /// Returns the index of the first Utf8 entry of the given constant pool.
+    fn first_utf8(cf: &ClassFile) -> u16 {
+        (1..=cf.constant_pool.len() as u16)
+            .find(|i| cf.constant_pool[*i - 1].tag() == ConstantPoolTag::Utf8)
+            .unwrap()
+    }
+
+    /// Returns the error message of parsing the given bytes.
+    fn parse_error(bytes: &[u8]) -> String {
+        parse_class_file_from_bytes(bytes).unwrap_err().message
+    }
+
+    #[test]
+    fn this_class_not_pointing_to_a_class() {
+        let utf8: u16 = first_utf8(
+            &parse_class_file_from_bytes(include_bytes!("../../e2e/data/Employee.class")).unwrap(),
+        );
+        assert_eq!(
+            parse_error(&with_header(|cf| cf.this_class = utf8)),
+            format!(
+                "The this_class index {utf8} should point to a Class entry but points to a Utf8 entry."
+            )
+        );
+    }
+
+    #[test]
+    fn super_class_not_pointing_to_a_class() {
+        let utf8: u16 = first_utf8(
+            &parse_class_file_from_bytes(include_bytes!("../../e2e/data/Employee.class")).unwrap(),
+        );
+        assert_eq!(
+            parse_error(&with_header(|cf| cf.super_class = utf8)),
+            format!(
+                "The super_class index {utf8} should point to a Class entry but points to a Utf8 entry."
+            )
+        );
+    }
+
+    #[test]
+    fn interface_not_pointing_to_a_class() {
+        let utf8: u16 = first_utf8(
+            &parse_class_file_from_bytes(include_bytes!("../../e2e/data/Employee.class")).unwrap(),
+        );
+        assert_eq!(
+            parse_error(&with_header(|cf| cf.interfaces.push(utf8))),
+            format!(
+                "The interface index {utf8} should point to a Class entry but points to a Utf8 entry."
+            )
+        );
+    }
+
+    #[test]
+    fn class_index_outside_of_the_constant_pool() {
+        let bytes: Vec<u8> = with_header(|cf| cf.super_class = cf.constant_pool.len() as u16 + 1);
+        let cp_len: usize =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Employee.class"))
+                .unwrap()
+                .constant_pool
+                .len();
+        assert_eq!(
+            parse_error(&bytes),
+            format!(
+                "The super_class index {} is outside of the constant pool, which has {cp_len} entries.",
+                cp_len + 1
+            )
+        );
+    }
+
+    #[test]
+    fn class_without_superclass() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(&with_header(|cf| cf.super_class = 0)).unwrap();
+        assert_eq!(cf.super_class, 0);
+    }
+
+    #[test]
+    fn parsing_from_bytes() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Employee.class");
+        let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        assert_eq!(cf.class_name(), "Employee");
+        assert_eq!(write_class_file(&cf), bytes);
+    }
+
+    #[test]
+    fn trailing_bytes_after_the_class_file() {
+        let mut bytes: Vec<u8> = include_bytes!("../../e2e/data/Employee.class").to_vec();
+        bytes.extend([0xca, 0xfe]);
+        assert_eq!(
+            parse_class_file_from_bytes(&bytes).unwrap_err().message,
+            "Found 2 unexpected bytes after the end of the class file."
+        );
     }
 }
@@ -0,0 +1,585 @@
+use std::collections::{BTreeMap, HashSet};
+
+use crate::{
+    attributes::{AttributeInfo, StackMapFrame, VerificationTypeInfo},
+    bytecode::{BytecodeInstruction, get_instruction_length},
+};
+
+/// Returns the bytecode offsets the given instruction may jump to, excluding the following instruction.
+pub fn branch_targets(position: u32, instruction: &BytecodeInstruction) -> Vec<u32> {
+    let mut targets: Vec<u32> = Vec::new();
+    let mut instruction: BytecodeInstruction = instruction.clone();
+    for_each_branch_offset(&mut instruction, &mut |offset| {
+        targets.push((i64::from(position) + offset) as u32);
+        offset
+    });
+    targets
+}
+
+/// Calls the given function with every relative jump offset of the instruction, replacing it with the result.
+fn for_each_branch_offset(instruction: &mut BytecodeInstruction, f: &mut dyn FnMut(i64) -> i64) {
+    let mut short = |offset: &mut i16| {
+        *offset = f((*offset).into())
+            .try_into()
+            .expect("Branch offset does not fit in 16 bits.");
+    };
+    match instruction {
+        BytecodeInstruction::IfAcmpEq { offset }
+        | BytecodeInstruction::IfAcmpNe { offset }
+        | BytecodeInstruction::IfIcmpEq { offset }
+        | BytecodeInstruction::IfIcmpNe { offset }
+        | BytecodeInstruction::IfIcmpLt { offset }
+        | BytecodeInstruction::IfIcmpGe { offset }
+        | BytecodeInstruction::IfIcmpGt { offset }
+        | BytecodeInstruction::IfIcmpLe { offset }
+        | BytecodeInstruction::IfEq { offset }
+        | BytecodeInstruction::IfNe { offset }
+        | BytecodeInstruction::IfLt { offset }
+        | BytecodeInstruction::IfGe { offset }
+        | BytecodeInstruction::IfGt { offset }
+        | BytecodeInstruction::IfLe { offset }
+        | BytecodeInstruction::IfNull { offset }
+        | BytecodeInstruction::IfNonNull { offset }
+        | BytecodeInstruction::GoTo { offset }
+        | BytecodeInstruction::Jsr { offset } => short(offset),
+        BytecodeInstruction::GoToW { offset } | BytecodeInstruction::JsrW { offset } => {
+            *offset = f((*offset).into())
+                .try_into()
+                .expect("Branch offset does not fit in 32 bits.");
+        }
+        BytecodeInstruction::TableSwitch {
+            default, offsets, ..
+        } => {
+            for offset in std::iter::once(default).chain(offsets.iter_mut()) {
+                *offset = f((*offset).into()).try_into().unwrap();
+            }
+        }
+        BytecodeInstruction::LookupSwitch { default, pairs, .. } => {
+            for offset in std::iter::once(default).chain(pairs.iter_mut().map(|p| &mut p.offset)) {
+                *offset = f((*offset).into()).try_into().unwrap();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns the absolute bytecode offset of each frame of a StackMapTable.
+fn frame_offsets(frames: &[StackMapFrame]) -> Vec<u32> {
+    let mut offsets: Vec<u32> = Vec::with_capacity(frames.len());
+    for (i, frame) in frames.iter().enumerate() {
+        let delta: u32 = frame_offset_delta(frame).into();
+        offsets.push(if i == 0 {
+            delta
+        } else {
+            offsets[i - 1] + delta + 1
+        });
+    }
+    offsets
+}
+
+fn frame_offset_delta(frame: &StackMapFrame) -> u16 {
+    match frame {
+        StackMapFrame::SameFrame { frame_type } => (*frame_type).into(),
+        StackMapFrame::SameLocals1StackItemFrame { frame_type, .. } => (*frame_type - 64).into(),
+        StackMapFrame::SameLocals1StackItemFrameExtended { offset_delta, .. }
+        | StackMapFrame::ChopFrame { offset_delta, .. }
+        | StackMapFrame::SameFrameExtended { offset_delta }
+        | StackMapFrame::AppendFrame { offset_delta, .. }
+        | StackMapFrame::FullFrame { offset_delta, .. } => *offset_delta,
+    }
+}
+
+/// Returns the same frame with a different offset delta, switching between the short and extended forms if needed.
+fn with_offset_delta(frame: StackMapFrame, delta: u16) -> StackMapFrame {
+    match frame {
+        StackMapFrame::SameFrame { .. } | StackMapFrame::SameFrameExtended { .. }
+            if delta <= 63 =>
+        {
+            StackMapFrame::SameFrame {
+                frame_type: delta as u8,
+            }
+        }
+        StackMapFrame::SameFrame { .. } | StackMapFrame::SameFrameExtended { .. } => {
+            StackMapFrame::SameFrameExtended {
+                offset_delta: delta,
+            }
+        }
+        StackMapFrame::SameLocals1StackItemFrame { stack, .. }
+        | StackMapFrame::SameLocals1StackItemFrameExtended { stack, .. }
+            if delta <= 63 =>
+        {
+            StackMapFrame::SameLocals1StackItemFrame {
+                frame_type: 64 + delta as u8,
+                stack,
+            }
+        }
+        StackMapFrame::SameLocals1StackItemFrame { stack, .. }
+        | StackMapFrame::SameLocals1StackItemFrameExtended { stack, .. } => {
+            StackMapFrame::SameLocals1StackItemFrameExtended {
+                offset_delta: delta,
+                stack,
+            }
+        }
+        StackMapFrame::ChopFrame { frame_type, .. } => StackMapFrame::ChopFrame {
+            frame_type,
+            offset_delta: delta,
+        },
+        StackMapFrame::AppendFrame {
+            frame_type, locals, ..
+        } => StackMapFrame::AppendFrame {
+            frame_type,
+            offset_delta: delta,
+            locals,
+        },
+        StackMapFrame::FullFrame { locals, stack, .. } => StackMapFrame::FullFrame {
+            offset_delta: delta,
+            locals,
+            stack,
+        },
+    }
+}
+
+/// Returns true if the frame describes the same locals as the previous one.
+fn keeps_locals(frame: &StackMapFrame) -> bool {
+    matches!(
+        frame,
+        StackMapFrame::SameFrame { .. }
+            | StackMapFrame::SameFrameExtended { .. }
+            | StackMapFrame::SameLocals1StackItemFrame { .. }
+            | StackMapFrame::SameLocals1StackItemFrameExtended { .. }
+    )
+}
+
+fn remap_uninitialized(frame: &mut StackMapFrame, remap: &dyn Fn(u32) -> u32) {
+    let types: Vec<&mut VerificationTypeInfo> = match frame {
+        StackMapFrame::SameFrame { .. }
+        | StackMapFrame::SameFrameExtended { .. }
+        | StackMapFrame::ChopFrame { .. } => Vec::new(),
+        StackMapFrame::SameLocals1StackItemFrame { stack, .. }
+        | StackMapFrame::SameLocals1StackItemFrameExtended { stack, .. } => vec![stack],
+        StackMapFrame::AppendFrame { locals, .. } => locals.iter_mut().collect(),
+        StackMapFrame::FullFrame { locals, stack, .. } => {
+            locals.iter_mut().chain(stack.iter_mut()).collect()
+        }
+    };
+    for vti in types {
+        if let VerificationTypeInfo::UninitializedVariable { offset } = vti {
+            *offset = remap((*offset).into()).try_into().unwrap();
+        }
+    }
+}
+
+/**
+ * Removes the instructions in the bytecode range `[start, end)` from the given Code attribute, moving every following
+ * instruction back and fixing all the offsets which refer to them: jumps, exception table, line numbers, local
+ * variable ranges and stack map frames. Switch paddings are recomputed as well.
+ *
+ * The removed range must behave like a statement: nothing outside of it can jump strictly inside it and it must not
+ * change the local variables seen by the following code. Returns false, leaving the attribute untouched, if that is
+ * not the case.
+ */
+pub fn remove_code_range(code_attribute: &mut AttributeInfo, start: u32, end: u32) -> bool {
+    let AttributeInfo::Code {
+        code,
+        exception_table,
+        attributes,
+        ..
+    } = code_attribute
+    else {
+        panic!("Expected a Code attribute.");
+    };
+
+    let code_length: u32 = code
+        .last()
+        .map(|(p, i)| p + get_instruction_length(i))
+        .unwrap_or(0);
+    let boundaries: HashSet<u32> = code
+        .iter()
+        .map(|(p, _)| *p)
+        .chain(std::iter::once(code_length))
+        .collect();
+    if start >= end || !boundaries.contains(&start) || !boundaries.contains(&end) {
+        return false;
+    }
+    let is_inside = |offset: u32| start < offset && offset < end;
+
+    let jumps_inside: bool = code
+        .iter()
+        .filter(|(p, _)| *p < start || *p >= end)
+        .any(|(p, i)| branch_targets(*p, i).into_iter().any(is_inside));
+    let handler_inside: bool = exception_table
+        .iter()
+        .any(|e| (start..end).contains(&u32::from(e.handler_pc)));
+    if jumps_inside || handler_inside {
+        return false;
+    }
+    for attribute in attributes.iter() {
+        if let AttributeInfo::StackMapTable {
+            stack_map_table, ..
+        } = attribute
+        {
+            let offsets: Vec<u32> = frame_offsets(stack_map_table);
+            let changes_locals: bool = stack_map_table
+                .iter()
+                .zip(offsets.iter())
+                .any(|(frame, offset)| (start..end).contains(offset) && !keeps_locals(frame));
+            if changes_locals {
+                return false;
+            }
+        }
+    }
+
+    // lay out the remaining instructions
+    let removed: u32 = end - start;
+    let mut new_positions: BTreeMap<u32, u32> = BTreeMap::new();
+    let mut kept: Vec<(u32, BytecodeInstruction)> = Vec::with_capacity(code.len());
+    let mut position: u32 = 0;
+    for (old_position, instruction) in code.iter() {
+        if (start..end).contains(old_position) {
+            continue;
+        }
+        let mut instruction: BytecodeInstruction = instruction.clone();
+        if let BytecodeInstruction::TableSwitch {
+            num_padding_bytes, ..
+        }
+        | BytecodeInstruction::LookupSwitch {
+            num_padding_bytes, ..
+        } = &mut instruction
+        {
+            *num_padding_bytes = ((4 - (position + 1) % 4) % 4) as u8;
+        }
+        new_positions.insert(*old_position, position);
+        position += get_instruction_length(&instruction);
+        kept.push((*old_position, instruction));
+    }
+    new_positions.insert(code_length, position);
+    let remap = |old: u32| -> u32 {
+        if (start..end).contains(&old) {
+            new_positions[&end]
+        } else {
+            *new_positions.get(&old).unwrap_or_else(|| {
+                panic!("Offset {old} is not an instruction boundary (removed {removed} bytes).")
+            })
+        }
+    };
+
+    *code = kept
+        .into_iter()
+        .map(|(old_position, mut instruction)| {
+            let new_position: u32 = remap(old_position);
+            for_each_branch_offset(&mut instruction, &mut |offset| {
+                i64::from(remap((i64::from(old_position) + offset) as u32))
+                    - i64::from(new_position)
+            });
+            (new_position, instruction)
+        })
+        .collect();
+
+    let remap_u16 = |old: u16| -> u16 { remap(old.into()).try_into().unwrap() };
+    for entry in exception_table.iter_mut() {
+        entry.start_pc = remap_u16(entry.start_pc);
+        entry.end_pc = remap_u16(entry.end_pc);
+        entry.handler_pc = remap_u16(entry.handler_pc);
+    }
+    exception_table.retain(|e| e.start_pc < e.end_pc);
+
+    for attribute in attributes.iter_mut() {
+        match attribute {
+            AttributeInfo::LineNumberTable {
+                line_number_table, ..
+            } => {
+                line_number_table.retain(|e| !(start..end).contains(&u32::from(e.start_pc)));
+                for entry in line_number_table.iter_mut() {
+                    entry.start_pc = remap_u16(entry.start_pc);
+                }
+            }
+            AttributeInfo::LocalVariableTable {
+                local_variable_table,
+                ..
+            } => {
+                for entry in local_variable_table.iter_mut() {
+                    let new_end: u16 = remap_u16(entry.start_pc + entry.length);
+                    entry.start_pc = remap_u16(entry.start_pc);
+                    entry.length = new_end - entry.start_pc;
+                }
+                local_variable_table.retain(|e| e.length > 0);
+            }
+            AttributeInfo::LocalVariableTypeTable {
+                local_variable_type_table,
+                ..
+            } => {
+                for entry in local_variable_type_table.iter_mut() {
+                    let new_end: u16 = remap_u16(entry.start_pc + entry.length);
+                    entry.start_pc = remap_u16(entry.start_pc);
+                    entry.length = new_end - entry.start_pc;
+                }
+                local_variable_type_table.retain(|e| e.length > 0);
+            }
+            AttributeInfo::StackMapTable {
+                stack_map_table, ..
+            } => {
+                let offsets: Vec<u32> = frame_offsets(stack_map_table);
+                let has_frame_at_end: bool = offsets.contains(&end);
+                let mut frames: Vec<(u32, StackMapFrame)> = Vec::with_capacity(offsets.len());
+                for (offset, mut frame) in offsets.into_iter().zip(stack_map_table.drain(..)) {
+                    // frames inside the range keep the same locals, so the one at the start can take the place of
+                    // the one at the end only if there is none
+                    if is_inside(offset) || (offset == start && has_frame_at_end) {
+                        continue;
+                    }
+                    remap_uninitialized(&mut frame, &remap);
+                    frames.push((remap(offset), frame));
+                }
+                let mut previous: Option<u32> = None;
+                for (offset, frame) in frames {
+                    let delta: u32 = match previous {
+                        None => offset,
+                        Some(p) => offset - p - 1,
+                    };
+                    stack_map_table.push(with_offset_delta(frame, delta.try_into().unwrap()));
+                    previous = Some(offset);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    true
+}
+
+/**
+ * Replaces each instruction of the given Code attribute for which `replacement` returns some instructions with them,
+ * moving every following instruction and fixing all the offsets which refer to them: jumps, exception table, line
+ * numbers, local variable ranges and stack map frames. Everything which referred to a replaced instruction refers to
+ * the first of its replacements.
+ *
+ * The replacements must not contain jumps and must leave the same values on the stack as the replaced instruction.
+ * Returns false, leaving the attribute untouched, if a jump or the code would become too long.
+ */
+pub fn replace_instructions(
+    code_attribute: &mut AttributeInfo,
+    replacement: &mut dyn FnMut(u32, &BytecodeInstruction) -> Option<Vec<BytecodeInstruction>>,
+) -> bool {
+    let AttributeInfo::Code {
+        code,
+        exception_table,
+        attributes,
+        ..
+    } = code_attribute
+    else {
+        panic!("Expected a Code attribute.");
+    };
+
+    let code_length: u32 = code
+        .last()
+        .map(|(p, i)| p + get_instruction_length(i))
+        .unwrap_or(0);
+
+    // lay out the new instructions, each one with the position of the instruction it comes from
+    let mut new_positions: BTreeMap<u32, u32> = BTreeMap::new();
+    let mut new_code: Vec<(u32, u32, BytecodeInstruction)> = Vec::with_capacity(code.len());
+    let mut position: u32 = 0;
+    for (old_position, instruction) in code.iter() {
+        new_positions.insert(*old_position, position);
+        let instructions: Vec<BytecodeInstruction> =
+            replacement(*old_position, instruction).unwrap_or_else(|| vec![instruction.clone()]);
+        for mut instruction in instructions {
+            if let BytecodeInstruction::TableSwitch {
+                num_padding_bytes, ..
+            }
+            | BytecodeInstruction::LookupSwitch {
+                num_padding_bytes, ..
+            } = &mut instruction
+            {
+                *num_padding_bytes = ((4 - (position + 1) % 4) % 4) as u8;
+            }
+            let length: u32 = get_instruction_length(&instruction);
+            new_code.push((*old_position, position, instruction));
+            position += length;
+        }
+    }
+    new_positions.insert(code_length, position);
+    if position > u32::from(u16::MAX) {
+        return false;
+    }
+    let remap = |old: u32| -> u32 {
+        *new_positions
+            .get(&old)
+            .unwrap_or_else(|| panic!("Offset {old} is not an instruction boundary."))
+    };
+
+    // the 16-bit jumps must still reach their targets
+    let fits: bool = new_code
+        .iter()
+        .all(|(old_position, new_position, instruction)| {
+            let is_wide: bool = matches!(
+                instruction,
+                BytecodeInstruction::GoToW { .. }
+                    | BytecodeInstruction::JsrW { .. }
+                    | BytecodeInstruction::TableSwitch { .. }
+                    | BytecodeInstruction::LookupSwitch { .. }
+            );
+            is_wide
+                || branch_targets(*old_position, instruction)
+                    .into_iter()
+                    .all(|target| {
+                        i16::try_from(i64::from(remap(target)) - i64::from(*new_position)).is_ok()
+                    })
+        });
+    if !fits {
+        return false;
+    }
+
+    *code = new_code
+        .into_iter()
+        .map(|(old_position, new_position, mut instruction)| {
+            for_each_branch_offset(&mut instruction, &mut |offset| {
+                i64::from(remap((i64::from(old_position) + offset) as u32))
+                    - i64::from(new_position)
+            });
+            (new_position, instruction)
+        })
+        .collect();
+
+    let remap_u16 = |old: u16| -> u16 { remap(old.into()).try_into().unwrap() };
+    for entry in exception_table.iter_mut() {
+        entry.start_pc = remap_u16(entry.start_pc);
+        entry.end_pc = remap_u16(entry.end_pc);
+        entry.handler_pc = remap_u16(entry.handler_pc);
+    }
+
+    for attribute in attributes.iter_mut() {
+        match attribute {
+            AttributeInfo::LineNumberTable {
+                line_number_table, ..
+            } => {
+                for entry in line_number_table.iter_mut() {
+                    entry.start_pc = remap_u16(entry.start_pc);
+                }
+            }
+            AttributeInfo::LocalVariableTable {
+                local_variable_table,
+                ..
+            } => {
+                for entry in local_variable_table.iter_mut() {
+                    let new_end: u16 = remap_u16(entry.start_pc + entry.length);
+                    entry.start_pc = remap_u16(entry.start_pc);
+                    entry.length = new_end - entry.start_pc;
+                }
+            }
+            AttributeInfo::LocalVariableTypeTable {
+                local_variable_type_table,
+                ..
+            } => {
+                for entry in local_variable_type_table.iter_mut() {
+                    let new_end: u16 = remap_u16(entry.start_pc + entry.length);
+                    entry.start_pc = remap_u16(entry.start_pc);
+                    entry.length = new_end - entry.start_pc;
+                }
+            }
+            AttributeInfo::StackMapTable {
+                stack_map_table, ..
+            } => {
+                let offsets: Vec<u32> = frame_offsets(stack_map_table);
+                let mut previous: Option<u32> = None;
+                for (offset, frame) in offsets.into_iter().zip(stack_map_table.iter_mut()) {
+                    let offset: u32 = remap(offset);
+                    let delta: u32 = match previous {
+                        None => offset,
+                        Some(p) => offset - p - 1,
+                    };
+                    remap_uninitialized(frame, &remap);
+                    *frame = with_offset_delta(frame.clone(), delta.try_into().unwrap());
+                    previous = Some(offset);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code_attribute(code: Vec<(u32, BytecodeInstruction)>) -> AttributeInfo {
+        AttributeInfo::Code {
+            name_index: 1,
+            max_stack: 1,
+            max_locals: 0,
+            code,
+            trailing_bytes: Vec::new(),
+            exception_table: Vec::new(),
+            attributes: Vec::new(),
+        }
+    }
+
+    fn sample() -> AttributeInfo {
+        code_attribute(vec![
+            (0, BytecodeInstruction::IConst { constant: 0 }),
+            (1, BytecodeInstruction::IfEq { offset: 7 }),
+            (4, BytecodeInstruction::IConst { constant: 1 }),
+            (5, BytecodeInstruction::Pop {}),
+            (6, BytecodeInstruction::IConst { constant: 2 }),
+            (7, BytecodeInstruction::Pop {}),
+            (8, BytecodeInstruction::Return {}),
+        ])
+    }
+
+    #[test]
+    fn jumps_over_removed_range_are_shortened() {
+        let mut attribute: AttributeInfo = sample();
+        assert!(remove_code_range(&mut attribute, 4, 6));
+        let AttributeInfo::Code { code, .. } = attribute else {
+            unreachable!()
+        };
+        let positions: Vec<u32> = code.iter().map(|(p, _)| *p).collect();
+        assert_eq!(positions, vec![0, 1, 4, 5, 6]);
+        assert!(matches!(code[1].1, BytecodeInstruction::IfEq { offset: 5 }));
+    }
+
+    #[test]
+    fn replaced_instructions_move_the_following_ones() {
+        let mut attribute: AttributeInfo = sample();
+        assert!(replace_instructions(&mut attribute, &mut |position, _| {
+            (position == 4).then(|| {
+                vec![
+                    BytecodeInstruction::IConst { constant: 1 },
+                    BytecodeInstruction::INeg {},
+                ]
+            })
+        }));
+        let AttributeInfo::Code { code, .. } = attribute else {
+            unreachable!()
+        };
+        let positions: Vec<u32> = code.iter().map(|(p, _)| *p).collect();
+        assert_eq!(positions, vec![0, 1, 4, 5, 6, 7, 8, 9]);
+        assert!(matches!(code[1].1, BytecodeInstruction::IfEq { offset: 8 }));
+        assert!(matches!(code[3].1, BytecodeInstruction::INeg {}));
+    }
+
+    #[test]
+    fn too_long_jump_is_refused() {
+        let mut attribute: AttributeInfo = sample();
+        assert!(!replace_instructions(&mut attribute, &mut |position, _| {
+            (position == 4).then(|| vec![BytecodeInstruction::Nop {}; 40000])
+        }));
+        let AttributeInfo::Code { code, .. } = attribute else {
+            unreachable!()
+        };
+        assert_eq!(code.len(), 7);
+    }
+
+    #[test]
+    fn jump_into_range_is_refused() {
+        let mut attribute: AttributeInfo = sample();
+        assert!(!remove_code_range(&mut attribute, 7, 9));
+        assert!(!remove_code_range(&mut attribute, 4, 10));
+        let AttributeInfo::Code { code, .. } = attribute else {
+            unreachable!()
+        };
+        assert_eq!(code.len(), 7);
+    }
+}
@@ -1,17 +1,38 @@
-use std::ops::Index;
+use std::{collections::HashMap, ops::Index};
 
 use binary_reader::BinaryReader;
 
 use crate::{
     attributes::{AttributeInfo, AttributeKind, find_attribute},
+    classfile::ClassFileError,
     reference_kind::ReferenceKind,
+    remapping::remap_constant_pool_entry,
 };
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConstantPool {
     pub(crate) entries: Vec<ConstantPoolInfo>,
 }
 
+fn is_binary_name(name: &str) -> bool {
+    let is_identifier_start = |c: char| c.is_alphabetic() || c == '$' || c == '_';
+    !name.is_empty()
+        && name.split('/').all(|identifier| {
+            identifier.starts_with(is_identifier_start)
+                && identifier
+                    .chars()
+                    .all(|c| is_identifier_start(c) || c.is_numeric())
+        })
+}
+
+/// The number of usable entries of a constant pool, since its count (which is one more) is stored in a u16.
+pub const MAX_CONSTANT_POOL_ENTRIES: usize = 65534;
+
+/// The error returned when adding an entry to a constant pool with not enough slots left for it.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ConstantPoolFull;
+
 impl ConstantPool {
     pub fn get_class_name(&self, cp_index: u16) -> String {
         let class_entry: &ConstantPoolInfo = &self[cp_index - 1];
@@ -21,6 +42,22 @@ impl ConstantPool {
         }
     }
 
+    /// Returns the name of the given Module entry, like `java.base`.
+    pub fn get_module_name(&self, cp_index: u16) -> String {
+        match &self[cp_index - 1] {
+            ConstantPoolInfo::Module { name_index } => self.get_utf8_content(*name_index),
+            _ => panic!("Expected entry #{cp_index} to be of Module type but it wasn't."),
+        }
+    }
+
+    /// Returns the internal name of the given Package entry, like `java/lang`.
+    pub fn get_package_name(&self, cp_index: u16) -> String {
+        match &self[cp_index - 1] {
+            ConstantPoolInfo::Package { name_index } => self.get_utf8_content(*name_index),
+            _ => panic!("Expected entry #{cp_index} to be of Package type but it wasn't."),
+        }
+    }
+
     pub fn get_method_ref(&self, cp_index: u16) -> String {
         let method_ref_entry: &ConstantPoolInfo = &self[cp_index - 1];
         match method_ref_entry {
@@ -92,6 +129,28 @@ impl ConstantPool {
             + &self.get_name_and_type(name_and_type_index)
     }
 
+    pub fn get_dynamic(&self, cp_index: u16) -> String {
+        let dynamic_entry: &ConstantPoolInfo = &self[cp_index - 1];
+        match dynamic_entry {
+            ConstantPoolInfo::Dynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            } => self.get_dynamic_string(*bootstrap_method_attr_index, *name_and_type_index),
+            _ => panic!("Expected entry #{cp_index} to be of Dynamic type but it wasn't."),
+        }
+    }
+
+    pub fn get_dynamic_string(
+        &self,
+        bootstrap_method_attr_index: u16,
+        name_and_type_index: u16,
+    ) -> String {
+        "#".to_owned()
+            + &bootstrap_method_attr_index.to_string()
+            + ":"
+            + &self.get_name_and_type(name_and_type_index)
+    }
+
     pub fn get_name_and_type(&self, cp_index: u16) -> String {
         let name_and_type_entry: &ConstantPoolInfo = &self[cp_index - 1];
         match name_and_type_entry {
@@ -112,13 +171,49 @@ impl ConstantPool {
         }
     }
 
+    pub fn get_integer(&self, cp_index: u16) -> i32 {
+        match &self[cp_index - 1] {
+            ConstantPoolInfo::Integer { bytes } => *bytes as i32,
+            _ => panic!("Expected entry #{cp_index} to be of Integer type but it wasn't."),
+        }
+    }
+
+    pub fn get_float(&self, cp_index: u16) -> f32 {
+        match &self[cp_index - 1] {
+            ConstantPoolInfo::Float { bytes } => f32::from_bits(*bytes),
+            _ => panic!("Expected entry #{cp_index} to be of Float type but it wasn't."),
+        }
+    }
+
+    pub fn get_long(&self, cp_index: u16) -> i64 {
+        match &self[cp_index - 1] {
+            ConstantPoolInfo::Long {
+                high_bytes,
+                low_bytes,
+            } => ((u64::from(*high_bytes) << 32) | u64::from(*low_bytes)) as i64,
+            _ => panic!("Expected entry #{cp_index} to be of Long type but it wasn't."),
+        }
+    }
+
+    pub fn get_double(&self, cp_index: u16) -> f64 {
+        match &self[cp_index - 1] {
+            ConstantPoolInfo::Double {
+                high_bytes,
+                low_bytes,
+            } => f64::from_bits((u64::from(*high_bytes) << 32) | u64::from(*low_bytes)),
+            _ => panic!("Expected entry #{cp_index} to be of Double type but it wasn't."),
+        }
+    }
+
     // FIXME: find a better name
+    /// Returns the content of the given Utf8 entry, quoted like `javap` does unless it is a valid binary name made of
+    /// identifiers separated by `/`, like `java/lang/Object`.
     pub fn get_wrapped_utf8_content(&self, cp_index: u16) -> String {
         let content = self.get_utf8_content(cp_index);
-        if content.starts_with('[') {
-            "\"".to_owned() + &content + "\""
-        } else {
+        if is_binary_name(&content) {
             content
+        } else {
+            "\"".to_owned() + &content + "\""
         }
     }
 
@@ -130,6 +225,84 @@ impl ConstantPool {
         }
     }
 
+    /**
+     * Appends the given entry at the end of the constant pool and returns its (1-based) index. Fails without changing
+     * the constant pool if there is not enough room left for the entry (two slots for Long and Double entries).
+     */
+    pub fn add(&mut self, entry: ConstantPoolInfo) -> Result<u16, ConstantPoolFull> {
+        let is_wide: bool = matches!(
+            entry,
+            ConstantPoolInfo::Long { .. } | ConstantPoolInfo::Double { .. }
+        );
+        if self.remaining_capacity() < 1 + u16::from(is_wide) {
+            return Err(ConstantPoolFull);
+        }
+        self.entries.push(entry);
+        let index: u16 = self.entries.len().try_into().unwrap();
+        if is_wide {
+            self.entries.push(ConstantPoolInfo::Null {});
+        }
+        Ok(index)
+    }
+
+    /// Returns the number of slots which can still be added before reaching the limit of the class file format.
+    pub fn remaining_capacity(&self) -> u16 {
+        (MAX_CONSTANT_POOL_ENTRIES - self.entries.len()) as u16
+    }
+
+    /**
+     * Removes the entries which cannot be reached from the given (1-based) indices, either directly or through other
+     * entries, and returns the old-to-new remapping of the kept entries, which stay in the same order. The references
+     * between the kept entries are rewritten, while the ones from outside of the constant pool are left to the caller:
+     * the constant pool alone does not know which of its entries the rest of the class refers to, so they have to be
+     * given as `roots`.
+     */
+    pub fn compact(&mut self, roots: &[u16]) -> HashMap<u16, u16> {
+        let mut to_visit: Vec<u16> = roots.to_vec();
+        let mut used: Vec<bool> = vec![false; self.entries.len()];
+        while let Some(idx) = to_visit.pop() {
+            if !used[usize::from(idx) - 1] {
+                used[usize::from(idx) - 1] = true;
+                remap_constant_pool_entry(
+                    &mut self.entries[usize::from(idx) - 1].clone(),
+                    &mut |idx| {
+                        to_visit.push(idx);
+                        idx
+                    },
+                );
+            }
+        }
+
+        let mut remapping: HashMap<u16, u16> = HashMap::new();
+        let mut next_index: u16 = 1;
+        for (i, entry) in self.entries.iter().enumerate() {
+            if used[i] {
+                remapping.insert((i + 1) as u16, next_index);
+                next_index += match entry {
+                    ConstantPoolInfo::Long { .. } | ConstantPoolInfo::Double { .. } => 2,
+                    _ => 1,
+                };
+            }
+        }
+
+        let old_entries: Vec<ConstantPoolInfo> = std::mem::take(&mut self.entries);
+        for (i, mut entry) in old_entries.into_iter().enumerate() {
+            if !used[i] {
+                continue;
+            }
+            remap_constant_pool_entry(&mut entry, &mut |idx| remapping[&idx]);
+            let is_wide: bool = matches!(
+                entry,
+                ConstantPoolInfo::Long { .. } | ConstantPoolInfo::Double { .. }
+            );
+            self.entries.push(entry);
+            if is_wide {
+                self.entries.push(ConstantPoolInfo::Null {});
+            }
+        }
+        remapping
+    }
+
     pub fn len(&self) -> usize {
         self.entries.len()
     }
@@ -147,21 +320,116 @@ impl Index<u16> for ConstantPool {
     }
 }
 
+/**
+ * Decodes the raw content of a Utf8 entry, without any escaping. Class files use a modified UTF-8: the null
+ * character takes two bytes (0xc0 0x80) and supplementary characters are written as two three-byte surrogates, so the
+ * bytes are decoded into UTF-16 code units first. Unpaired surrogates are written as `\uXXXX` escapes.
+ * Reference available at <https://docs.oracle.com/javase/specs/jvms/se25/html/jvms-4.html#jvms-4.4.7>
+ */
 pub fn convert_utf8(utf8_bytes: &[u8]) -> String {
-    String::from_utf8(utf8_bytes.to_vec())
-        .unwrap()
+    let continuation = |i: usize| -> u16 {
+        let byte: u8 = *utf8_bytes
+            .get(i)
+            .expect("Truncated modified UTF-8 sequence.");
+        assert!(
+            byte & 0xc0 == 0x80,
+            "Expected a continuation byte at offset {i} but was 0x{byte:02x}."
+        );
+        u16::from(byte & 0x3f)
+    };
+
+    let mut units: Vec<u16> = Vec::with_capacity(utf8_bytes.len());
+    let mut i: usize = 0;
+    while i < utf8_bytes.len() {
+        let first: u16 = utf8_bytes[i].into();
+        if first & 0x80 == 0 {
+            units.push(first);
+            i += 1;
+        } else if first & 0xe0 == 0xc0 {
+            units.push(((first & 0x1f) << 6) | continuation(i + 1));
+            i += 2;
+        } else if first & 0xf0 == 0xe0 {
+            units.push(((first & 0x0f) << 12) | (continuation(i + 1) << 6) | continuation(i + 2));
+            i += 3;
+        } else {
+            panic!("Invalid modified UTF-8 byte 0x{first:02x} at offset {i}.");
+        }
+    }
+    // Java strings may hold unpaired surrogates, which have no Rust equivalent
+    char::decode_utf16(units)
+        .map(|c| match c {
+            Ok(c) => c.to_string(),
+            Err(error) => format!("\\u{:04x}", error.unpaired_surrogate()),
+        })
+        .collect()
+}
+
+/// Escapes the given string constant the way `javap` displays it.
+pub fn escape_for_display(content: &str) -> String {
+    content
         .replace("\n", "\\n")
         .replace("'", "\\'")
+        .replace("\"", "\\\"")
+        .replace("\u{0000}", "\\u0000")
         .replace("\u{0001}", "\\u0001")
 }
 
-#[derive(Clone)]
+/**
+ * Serializes the bytes of a Utf8 entry as a string when they are also valid standard UTF-8, which is the case for
+ * almost every entry, and as a sequence of bytes otherwise.
+ */
+#[cfg(feature = "serde")]
+mod utf8_bytes {
+    use std::fmt;
+
+    use serde::de::{SeqAccess, Visitor};
+    use serde::{Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        match std::str::from_utf8(bytes) {
+            Ok(content) => serializer.serialize_str(content),
+            Err(_) => serializer.collect_seq(bytes),
+        }
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<u8>, D::Error> {
+        struct BytesVisitor;
+
+        impl<'de> Visitor<'de> for BytesVisitor {
+            type Value = Vec<u8>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a string or a sequence of bytes")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, content: &str) -> Result<Self::Value, E> {
+                Ok(content.as_bytes().to_vec())
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut bytes: Vec<u8> = Vec::new();
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                }
+                Ok(bytes)
+            }
+        }
+
+        deserializer.deserialize_any(BytesVisitor)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ConstantPoolInfo {
     /**
      * The type of constant pool entry which can be found right after a Long or Double one.
      */
     Null {},
     Utf8 {
+        #[cfg_attr(feature = "serde", serde(with = "utf8_bytes"))]
         bytes: Vec<u8>,
     },
     Integer {
@@ -207,10 +475,20 @@ pub enum ConstantPoolInfo {
         reference_kind: ReferenceKind,
         reference_index: u16,
     },
+    Dynamic {
+        bootstrap_method_attr_index: u16,
+        name_and_type_index: u16,
+    },
     InvokeDynamic {
         bootstrap_method_attr_index: u16,
         name_and_type_index: u16,
     },
+    Module {
+        name_index: u16,
+    },
+    Package {
+        name_index: u16,
+    },
 }
 
 impl ConstantPoolInfo {
@@ -230,7 +508,10 @@ impl ConstantPoolInfo {
             ConstantPoolInfo::NameAndType { .. } => ConstantPoolTag::NameAndType,
             ConstantPoolInfo::MethodHandle { .. } => ConstantPoolTag::MethodHandle,
             ConstantPoolInfo::MethodType { .. } => ConstantPoolTag::MethodType,
+            ConstantPoolInfo::Dynamic { .. } => ConstantPoolTag::Dynamic,
             ConstantPoolInfo::InvokeDynamic { .. } => ConstantPoolTag::InvokeDynamic,
+            ConstantPoolInfo::Module { .. } => ConstantPoolTag::Module,
+            ConstantPoolInfo::Package { .. } => ConstantPoolTag::Package,
         }
     }
 }
@@ -316,12 +597,22 @@ impl std::fmt::Display for ConstantPoolTag {
     }
 }
 
-pub fn parse_constant_pool(reader: &mut BinaryReader, cp_count: usize) -> ConstantPool {
+pub fn parse_constant_pool(
+    reader: &mut BinaryReader,
+    cp_count: usize,
+) -> Result<ConstantPool, ClassFileError> {
     let mut entries: Vec<ConstantPoolInfo> = Vec::with_capacity(cp_count);
     let mut i = 0;
     while i < cp_count {
-        let tag = ConstantPoolTag::try_from(reader.read_u8().unwrap()).unwrap();
-        entries.push(parse_constant_pool_entry(reader, tag.clone()));
+        let tag = ConstantPoolTag::try_from(reader.read_u8()?).map_err(
+            |UnknownConstantPoolTag(tag)| {
+                ClassFileError::new(format!(
+                    "Unknown constant pool tag {tag} at entry #{}.",
+                    i + 1
+                ))
+            },
+        )?;
+        entries.push(parse_constant_pool_entry(reader, tag.clone())?);
 
         if matches!(tag, ConstantPoolTag::Long) || matches!(tag, ConstantPoolTag::Double) {
             entries.push(ConstantPoolInfo::Null {});
@@ -329,66 +620,78 @@ pub fn parse_constant_pool(reader: &mut BinaryReader, cp_count: usize) -> Consta
         }
         i += 1;
     }
-    ConstantPool { entries }
+    Ok(ConstantPool { entries })
 }
 
-fn parse_constant_pool_entry(reader: &mut BinaryReader, tag: ConstantPoolTag) -> ConstantPoolInfo {
-    match tag {
+fn parse_constant_pool_entry(
+    reader: &mut BinaryReader,
+    tag: ConstantPoolTag,
+) -> std::io::Result<ConstantPoolInfo> {
+    Ok(match tag {
         ConstantPoolTag::Utf8 => {
-            let length: u16 = reader.read_u16().unwrap();
+            let length: u16 = reader.read_u16()?;
             ConstantPoolInfo::Utf8 {
-                bytes: reader.read_u8_vec(length.into()).unwrap(),
+                bytes: reader.read_u8_vec(length.into())?,
             }
         }
         ConstantPoolTag::Integer => ConstantPoolInfo::Integer {
-            bytes: reader.read_u32().unwrap(),
+            bytes: reader.read_u32()?,
         },
         ConstantPoolTag::Float => ConstantPoolInfo::Float {
-            bytes: reader.read_u32().unwrap(),
+            bytes: reader.read_u32()?,
         },
         ConstantPoolTag::Long => ConstantPoolInfo::Long {
-            high_bytes: reader.read_u32().unwrap(),
-            low_bytes: reader.read_u32().unwrap(),
+            high_bytes: reader.read_u32()?,
+            low_bytes: reader.read_u32()?,
         },
         ConstantPoolTag::Double => ConstantPoolInfo::Double {
-            high_bytes: reader.read_u32().unwrap(),
-            low_bytes: reader.read_u32().unwrap(),
+            high_bytes: reader.read_u32()?,
+            low_bytes: reader.read_u32()?,
         },
         ConstantPoolTag::String => ConstantPoolInfo::String {
-            string_index: reader.read_u16().unwrap(),
+            string_index: reader.read_u16()?,
         },
         ConstantPoolTag::Class => ConstantPoolInfo::Class {
-            name_index: reader.read_u16().unwrap(),
+            name_index: reader.read_u16()?,
         },
         ConstantPoolTag::Fieldref => ConstantPoolInfo::FieldRef {
-            class_index: reader.read_u16().unwrap(),
-            name_and_type_index: reader.read_u16().unwrap(),
+            class_index: reader.read_u16()?,
+            name_and_type_index: reader.read_u16()?,
         },
         ConstantPoolTag::Methodref => ConstantPoolInfo::MethodRef {
-            class_index: reader.read_u16().unwrap(),
-            name_and_type_index: reader.read_u16().unwrap(),
+            class_index: reader.read_u16()?,
+            name_and_type_index: reader.read_u16()?,
         },
         ConstantPoolTag::InterfaceMethodref => ConstantPoolInfo::InterfaceMethodRef {
-            class_index: reader.read_u16().unwrap(),
-            name_and_type_index: reader.read_u16().unwrap(),
+            class_index: reader.read_u16()?,
+            name_and_type_index: reader.read_u16()?,
         },
         ConstantPoolTag::NameAndType => ConstantPoolInfo::NameAndType {
-            name_index: reader.read_u16().unwrap(),
-            descriptor_index: reader.read_u16().unwrap(),
+            name_index: reader.read_u16()?,
+            descriptor_index: reader.read_u16()?,
         },
         ConstantPoolTag::MethodHandle => ConstantPoolInfo::MethodHandle {
-            reference_kind: ReferenceKind::from(reader.read_u8().unwrap()),
-            reference_index: reader.read_u16().unwrap(),
+            reference_kind: ReferenceKind::from(reader.read_u8()?),
+            reference_index: reader.read_u16()?,
         },
         ConstantPoolTag::MethodType => ConstantPoolInfo::MethodType {
-            descriptor_index: reader.read_u16().unwrap(),
+            descriptor_index: reader.read_u16()?,
+        },
+        ConstantPoolTag::Dynamic => ConstantPoolInfo::Dynamic {
+            bootstrap_method_attr_index: reader.read_u16()?,
+            name_and_type_index: reader.read_u16()?,
         },
         ConstantPoolTag::InvokeDynamic => ConstantPoolInfo::InvokeDynamic {
-            bootstrap_method_attr_index: reader.read_u16().unwrap(),
-            name_and_type_index: reader.read_u16().unwrap(),
+            bootstrap_method_attr_index: reader.read_u16()?,
+            name_and_type_index: reader.read_u16()?,
         },
-        _ => panic!("Unknown constant pool tag {tag:?}."),
-    }
+        ConstantPoolTag::Module => ConstantPoolInfo::Module {
+            name_index: reader.read_u16()?,
+        },
+        ConstantPoolTag::Package => ConstantPoolInfo::Package {
+            name_index: reader.read_u16()?,
+        },
+    })
 }
 
 // TODO: find a better name
@@ -443,7 +746,9 @@ pub(crate) fn check_constant_pool(cp: &ConstantPool, attributes: &[AttributeInfo
             ConstantPoolInfo::String { string_index } => {
                 assert_valid_and_type!(cp, *string_index, ConstantPoolTag::Utf8);
             }
-            ConstantPoolInfo::Class { name_index } => {
+            ConstantPoolInfo::Class { name_index }
+            | ConstantPoolInfo::Module { name_index }
+            | ConstantPoolInfo::Package { name_index } => {
                 assert_valid_and_type!(cp, *name_index, ConstantPoolTag::Utf8);
             }
             ConstantPoolInfo::FieldRef {
@@ -506,12 +811,16 @@ pub(crate) fn check_constant_pool(cp: &ConstantPool, attributes: &[AttributeInfo
                     );
                 }
             },
-            ConstantPoolInfo::InvokeDynamic {
+            ConstantPoolInfo::Dynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            }
+            | ConstantPoolInfo::InvokeDynamic {
                 bootstrap_method_attr_index,
                 name_and_type_index,
             } => {
                 let bootstrap_method_attribute =
-                    find_attribute(attributes,AttributeKind::BootstrapMethods).expect("The presence of an InvokeDynamic entry in the constant pool implies the presence of a BootstrapMethods attribute in the class file, which is not present.");
+                    find_attribute(attributes,AttributeKind::BootstrapMethods).unwrap_or_else(|| panic!("The presence of a {} entry in the constant pool implies the presence of a BootstrapMethods attribute in the class file, which is not present.", entry.tag()));
                 match bootstrap_method_attribute {
                     AttributeInfo::BootstrapMethods { methods, .. } => {
                         let num_bootstrap_methods = methods.len();
@@ -529,3 +838,284 @@ pub(crate) fn check_constant_pool(cp: &ConstantPool, attributes: &[AttributeInfo
         i += 1;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use binary_reader::Endianness;
+
+    use super::*;
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn utf8_serialization() {
+        let to_json = |bytes: &[u8]| -> String {
+            serde_json::to_string(&ConstantPoolInfo::Utf8 {
+                bytes: bytes.to_vec(),
+            })
+            .unwrap()
+        };
+        assert_eq!(to_json(b"<init>"), "{\"Utf8\":{\"bytes\":\"<init>\"}}");
+        // the modified UTF-8 encoding of U+0000 is not valid standard UTF-8
+        assert_eq!(
+            to_json(&[0x41, 0xc0, 0x80]),
+            "{\"Utf8\":{\"bytes\":[65,192,128]}}"
+        );
+        for bytes in [b"<init>".as_slice(), &[0x41, 0xc0, 0x80]] {
+            let entry: ConstantPoolInfo = serde_json::from_str(&to_json(bytes)).unwrap();
+            assert_eq!(
+                entry,
+                ConstantPoolInfo::Utf8 {
+                    bytes: bytes.to_vec()
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn newline_is_decoded_raw_and_displayed_escaped() {
+        let content: String = convert_utf8(b"first line\nit's \"quoted\"\x01");
+        assert_eq!(content, "first line\nit's \"quoted\"\u{0001}");
+        assert_eq!(
+            escape_for_display(&content),
+            "first line\\nit\\'s \\\"quoted\\\"\\u0001"
+        );
+    }
+
+    #[test]
+    fn binary_names() {
+        for name in [
+            "java/lang/Object",
+            "Sealed$Circle",
+            "SecuritySettings$1",
+            "_",
+        ] {
+            assert!(
+                is_binary_name(name),
+                "Expected '{name}' to be a binary name."
+            );
+        }
+        for name in ["", "[I", "module-info", "java.base", "a//b", "com/1st"] {
+            assert!(
+                !is_binary_name(name),
+                "Expected '{name}' not to be a binary name."
+            );
+        }
+    }
+
+    #[test]
+    fn integer_entry() {
+        let bytes: [u8; 5] = [0x03, 0xca, 0xfe, 0xba, 0xbe];
+        let cp: ConstantPool =
+            parse_constant_pool(&mut BinaryReader::new(&bytes, Endianness::Big), 1).unwrap();
+        assert_eq!(cp.len(), 1);
+        assert!(matches!(
+            cp[0],
+            ConstantPoolInfo::Integer { bytes: 0xcafebabe }
+        ));
+    }
+
+    #[test]
+    fn float_entry() {
+        let bytes: [u8; 5] = [0x04, 0x3f, 0xc0, 0x00, 0x00];
+        let cp: ConstantPool =
+            parse_constant_pool(&mut BinaryReader::new(&bytes, Endianness::Big), 1).unwrap();
+        let ConstantPoolInfo::Float { bytes } = cp[0] else {
+            panic!("Expected a Float entry.");
+        };
+        assert_eq!(f32::from_bits(bytes), 1.5);
+    }
+
+    #[test]
+    fn double_entry_is_followed_by_a_placeholder() {
+        let bytes: [u8; 14] = [
+            0x06, 0x40, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01,
+        ];
+        let cp: ConstantPool =
+            parse_constant_pool(&mut BinaryReader::new(&bytes, Endianness::Big), 3).unwrap();
+        assert_eq!(cp.len(), 3);
+        let ConstantPoolInfo::Double {
+            high_bytes,
+            low_bytes,
+        } = cp[0]
+        else {
+            panic!("Expected a Double entry.");
+        };
+        assert_eq!(
+            f64::from_bits((u64::from(high_bytes) << 32) | u64::from(low_bytes)),
+            2.5
+        );
+        assert!(matches!(cp[1], ConstantPoolInfo::Null {}));
+        assert!(matches!(cp[2], ConstantPoolInfo::Integer { bytes: 1 }));
+    }
+
+    #[test]
+    fn dynamic_entry() {
+        // #1 = Dynamic #0:#2, #2 = NameAndType #3:#4, #3 = Utf8 "PI", #4 = Utf8 "D"
+        let bytes: [u8; 19] = [
+            0x11, 0x00, 0x00, 0x00, 0x02, 0x0c, 0x00, 0x03, 0x00, 0x04, 0x01, 0x00, 0x02, b'P',
+            b'I', 0x01, 0x00, 0x01, b'D',
+        ];
+        let cp: ConstantPool =
+            parse_constant_pool(&mut BinaryReader::new(&bytes, Endianness::Big), 4).unwrap();
+        assert_eq!(cp[0].tag(), ConstantPoolTag::Dynamic);
+        assert_eq!(cp.get_dynamic(1), "#0:PI:D");
+    }
+
+    #[test]
+    fn module_and_package_entries() {
+        // #1 = Module #3, #2 = Package #4, #3 = Utf8 "m", #4 = Utf8 "p"
+        let bytes: [u8; 14] = [
+            0x13, 0x00, 0x03, 0x14, 0x00, 0x04, 0x01, 0x00, 0x01, b'm', 0x01, 0x00, 0x01, b'p',
+        ];
+        let cp: ConstantPool =
+            parse_constant_pool(&mut BinaryReader::new(&bytes, Endianness::Big), 4).unwrap();
+        assert!(matches!(cp[0], ConstantPoolInfo::Module { name_index: 3 }));
+        assert!(matches!(cp[1], ConstantPoolInfo::Package { name_index: 4 }));
+        assert_eq!(cp[0].tag(), ConstantPoolTag::Module);
+        assert_eq!(cp[1].tag(), ConstantPoolTag::Package);
+    }
+
+    #[test]
+    fn modified_utf8() {
+        // the null character and the surrogates of U+1F600 taken from a class compiled by javac
+        let bytes: &[u8] = b"a\xc0\x80b \xed\xa0\xbd\xed\xb8\x80 \xc3\xa9\xe2\x82\xac";
+        let content: String = convert_utf8(bytes);
+        assert_eq!(content, "a\u{0000}b \u{1F600} \u{00e9}\u{20ac}");
+        assert_eq!(
+            escape_for_display(&content),
+            "a\\u0000b \u{1F600} \u{00e9}\u{20ac}"
+        );
+    }
+
+    #[test]
+    fn unpaired_surrogates() {
+        // javac writes "\uD800" and "\uDC00x" as lone three-byte surrogates
+        assert_eq!(convert_utf8(b"\xed\xa0\x80"), "\\ud800");
+        assert_eq!(convert_utf8(b"\xed\xb0\x80x"), "\\udc00x");
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid modified UTF-8 byte 0xf0 at offset 0.")]
+    fn four_byte_sequences_are_not_modified_utf8() {
+        convert_utf8("\u{1F600}".as_bytes());
+    }
+
+    #[test]
+    fn numeric_accessors() {
+        let cp: ConstantPool = ConstantPool {
+            entries: vec![
+                ConstantPoolInfo::Integer { bytes: 0xcafebabe },
+                ConstantPoolInfo::Float { bytes: 0xbfc00000 },
+                ConstantPoolInfo::Long {
+                    high_bytes: 0xffffffff,
+                    low_bytes: 0xfffffffe,
+                },
+                ConstantPoolInfo::Null {},
+                ConstantPoolInfo::Double {
+                    high_bytes: 0x40040000,
+                    low_bytes: 0,
+                },
+                ConstantPoolInfo::Null {},
+            ],
+        };
+        assert_eq!(cp.get_integer(1), -889275714);
+        assert_eq!(cp.get_float(2), -1.5);
+        assert_eq!(cp.get_long(3), -2);
+        assert_eq!(cp.get_double(5), 2.5);
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected entry #1 to be of Long type but it wasn't.")]
+    fn numeric_accessor_of_the_wrong_type() {
+        let cp: ConstantPool = ConstantPool {
+            entries: vec![ConstantPoolInfo::Integer { bytes: 1 }],
+        };
+        cp.get_long(1);
+    }
+
+    #[test]
+    fn compact() {
+        let mut cp: ConstantPool = ConstantPool {
+            entries: vec![
+                ConstantPoolInfo::Utf8 {
+                    bytes: b"Unused".to_vec(),
+                },
+                ConstantPoolInfo::Long {
+                    high_bytes: 0,
+                    low_bytes: 1,
+                },
+                ConstantPoolInfo::Null {},
+                ConstantPoolInfo::Utf8 {
+                    bytes: b"Name".to_vec(),
+                },
+                ConstantPoolInfo::Class { name_index: 4 },
+                ConstantPoolInfo::Double {
+                    high_bytes: 0,
+                    low_bytes: 0,
+                },
+                ConstantPoolInfo::Null {},
+            ],
+        };
+        let remapping: HashMap<u16, u16> = cp.compact(&[6, 5]);
+        assert_eq!(remapping, HashMap::from([(4, 1), (5, 2), (6, 3)]));
+        assert_eq!(
+            cp.entries,
+            [
+                ConstantPoolInfo::Utf8 {
+                    bytes: b"Name".to_vec(),
+                },
+                ConstantPoolInfo::Class { name_index: 1 },
+                ConstantPoolInfo::Double {
+                    high_bytes: 0,
+                    low_bytes: 0,
+                },
+                ConstantPoolInfo::Null {},
+            ]
+        );
+    }
+
+    #[test]
+    fn adding_past_the_limit() {
+        let mut cp: ConstantPool = ConstantPool {
+            entries: Vec::new(),
+        };
+        assert_eq!(cp.remaining_capacity(), 65534);
+        for i in 1..MAX_CONSTANT_POOL_ENTRIES - 1 {
+            assert_eq!(
+                cp.add(ConstantPoolInfo::Integer { bytes: i as u32 }),
+                Ok(i as u16)
+            );
+        }
+        assert_eq!(cp.remaining_capacity(), 2);
+        assert_eq!(
+            cp.add(ConstantPoolInfo::Long {
+                high_bytes: 0,
+                low_bytes: 0
+            }),
+            Ok(65533)
+        );
+        assert_eq!(cp.remaining_capacity(), 0);
+        assert_eq!(
+            cp.add(ConstantPoolInfo::Integer { bytes: 0 }),
+            Err(ConstantPoolFull)
+        );
+        assert_eq!(cp.len(), MAX_CONSTANT_POOL_ENTRIES);
+    }
+
+    #[test]
+    fn wide_entry_needs_two_slots() {
+        let mut cp: ConstantPool = ConstantPool {
+            entries: vec![ConstantPoolInfo::Integer { bytes: 0 }; MAX_CONSTANT_POOL_ENTRIES - 1],
+        };
+        assert_eq!(cp.remaining_capacity(), 1);
+        assert_eq!(
+            cp.add(ConstantPoolInfo::Double {
+                high_bytes: 0,
+                low_bytes: 0
+            }),
+            Err(ConstantPoolFull)
+        );
+        assert_eq!(cp.remaining_capacity(), 1);
+        assert_eq!(cp.add(ConstantPoolInfo::Integer { bytes: 0 }), Ok(65534));
+    }
+}
@@ -1,4 +1,4 @@
-use std::{iter::Peekable, str::Chars};
+use std::{fmt, iter::Peekable, str::Chars};
 
 const START_GENERIC: char = '<';
 const END_GENERIC: char = '>';
@@ -12,6 +12,21 @@ const LEFT_SQUARE_BRACKET: char = '[';
 const LEFT_BRACKET: char = '(';
 const RIGHT_BRACKET: char = ')';
 
+/// The default maximum nesting of arrays and generic arguments accepted while decoding, which is also the maximum
+/// number of array dimensions allowed by the JVM specification.
+pub const MAX_DEPTH: usize = 255;
+
+#[derive(PartialEq, Debug)]
+pub enum DescriptorError {
+    /// The type is nested deeper than the allowed maximum depth.
+    TooDeep,
+}
+
+/// Returns the nesting still allowed inside a type which could be nested `depth` more times.
+fn nested(depth: usize) -> Result<usize, DescriptorError> {
+    depth.checked_sub(1).ok_or(DescriptorError::TooDeep)
+}
+
 fn collect_until(it: &mut Peekable<Chars>, stop: impl Fn(char) -> bool) -> String {
     let mut s = String::new();
     while let Some(&x) = it.peek() {
@@ -39,15 +54,15 @@ fn decode_type_variable(it: &mut Peekable<Chars>) -> String {
     name
 }
 
-fn decode_generic_arg(it: &mut Peekable<Chars>) -> String {
+fn decode_generic_arg(it: &mut Peekable<Chars>, depth: usize) -> Result<String, DescriptorError> {
     let ch = it
         .peek()
         .expect("Expected generic argument but found end of input.");
-    match *ch {
-        REFERENCE_START => decode_ref_type(it),
+    Ok(match *ch {
+        REFERENCE_START => decode_ref_type(it, depth)?,
         LEFT_SQUARE_BRACKET => {
             it.next();
-            let inner = decode_generic_arg(it);
+            let inner = decode_generic_arg(it, nested(depth)?)?;
             inner + "[]"
         }
         TYPE_VAR_START => decode_type_variable(it),
@@ -57,23 +72,23 @@ fn decode_generic_arg(it: &mut Peekable<Chars>) -> String {
         }
         '+' => {
             it.next();
-            "? extends ".to_owned() + &decode_generic_arg(it)
+            "? extends ".to_owned() + &decode_generic_arg(it, nested(depth)?)?
         }
         '-' => {
             it.next();
-            "? super ".to_owned() + &decode_generic_arg(it)
+            "? super ".to_owned() + &decode_generic_arg(it, nested(depth)?)?
         }
 
         // forbid everything else inside generics
         _ => panic!("Invalid generic argument type: '{ch}'."),
-    }
+    })
 }
 
-fn decode_type_it(it: &mut Peekable<Chars>) -> String {
+fn decode_type_it(it: &mut Peekable<Chars>, depth: usize) -> Result<String, DescriptorError> {
     let ch = it
         .peek()
         .expect("Expected start of type but found end of input.");
-    match *ch {
+    Ok(match *ch {
         'B' => {
             it.next();
             "byte".to_owned()
@@ -111,11 +126,11 @@ fn decode_type_it(it: &mut Peekable<Chars>) -> String {
             "void".to_owned()
         }
 
-        REFERENCE_START => decode_ref_type(it),
+        REFERENCE_START => decode_ref_type(it, depth)?,
 
         LEFT_SQUARE_BRACKET => {
             it.next();
-            let inner = decode_type_it(it);
+            let inner = decode_type_it(it, nested(depth)?)?;
             inner + "[]"
         }
 
@@ -127,20 +142,20 @@ fn decode_type_it(it: &mut Peekable<Chars>) -> String {
                     it.next();
                     break;
                 }
-                args.push(decode_type_it(it));
+                args.push(decode_type_it(it, depth)?);
             }
 
-            let ret = decode_type_it(it);
+            let ret = decode_type_it(it, depth)?;
             format!("{}({})", ret, args.join(", "))
         }
 
         TYPE_VAR_START => decode_type_variable(it),
 
         _ => panic!("Unexpected '{ch}' at the start of type."),
-    }
+    })
 }
 
-fn decode_ref_type(it: &mut Peekable<Chars>) -> String {
+fn decode_ref_type(it: &mut Peekable<Chars>, depth: usize) -> Result<String, DescriptorError> {
     expect(it, REFERENCE_START);
 
     let mut s = consume_class_name(it);
@@ -163,14 +178,14 @@ fn decode_ref_type(it: &mut Peekable<Chars>) -> String {
             }
             first = false;
 
-            s.push_str(&decode_generic_arg(it));
+            s.push_str(&decode_generic_arg(it, nested(depth)?)?);
         }
 
         s.push(END_GENERIC);
     }
 
     expect(it, SEMICOLON);
-    s
+    Ok(s)
 }
 
 /// Describes the bounds of a generic type parameter in a JVM-style descriptor.
@@ -192,7 +207,10 @@ pub struct GenericTypeBound {
     pub type_bounds: Vec<String>,
 }
 
-fn parse_generic_type_bounds(it: &mut Peekable<Chars>) -> Vec<GenericTypeBound> {
+fn parse_generic_type_bounds(
+    it: &mut Peekable<Chars>,
+    max_depth: usize,
+) -> Result<Vec<GenericTypeBound>, DescriptorError> {
     expect(it, START_GENERIC);
     let mut generics = Vec::new();
     while let Some(&x) = it.peek() {
@@ -212,7 +230,7 @@ fn parse_generic_type_bounds(it: &mut Peekable<Chars>) -> Vec<GenericTypeBound>
                 // empty class bound — do NOT consume, the interface loop will handle it
             } else {
                 // actual class bound
-                type_bounds.push(decode_type_it(it));
+                type_bounds.push(decode_type_it(it, max_depth)?);
             }
         }
 
@@ -222,7 +240,7 @@ fn parse_generic_type_bounds(it: &mut Peekable<Chars>) -> Vec<GenericTypeBound>
                 break;
             }
             it.next(); // consume ':'
-            type_bounds.push(decode_type_it(it));
+            type_bounds.push(decode_type_it(it, max_depth)?);
         }
 
         generics.push(GenericTypeBound {
@@ -230,7 +248,7 @@ fn parse_generic_type_bounds(it: &mut Peekable<Chars>) -> Vec<GenericTypeBound>
             type_bounds,
         });
     }
-    generics
+    Ok(generics)
 }
 
 /// Checks if the next character in the iterator is the given one and consumes it.
@@ -254,12 +272,22 @@ fn format_generic_bound(gtb: &GenericTypeBound) -> String {
     )
 }
 
-pub fn decode_type(descriptor: &str) -> String {
+/// Decodes a descriptor or a generic signature into its Java form, for example `java.util.List<java.lang.String>`.
+pub fn decode_type(descriptor: &str) -> Result<String, DescriptorError> {
+    decode_type_with_max_depth(descriptor, MAX_DEPTH)
+}
+
+/// Like [`decode_type`], but with a custom limit on the nesting of arrays and generic arguments.
+pub fn decode_type_with_max_depth(
+    descriptor: &str,
+    max_depth: usize,
+) -> Result<String, DescriptorError> {
     let mut s = String::new();
     let mut it = descriptor.chars().peekable();
 
     if descriptor.starts_with(START_GENERIC) {
-        let generic_type_bounds: Vec<GenericTypeBound> = parse_generic_type_bounds(&mut it);
+        let generic_type_bounds: Vec<GenericTypeBound> =
+            parse_generic_type_bounds(&mut it, max_depth)?;
         s.push(START_GENERIC);
         s.push_str(
             &generic_type_bounds
@@ -273,10 +301,10 @@ pub fn decode_type(descriptor: &str) -> String {
     }
 
     while it.peek().is_some() {
-        s.push_str(&decode_type_it(&mut it));
+        s.push_str(&decode_type_it(&mut it, max_depth)?);
     }
 
-    s
+    Ok(s)
 }
 
 /// From the given iterator, this function extracts the full class name included with generic declarations from the raw descriptor.
@@ -308,29 +336,175 @@ pub struct ClassSignature {
     pub interfaces: Vec<String>,
 }
 
-pub fn decode_class_signature(class_signature: &str) -> ClassSignature {
+pub fn decode_class_signature(class_signature: &str) -> Result<ClassSignature, DescriptorError> {
     let mut it = class_signature.chars().peekable();
 
     let generic_type_bounds = if it.peek() == Some(&START_GENERIC) {
-        parse_generic_type_bounds(&mut it)
+        parse_generic_type_bounds(&mut it, MAX_DEPTH)?
     } else {
         Vec::new()
     };
 
-    let super_class_name = decode_type(&split_class_name(&mut it));
+    let super_class_name = decode_type(&split_class_name(&mut it))?;
     let mut interfaces: Vec<String> = Vec::new();
 
     while it.peek().is_some() {
-        interfaces.push(decode_type(&split_class_name(&mut it)));
+        interfaces.push(decode_type(&split_class_name(&mut it))?);
     }
 
-    ClassSignature {
+    Ok(ClassSignature {
         generic_type_bounds,
         super_class_name,
         interfaces,
+    })
+}
+
+/// A type as written in a field or method descriptor, without generic information.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Type {
+    Void,
+    Boolean,
+    Byte,
+    Char,
+    Short,
+    Int,
+    Long,
+    Float,
+    Double,
+    Array {
+        element_type: Box<Type>,
+    },
+    /// A class or interface, with its binary name like `java/lang/String`.
+    Object {
+        class_name: String,
+    },
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Void => write!(f, "void"),
+            Type::Boolean => write!(f, "boolean"),
+            Type::Byte => write!(f, "byte"),
+            Type::Char => write!(f, "char"),
+            Type::Short => write!(f, "short"),
+            Type::Int => write!(f, "int"),
+            Type::Long => write!(f, "long"),
+            Type::Float => write!(f, "float"),
+            Type::Double => write!(f, "double"),
+            Type::Array { element_type } => write!(f, "{element_type}[]"),
+            Type::Object { class_name } => write!(f, "{}", class_name.replace(FORWARD_SLASH, ".")),
+        }
     }
 }
 
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FieldDescriptor {
+    pub field_type: Type,
+}
+
+/// Parses a single type, where `void` is accepted only if `allow_void` is true and never as the type of array elements.
+fn parse_type(it: &mut Peekable<Chars>, allow_void: bool) -> Result<Type, DescriptorError> {
+    let mut dimensions: usize = 0;
+    while it.next_if_eq(&LEFT_SQUARE_BRACKET).is_some() {
+        dimensions += 1;
+    }
+    if dimensions > MAX_DEPTH {
+        return Err(DescriptorError::TooDeep);
+    }
+
+    let ch: char = it.next().expect("Expected type but found end of input.");
+    let mut result: Type = match ch {
+        'V' if allow_void && dimensions == 0 => Type::Void,
+        'Z' => Type::Boolean,
+        'B' => Type::Byte,
+        'C' => Type::Char,
+        'S' => Type::Short,
+        'I' => Type::Int,
+        'J' => Type::Long,
+        'F' => Type::Float,
+        'D' => Type::Double,
+        REFERENCE_START => {
+            let mut class_name: String = String::new();
+            while let Some(x) = it.next_if(|&x| x != SEMICOLON) {
+                class_name.push(x);
+            }
+            assert!(
+                !class_name.is_empty()
+                    && !class_name.contains([DOT, LEFT_SQUARE_BRACKET, START_GENERIC]),
+                "Invalid class name '{class_name}'."
+            );
+            expect(it, SEMICOLON);
+            Type::Object { class_name }
+        }
+        _ => panic!("Unexpected character '{ch}' in descriptor."),
+    };
+    for _ in 0..dimensions {
+        result = Type::Array {
+            element_type: Box::new(result),
+        };
+    }
+    Ok(result)
+}
+
+/**
+ * Parses a field descriptor like `I`, `[J` or `Ljava/lang/String;`. Generic signatures are not field descriptors:
+ * use [`decode_type`] for them.
+ */
+pub fn parse_field_descriptor(descriptor: &str) -> Result<FieldDescriptor, DescriptorError> {
+    let mut it = descriptor.chars().peekable();
+    let field_type: Type = parse_type(&mut it, false)?;
+    assert!(
+        it.peek().is_none(),
+        "Unexpected characters after the end of field descriptor '{descriptor}'."
+    );
+    Ok(FieldDescriptor { field_type })
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MethodDescriptor {
+    pub parameter_types: Vec<Type>,
+    pub return_type: Type,
+}
+
+impl fmt::Display for MethodDescriptor {
+    /// Formats the descriptor like [`decode_type`] does, for example `double(int, java.lang.String)`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}({})",
+            self.return_type,
+            self.parameter_types
+                .iter()
+                .map(Type::to_string)
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
+
+/**
+ * Parses a method descriptor like `(I[JLjava/lang/String;)Z`. Generic signatures are not method descriptors: use
+ * [`decode_type`] for them.
+ */
+pub fn parse_method_descriptor(descriptor: &str) -> Result<MethodDescriptor, DescriptorError> {
+    let mut it = descriptor.chars().peekable();
+    expect(&mut it, LEFT_BRACKET);
+    let mut parameter_types: Vec<Type> = Vec::new();
+    while it.next_if_eq(&RIGHT_BRACKET).is_none() {
+        parameter_types.push(parse_type(&mut it, false)?);
+    }
+    let return_type: Type = parse_type(&mut it, true)?;
+    assert!(
+        it.peek().is_none(),
+        "Unexpected characters after the end of method descriptor '{descriptor}'."
+    );
+    Ok(MethodDescriptor {
+        parameter_types,
+        return_type,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -409,6 +583,8 @@ mod tests {
     #[case("()Ljava/lang/String;", "java.lang.String()")]
     #[case("(I)S", "short(int)")]
     #[case("(IFS)D", "double(int, float, short)")]
+    #[case("(II)V", "void(int, int)")]
+    #[case("(I[JLjava/lang/String;)Z", "boolean(int, long[], java.lang.String)")]
     #[case(
         "([ZI[CJ[[S)[[[C",
         "char[][][](boolean[], int, char[], long, short[][])"
@@ -455,13 +631,53 @@ mod tests {
         "<X extends java.lang.Integer & java.io.Serializable & java.lang.Comparable> java.util.Optional<X>(java.lang.Class<X>)"
     )]
     fn decode_signatures(#[case] input: &str, #[case] expected: &str) {
-        let actual = decode_type(input);
+        let actual = decode_type(input).unwrap();
         assert_eq!(
             expected, actual,
             "Expected '{input}' to be decoded into '{expected}' but was '{actual}'."
         );
     }
 
+    #[test]
+    fn too_deep_array() {
+        let descriptor: String = "[".repeat(300) + "I";
+        assert_eq!(decode_type(&descriptor), Err(DescriptorError::TooDeep));
+        assert_eq!(
+            parse_field_descriptor(&descriptor),
+            Err(DescriptorError::TooDeep)
+        );
+        assert_eq!(
+            parse_method_descriptor(&format!("({descriptor})V")),
+            Err(DescriptorError::TooDeep)
+        );
+        assert_eq!(
+            decode_class_signature(&format!("Ljava/lang/Object;Ljava/util/List<{descriptor}>;")),
+            Err(DescriptorError::TooDeep)
+        );
+    }
+
+    #[test]
+    fn max_depth_array() {
+        let descriptor: String = "[".repeat(MAX_DEPTH) + "I";
+        assert_eq!(
+            decode_type(&descriptor),
+            Ok("int".to_owned() + &"[]".repeat(MAX_DEPTH))
+        );
+    }
+
+    #[test]
+    fn custom_max_depth() {
+        let descriptor: &str = "Ljava/util/List<Ljava/util/List<Ljava/lang/String;>;>;";
+        assert_eq!(
+            decode_type_with_max_depth(descriptor, 1),
+            Err(DescriptorError::TooDeep)
+        );
+        assert_eq!(
+            decode_type_with_max_depth(descriptor, 2),
+            Ok("java.util.List<java.util.List<java.lang.String>>".to_owned())
+        );
+    }
+
     #[rstest]
     #[case("Q")]
     #[case("[")]
@@ -473,7 +689,7 @@ mod tests {
     #[case("Ljava/util/List<I>;")]
     #[case("Ljava/util/List<Ljava/lang/String>;")]
     fn invalid_parsing(#[case] input: &str) {
-        let result = std::panic::catch_unwind(|| decode_type(input));
+        let result = std::panic::catch_unwind(|| decode_type(input).unwrap());
         assert!(
             result.is_err(),
             "Parsing of '{input}' should have panicked but did not.",
@@ -549,10 +765,117 @@ mod tests {
         }
     )]
     fn decode_class_signatures(#[case] input: &str, #[case] expected: ClassSignature) {
-        let actual = decode_class_signature(input);
+        let actual = decode_class_signature(input).unwrap();
         assert_eq!(
             expected, actual,
             "Expected class signature '{input}' to be decoded into '{expected:?}' but was '{actual:?}'."
         );
     }
+
+    #[rstest]
+    #[case("Z", Type::Boolean)]
+    #[case("B", Type::Byte)]
+    #[case("C", Type::Char)]
+    #[case("S", Type::Short)]
+    #[case("I", Type::Int)]
+    #[case("J", Type::Long)]
+    #[case("F", Type::Float)]
+    #[case("D", Type::Double)]
+    #[case("[C", Type::Array { element_type: Box::new(Type::Char) })]
+    #[case("[[D", Type::Array { element_type: Box::new(Type::Array { element_type: Box::new(Type::Double) }) })]
+    #[case("Ljava/lang/String;", Type::Object { class_name: "java/lang/String".to_owned() })]
+    #[case("[Ljava/lang/Object;", Type::Array { element_type: Box::new(Type::Object { class_name: "java/lang/Object".to_owned() }) })]
+    fn field_descriptors(#[case] input: &str, #[case] expected: Type) {
+        assert_eq!(parse_field_descriptor(input).unwrap().field_type, expected);
+    }
+
+    #[rstest]
+    #[case("D", "double")]
+    #[case("[C", "char[]")]
+    #[case("[[S", "short[][]")]
+    #[case("Ljava/lang/String;", "java.lang.String")]
+    #[case("[Ljava/util/Map$Entry;", "java.util.Map$Entry[]")]
+    fn field_descriptors_display(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(
+            parse_field_descriptor(input)
+                .unwrap()
+                .field_type
+                .to_string(),
+            expected
+        );
+        assert_eq!(decode_type(input).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("V")]
+    #[case("[V")]
+    #[case("Q")]
+    #[case("[")]
+    #[case("II")]
+    #[case("L;")]
+    #[case("Ljava/lang/String")]
+    #[case("Ljava.lang.String;")]
+    #[case("Ljava/util/List<Ljava/lang/String;>;")]
+    #[case("Ljava/lang/String;I")]
+    fn invalid_field_descriptors(#[case] input: &str) {
+        let result = std::panic::catch_unwind(|| parse_field_descriptor(input).unwrap());
+        assert!(
+            result.is_err(),
+            "Parsing of '{input}' should have panicked but did not.",
+        );
+    }
+
+    #[rstest]
+    #[case("()V", "void()")]
+    #[case("(II)V", "void(int, int)")]
+    #[case("(IFLjava/lang/String;)V", "void(int, float, java.lang.String)")]
+    #[case("(I[JLjava/lang/String;)Z", "boolean(int, long[], java.lang.String)")]
+    #[case(
+        "([[BCSD)[Ljava/lang/Object;",
+        "java.lang.Object[](byte[][], char, short, double)"
+    )]
+    fn method_descriptors(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(
+            parse_method_descriptor(input).unwrap().to_string(),
+            expected
+        );
+        assert_eq!(decode_type(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn method_descriptor_types() {
+        assert_eq!(
+            parse_method_descriptor("(I[JLjava/lang/String;)Z").unwrap(),
+            MethodDescriptor {
+                parameter_types: vec![
+                    Type::Int,
+                    Type::Array {
+                        element_type: Box::new(Type::Long)
+                    },
+                    Type::Object {
+                        class_name: "java/lang/String".to_owned()
+                    }
+                ],
+                return_type: Type::Boolean
+            }
+        );
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("V")]
+    #[case("()")]
+    #[case("(I")]
+    #[case("(V)V")]
+    #[case("()[V")]
+    #[case("()VV")]
+    #[case("(Ljava/lang/String)V")]
+    fn invalid_method_descriptors(#[case] input: &str) {
+        let result = std::panic::catch_unwind(|| parse_method_descriptor(input).unwrap());
+        assert!(
+            result.is_err(),
+            "Parsing of '{input}' should have panicked but did not.",
+        );
+    }
 }
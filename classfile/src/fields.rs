@@ -7,7 +7,8 @@ use crate::{
     constant_pool::{ConstantPool, ConstantPoolTag},
 };
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FieldInfo {
     pub access_flags: FieldAccessFlags,
     pub name_index: u16,
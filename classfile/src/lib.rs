@@ -1,13 +1,19 @@
 #![forbid(unsafe_code)]
 
 pub mod access_flags;
+pub mod analysis;
 pub mod attributes;
 pub mod bytecode;
 pub mod classfile;
+pub mod code_editing;
 pub mod constant_pool;
 pub mod descriptor;
 pub mod fields;
 pub mod methods;
+pub mod obfuscate;
+pub mod optimize;
 pub mod reference_kind;
+pub mod remapping;
 pub mod utils;
+pub mod verify;
 pub mod writer;
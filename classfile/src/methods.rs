@@ -1,13 +1,14 @@
 use binary_reader::BinaryReader;
 
 use crate::{
-    access_flags::MethodAccessFlags,
+    access_flags::{MethodAccessFlag, MethodAccessFlags},
     assert_valid_and_type,
-    attributes::{AttributeInfo, parse_method_attributes},
+    attributes::{AttributeInfo, AttributeKind, find_attribute, parse_method_attributes},
     constant_pool::{ConstantPool, ConstantPoolTag},
 };
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MethodInfo {
     pub access_flags: MethodAccessFlags,
     pub name_index: u16,
@@ -30,6 +31,17 @@ pub fn parse_methods(
         let attribute_count: u16 = reader.read_u16().unwrap();
         let attributes: Vec<AttributeInfo> =
             parse_method_attributes(reader, cp, attribute_count.into());
+        // abstract and native methods have no body, every other method must have one
+        let has_body: bool = !access_flags.contains(MethodAccessFlag::Abstract)
+            && !access_flags.contains(MethodAccessFlag::Native);
+        let has_code: bool = find_attribute(&attributes, AttributeKind::Code).is_some();
+        assert!(
+            has_body == has_code,
+            "Method '{}' has flags {} but {} a Code attribute.",
+            cp.get_utf8_content(name_index),
+            access_flags.java_repr(),
+            if has_code { "has" } else { "does not have" }
+        );
         methods.push(MethodInfo {
             access_flags,
             name_index,
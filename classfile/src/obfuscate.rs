@@ -0,0 +1,826 @@
+use std::{
+    cell::Cell,
+    collections::{HashMap, HashSet},
+};
+
+use crate::{
+    access_flags::{FieldAccessFlag, MethodAccessFlag},
+    analysis::{ASSERTIONS_DISABLED_FIELD, assertion_guards, is_assertions_disabled_ref},
+    attributes::{AttributeInfo, AttributeKind, find_attribute},
+    bytecode::BytecodeInstruction,
+    classfile::ClassFile,
+    code_editing::remove_code_range,
+    constant_pool::{ConstantPool, ConstantPoolInfo},
+    remapping::{
+        has_unknown_attributes, rebuild_constant_pool, remap_constant_pool_entry,
+        remap_constant_pool_indices,
+    },
+};
+
+/**
+ * Rewrites the class file in a form which depends only on its contents, so that equivalent classes are written to the
+ * same bytes. Constant pool entries are sorted by their contents, each one after the entries it references, and
+ * attributes are sorted by name. The entries loaded by `ldc` are placed first, to keep them within its one-byte index.
+ * Classes with attributes kept as raw bytes are left unchanged, since the indices inside those attributes cannot be
+ * rewritten.
+ */
+pub fn canonicalize(cf: &mut ClassFile) {
+    if has_unknown_attributes(cf) {
+        return;
+    }
+    let cp: &ConstantPool = &cf.constant_pool;
+    let loaded_by_ldc: HashSet<u16> = loaded_by_ldc(cf);
+    let mut contents: HashMap<u16, (usize, Vec<u8>)> = HashMap::new();
+    let mut old_indices: Vec<u16> = Vec::new();
+    for i in 0..cp.len() {
+        if !matches!(cp[i as u16], ConstantPoolInfo::Null {}) {
+            let old_index: u16 = (i + 1) as u16;
+            compute_contents(cp, old_index, &mut contents);
+            old_indices.push(old_index);
+        }
+    }
+    // identical entries compare equal, so the old index breaks ties to keep the order total
+    old_indices.sort_by_key(|idx| {
+        let (depth, bytes) = &contents[idx];
+        (!loaded_by_ldc.contains(idx), *depth, bytes.clone(), *idx)
+    });
+
+    let mut remapping: HashMap<u16, u16> = HashMap::new();
+    let mut next_index: u16 = 1;
+    for old_index in old_indices {
+        remapping.insert(old_index, next_index);
+        next_index += match cp[old_index - 1] {
+            ConstantPoolInfo::Long { .. } | ConstantPoolInfo::Double { .. } => 2,
+            _ => 1,
+        };
+    }
+
+    *cf = rebuild_constant_pool(cf, &remapping);
+    sort_attributes(&cf.constant_pool, &mut cf.attributes);
+    for field in cf.fields.iter_mut() {
+        sort_attributes(&cf.constant_pool, &mut field.attributes);
+    }
+    for method in cf.methods.iter_mut() {
+        sort_attributes(&cf.constant_pool, &mut method.attributes);
+    }
+}
+
+/// Returns the indices of the entries referenced by the given one.
+fn references(entry: &ConstantPoolInfo) -> Vec<u16> {
+    let mut indices: Vec<u16> = Vec::new();
+    remap_constant_pool_entry(&mut entry.clone(), &mut |idx| {
+        indices.push(idx);
+        idx
+    });
+    indices
+}
+
+/// Returns the indices of the entries loaded by an `ldc` instruction, together with all the entries they reference.
+fn loaded_by_ldc(cf: &ClassFile) -> HashSet<u16> {
+    let mut to_visit: Vec<u16> = Vec::new();
+    for method in cf.methods.iter() {
+        if let Some(AttributeInfo::Code { code, .. }) =
+            find_attribute(&method.attributes, AttributeKind::Code)
+        {
+            for (_, instruction) in code.iter() {
+                if let BytecodeInstruction::Ldc {
+                    constant_pool_index,
+                } = instruction
+                {
+                    to_visit.push(u16::from(*constant_pool_index));
+                }
+            }
+        }
+    }
+
+    let mut visited: HashSet<u16> = HashSet::new();
+    while let Some(idx) = to_visit.pop() {
+        if visited.insert(idx) {
+            to_visit.extend(references(&cf.constant_pool[idx - 1]));
+        }
+    }
+    visited
+}
+
+/**
+ * Computes the depth of the entry at the given index (zero for entries which do not reference others, one more than
+ * the deepest referenced entry otherwise) and a byte representation of its contents, which embeds the ones of the
+ * referenced entries instead of their indices.
+ */
+fn compute_contents(cp: &ConstantPool, idx: u16, contents: &mut HashMap<u16, (usize, Vec<u8>)>) {
+    if contents.contains_key(&idx) {
+        return;
+    }
+    let entry: &ConstantPoolInfo = &cp[idx - 1];
+    let mut bytes: Vec<u8> = format!("{:?}", entry.tag()).into_bytes();
+    bytes.push(0);
+    match entry {
+        ConstantPoolInfo::Utf8 { bytes: content } => {
+            bytes.extend((content.len() as u32).to_be_bytes());
+            bytes.extend(content);
+        }
+        ConstantPoolInfo::Integer { bytes: value } | ConstantPoolInfo::Float { bytes: value } => {
+            bytes.extend(value.to_be_bytes())
+        }
+        ConstantPoolInfo::Long {
+            high_bytes,
+            low_bytes,
+        }
+        | ConstantPoolInfo::Double {
+            high_bytes,
+            low_bytes,
+        } => {
+            bytes.extend(high_bytes.to_be_bytes());
+            bytes.extend(low_bytes.to_be_bytes());
+        }
+        ConstantPoolInfo::MethodHandle { reference_kind, .. } => bytes.push(*reference_kind as u8),
+        ConstantPoolInfo::Dynamic {
+            bootstrap_method_attr_index,
+            ..
+        }
+        | ConstantPoolInfo::InvokeDynamic {
+            bootstrap_method_attr_index,
+            ..
+        } => bytes.extend(bootstrap_method_attr_index.to_be_bytes()),
+        _ => {}
+    }
+
+    let mut depth: usize = 0;
+    for reference in references(entry) {
+        compute_contents(cp, reference, contents);
+        let (reference_depth, reference_bytes) = &contents[&reference];
+        depth = depth.max(reference_depth + 1);
+        bytes.extend((reference_bytes.len() as u32).to_be_bytes());
+        bytes.extend(reference_bytes);
+    }
+    contents.insert(idx, (depth, bytes));
+}
+
+fn sort_attributes(cp: &ConstantPool, attributes: &mut [AttributeInfo]) {
+    attributes.sort_by_key(|attribute| match attribute {
+        AttributeInfo::Unknown { name_index, .. } => cp.get_utf8_content(*name_index),
+        _ => attribute.kind().to_string(),
+    });
+    for attribute in attributes.iter_mut() {
+        if let AttributeInfo::Code { attributes, .. } = attribute {
+            sort_attributes(cp, attributes);
+        }
+    }
+}
+
+/// The names looked up through reflection by the serialization machinery.
+const SERIALIZATION_NAMES: [&str; 7] = [
+    "serialVersionUID",
+    "serialPersistentFields",
+    "writeObject",
+    "readObject",
+    "readObjectNoData",
+    "writeReplace",
+    "readResolve",
+];
+
+/**
+ * Renames the private fields and methods of the class to the names given by `strategy` for their old ones, which must
+ * not be used by its other members. Only private members are renamed, because the others can be referenced by classes
+ * which are not visible here, and native methods keep their names to stay bound to their implementation. For the same
+ * reason, nestmates and records are left untouched: their private members are also accessed by the other classes of
+ * the nest and through the record components. The entries holding the old names stay in the constant pool, as string
+ * constants may still use them.
+ */
+pub fn rename_identifiers(cf: &mut ClassFile, strategy: &dyn Fn(&str) -> String) {
+    let cp: ConstantPool = cf.constant_pool.clone();
+    if [
+        AttributeKind::NestHost,
+        AttributeKind::NestMembers,
+        AttributeKind::Record,
+    ]
+    .into_iter()
+    .any(|kind| find_attribute(&cf.attributes, kind).is_some())
+    {
+        return;
+    }
+
+    let is_renamable = |name: &str| !name.starts_with('<') && !SERIALIZATION_NAMES.contains(&name);
+
+    // (is_method, old name, descriptor) -> new name
+    let mut renamed: HashMap<(bool, String, String), String> = HashMap::new();
+    let mut field_names: Vec<(usize, String)> = Vec::new();
+    for (i, field) in cf.fields.iter().enumerate() {
+        let name: String = cp.get_utf8_content(field.name_index);
+        if field.access_flags.contains(FieldAccessFlag::Private) && is_renamable(&name) {
+            let new_name: String = strategy(&name);
+            let descriptor: String = cp.get_utf8_content(field.descriptor_index);
+            renamed.insert((false, name, descriptor), new_name.clone());
+            field_names.push((i, new_name));
+        }
+    }
+    let mut method_names: Vec<(usize, String)> = Vec::new();
+    for (i, method) in cf.methods.iter().enumerate() {
+        let name: String = cp.get_utf8_content(method.name_index);
+        // native methods are bound to their implementation by name
+        if method.access_flags.contains(MethodAccessFlag::Private)
+            && !method.access_flags.contains(MethodAccessFlag::Native)
+            && is_renamable(&name)
+        {
+            let new_name: String = strategy(&name);
+            let descriptor: String = cp.get_utf8_content(method.descriptor_index);
+            renamed.insert((true, name, descriptor), new_name.clone());
+            method_names.push((i, new_name));
+        }
+    }
+
+    // the references to the renamed members, with the index of their descriptor and their new name
+    let class_name: String = cf.class_name();
+    let mut references: Vec<(u16, u16, String)> = Vec::new();
+    for i in 0..cp.len() {
+        let (is_method, class_index, name_and_type_index) = match cp[i as u16] {
+            ConstantPoolInfo::FieldRef {
+                class_index,
+                name_and_type_index,
+            } => (false, class_index, name_and_type_index),
+            ConstantPoolInfo::MethodRef {
+                class_index,
+                name_and_type_index,
+            }
+            | ConstantPoolInfo::InterfaceMethodRef {
+                class_index,
+                name_and_type_index,
+            } => (true, class_index, name_and_type_index),
+            _ => continue,
+        };
+        let ConstantPoolInfo::NameAndType {
+            name_index,
+            descriptor_index,
+        } = cp[name_and_type_index - 1]
+        else {
+            unreachable!()
+        };
+        let key = (
+            is_method,
+            cp.get_utf8_content(name_index),
+            cp.get_utf8_content(descriptor_index),
+        );
+        if let Some(new_name) = renamed.get(&key)
+            && cp.get_class_name(class_index) == class_name
+        {
+            references.push(((i + 1) as u16, descriptor_index, new_name.clone()));
+        }
+    }
+
+    // one Utf8 entry for each new name, one NameAndType and one reference entry for each reference
+    let needed: usize = field_names.len() + method_names.len() + 2 * references.len();
+    if usize::from(cp.remaining_capacity()) < needed {
+        return;
+    }
+
+    let mut name_indices: HashMap<String, u16> = HashMap::new();
+    for (i, new_name) in field_names {
+        let name_index: u16 = add_utf8(&mut cf.constant_pool, &new_name);
+        cf.fields[i].name_index = name_index;
+        name_indices.insert(new_name, name_index);
+    }
+    for (i, new_name) in method_names {
+        let name_index: u16 = add_utf8(&mut cf.constant_pool, &new_name);
+        cf.methods[i].name_index = name_index;
+        name_indices.insert(new_name, name_index);
+    }
+    let mut redirections: HashMap<u16, u16> = HashMap::new();
+    for (reference_index, descriptor_index, new_name) in references {
+        let name_and_type_index: u16 = cf
+            .constant_pool
+            .add(ConstantPoolInfo::NameAndType {
+                name_index: name_indices[&new_name],
+                descriptor_index,
+            })
+            .unwrap();
+        let reference: ConstantPoolInfo = match cp[reference_index - 1] {
+            ConstantPoolInfo::FieldRef { class_index, .. } => ConstantPoolInfo::FieldRef {
+                class_index,
+                name_and_type_index,
+            },
+            ConstantPoolInfo::MethodRef { class_index, .. } => ConstantPoolInfo::MethodRef {
+                class_index,
+                name_and_type_index,
+            },
+            ConstantPoolInfo::InterfaceMethodRef { class_index, .. } => {
+                ConstantPoolInfo::InterfaceMethodRef {
+                    class_index,
+                    name_and_type_index,
+                }
+            }
+            _ => unreachable!(),
+        };
+        redirections.insert(reference_index, cf.constant_pool.add(reference).unwrap());
+    }
+    remap_constant_pool_indices(cf, &mut |idx| {
+        redirections.get(&idx).copied().unwrap_or(idx)
+    });
+}
+
+/**
+ * A renaming strategy which gives the short meaningless names `a`, `b`, ..., `z`, `aa`, ... in order, skipping the
+ * ones already used by the members of the class.
+ */
+pub struct SequentialRenamer {
+    taken: HashSet<String>,
+    next_index: Cell<usize>,
+}
+
+impl SequentialRenamer {
+    pub fn new(cf: &ClassFile) -> Self {
+        SequentialRenamer {
+            taken: cf
+                .fields
+                .iter()
+                .map(|field| field.name_index)
+                .chain(cf.methods.iter().map(|method| method.name_index))
+                .map(|idx| cf.constant_pool.get_utf8_content(idx))
+                .collect(),
+            next_index: Cell::new(0),
+        }
+    }
+
+    /// Returns the next free name, whatever the old one was.
+    pub fn rename(&self, _old_name: &str) -> String {
+        loop {
+            let name: String = sequential_name(self.next_index.get());
+            self.next_index.set(self.next_index.get() + 1);
+            if !self.taken.contains(&name) {
+                return name;
+            }
+        }
+    }
+}
+
+fn add_utf8(cp: &mut ConstantPool, content: &str) -> u16 {
+    cp.add(ConstantPoolInfo::Utf8 {
+        bytes: content.as_bytes().to_vec(),
+    })
+    .unwrap()
+}
+
+/// Returns the name with the given index in the sequence `a`, `b`, ..., `z`, `aa`, `ab`, ..., `zz`, `aaa`, ...
+fn sequential_name(index: usize) -> String {
+    let mut letters: Vec<u8> = Vec::new();
+    let mut rest: usize = index + 1;
+    while rest > 0 {
+        rest -= 1;
+        letters.push(b'a' + (rest % 26) as u8);
+        rest /= 26;
+    }
+    letters.reverse();
+    String::from_utf8(letters).unwrap()
+}
+
+/**
+ * Removes every `assert` statement, together with the synthetic `$assertionsDisabled` field and its initialization
+ * in the static initializer.
+ */
+pub fn strip_assertions(cf: &mut ClassFile) {
+    for i in 0..cf.methods.len() {
+        let Some(AttributeInfo::Code { code, .. }) =
+            find_attribute(&cf.methods[i].attributes, AttributeKind::Code)
+        else {
+            continue;
+        };
+
+        let mut ranges: Vec<(u32, u32)> = assertion_guards(cf, code)
+            .into_iter()
+            .filter_map(|guard| guarded_range(code, guard))
+            .collect();
+        ranges.extend(initialization_range(cf, code));
+        // removing from the end keeps the offsets of the remaining ranges valid
+        ranges.sort();
+        let code_attribute: &mut AttributeInfo = cf.methods[i]
+            .attributes
+            .iter_mut()
+            .find(|a| a.kind() == AttributeKind::Code)
+            .unwrap();
+        for (start, end) in ranges.into_iter().rev() {
+            remove_code_range(code_attribute, start, end);
+        }
+    }
+
+    let is_still_used: bool = cf.methods.iter().any(|method| {
+        match find_attribute(&method.attributes, AttributeKind::Code) {
+            Some(AttributeInfo::Code { code, .. }) => code.iter().any(|(_, i)| match i {
+                BytecodeInstruction::GetStatic { field_ref_index }
+                | BytecodeInstruction::PutStatic { field_ref_index } => {
+                    is_assertions_disabled_ref(cf, *field_ref_index)
+                }
+                _ => false,
+            }),
+            _ => false,
+        }
+    });
+    if !is_still_used {
+        let cp: &ConstantPool = &cf.constant_pool;
+        cf.fields
+            .retain(|field| cp.get_utf8_content(field.name_index) != ASSERTIONS_DISABLED_FIELD);
+    }
+}
+
+/// Returns the range going from the `getstatic $assertionsDisabled` at the given index to the end of the asserted
+/// body, which is the target of the following `ifne`.
+fn guarded_range(code: &[(u32, BytecodeInstruction)], guard: usize) -> Option<(u32, u32)> {
+    let start: u32 = code[guard].0;
+    let (ifne_position, BytecodeInstruction::IfNe { offset }) = &code[guard + 1] else {
+        return None;
+    };
+    let end: u32 = (i64::from(*ifne_position) + i64::from(*offset)) as u32;
+    // the body of an assert always ends by throwing the AssertionError
+    let last: &(u32, BytecodeInstruction) = code.iter().take_while(|(p, _)| *p < end).last()?;
+    matches!(last.1, BytecodeInstruction::AThrow {}).then_some((start, end))
+}
+
+/// Returns the range of the static initializer computing `$assertionsDisabled`, which javac emits as:
+/// ```text
+/// ldc           #n    // class ThisClass
+/// invokevirtual #m    // Method java/lang/Class.desiredAssertionStatus:()Z
+/// ifne          L1
+/// iconst_1
+/// goto          L2
+/// L1: iconst_0
+/// L2: putstatic #k    // Field $assertionsDisabled:Z
+/// ```
+fn initialization_range(cf: &ClassFile, code: &[(u32, BytecodeInstruction)]) -> Option<(u32, u32)> {
+    let store: usize = code.iter().position(|(_, i)| match i {
+        BytecodeInstruction::PutStatic { field_ref_index } => {
+            is_assertions_disabled_ref(cf, *field_ref_index)
+        }
+        _ => false,
+    })?;
+    let call: usize = code[..store].iter().rposition(|(_, i)| match i {
+        BytecodeInstruction::InvokeVirtual { method_ref_index } => {
+            cf.constant_pool.get_method_ref(*method_ref_index)
+                == "java/lang/Class.desiredAssertionStatus:()Z"
+        }
+        _ => false,
+    })?;
+    if call == 0
+        || !matches!(
+            code[call - 1].1,
+            BytecodeInstruction::Ldc { .. } | BytecodeInstruction::LdcW { .. }
+        )
+    {
+        return None;
+    }
+    let end: u32 = code.get(store + 1).map(|(p, _)| *p)?;
+    Some((code[call - 1].0, end))
+}
+
+/**
+ * Removes the attributes only used by debuggers: SourceFile, LineNumberTable, LocalVariableTable and
+ * LocalVariableTypeTable, also from the attributes of each Code attribute. The constant pool entries they referenced are
+ * left untouched.
+ */
+pub fn strip_debug(cf: &mut ClassFile) {
+    strip(&mut cf.attributes);
+    for field in cf.fields.iter_mut() {
+        strip(&mut field.attributes);
+    }
+    for method in cf.methods.iter_mut() {
+        strip(&mut method.attributes);
+    }
+}
+
+fn strip(attributes: &mut Vec<AttributeInfo>) {
+    attributes.retain(|attribute| !is_debug_attribute(attribute));
+    for attribute in attributes.iter_mut() {
+        if let AttributeInfo::Code { attributes, .. } = attribute {
+            strip(attributes);
+        }
+    }
+}
+
+fn is_debug_attribute(attribute: &AttributeInfo) -> bool {
+    matches!(
+        attribute,
+        AttributeInfo::SourceFile { .. }
+            | AttributeInfo::LineNumberTable { .. }
+            | AttributeInfo::LocalVariableTable { .. }
+            | AttributeInfo::LocalVariableTypeTable { .. }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        access_flags::MethodAccessFlags, analysis::uses_assertions,
+        classfile::parse_class_file_from_bytes, remapping::reverse_constant_pool,
+        verify::verify_method, writer::write_class_file,
+    };
+
+    fn canonical(cf: &ClassFile) -> ClassFile {
+        let mut result: ClassFile = cf.clone();
+        canonicalize(&mut result);
+        result
+    }
+
+    #[test]
+    fn canonical_form_is_stable() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Employee.class")).unwrap();
+        let once: Vec<u8> = write_class_file(&canonical(&cf));
+        let twice: Vec<u8> =
+            write_class_file(&canonical(&parse_class_file_from_bytes(&once).unwrap()));
+        assert_eq!(once, twice);
+
+        let canonical: ClassFile = parse_class_file_from_bytes(&once).unwrap();
+        assert_eq!(canonical.constant_pool.len(), cf.constant_pool.len());
+        assert_eq!(canonical.class_name(), "Employee");
+        for method in canonical.methods.iter() {
+            assert_eq!(verify_method(&canonical.constant_pool, method), Ok(()));
+        }
+    }
+
+    #[test]
+    fn canonical_form_ignores_the_original_order() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Employee.class")).unwrap();
+        let reversed: ClassFile = reverse_constant_pool(&cf);
+        assert_ne!(write_class_file(&reversed), write_class_file(&cf));
+        assert_eq!(
+            write_class_file(&canonical(&reversed)),
+            write_class_file(&canonical(&cf))
+        );
+    }
+
+    #[test]
+    fn entries_come_after_their_references() {
+        let cf: ClassFile = canonical(
+            &parse_class_file_from_bytes(include_bytes!("../../e2e/data/TimeUnit.class")).unwrap(),
+        );
+        let cp: &ConstantPool = &cf.constant_pool;
+        for i in 0..cp.len() {
+            for reference in references(&cp[i as u16]) {
+                assert!(
+                    usize::from(reference) <= i,
+                    "Entry #{} references #{reference}.",
+                    i + 1
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_attributes_are_left_untouched() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/InvisibleAnnotations.class");
+        assert_eq!(
+            write_class_file(&canonical(&parse_class_file_from_bytes(bytes).unwrap())),
+            bytes
+        );
+    }
+
+    fn member_names(cf: &ClassFile) -> Vec<String> {
+        cf.fields
+            .iter()
+            .map(|field| field.name_index)
+            .chain(cf.methods.iter().map(|method| method.name_index))
+            .map(|idx| cf.constant_pool.get_utf8_content(idx))
+            .collect()
+    }
+
+    fn renamed(cf: &ClassFile) -> ClassFile {
+        let mut result: ClassFile = cf.clone();
+        let renamer: SequentialRenamer = SequentialRenamer::new(cf);
+        rename_identifiers(&mut result, &|name| renamer.rename(name));
+        result
+    }
+
+    #[test]
+    fn sequential_names() {
+        let names: Vec<String> = [0, 1, 25, 26, 27, 701, 702]
+            .into_iter()
+            .map(sequential_name)
+            .collect();
+        assert_eq!(names, ["a", "b", "z", "aa", "ab", "zz", "aaa"]);
+    }
+
+    #[test]
+    fn private_fields_are_renamed() {
+        let cf: ClassFile = renamed(
+            &parse_class_file_from_bytes(include_bytes!("../../e2e/data/Employee.class")).unwrap(),
+        );
+        let names: Vec<String> = member_names(&cf);
+        assert_eq!(
+            names[..10],
+            ["a", "b", "c", "d", "e", "f", "g", "h", "i", "j"]
+        );
+        assert!(names[10..].contains(&"<init>".to_owned()));
+        assert!(names[10..].contains(&"getFirstName".to_owned()));
+
+        let cf: ClassFile = parse_class_file_from_bytes(&write_class_file(&cf)).unwrap();
+        let mut field_accesses: Vec<String> = Vec::new();
+        for method in cf.methods.iter() {
+            assert_eq!(verify_method(&cf.constant_pool, method), Ok(()));
+            let Some(AttributeInfo::Code { code, .. }) =
+                find_attribute(&method.attributes, AttributeKind::Code)
+            else {
+                continue;
+            };
+            for (_, instruction) in code.iter() {
+                if let BytecodeInstruction::GetField {
+                    field_ref_index, ..
+                } = instruction
+                {
+                    field_accesses.push(cf.constant_pool.get_field_ref(*field_ref_index));
+                }
+            }
+        }
+        assert!(field_accesses.contains(&"Employee.b:Ljava/lang/String;".to_owned()));
+        assert!(
+            field_accesses
+                .iter()
+                .all(|access| !access.contains("firstName"))
+        );
+    }
+
+    #[test]
+    fn private_lambda_is_renamed() {
+        let cf: ClassFile = renamed(
+            &parse_class_file_from_bytes(include_bytes!("../../e2e/data/MethodConstants.class"))
+                .unwrap(),
+        );
+        let cp: &ConstantPool = &cf.constant_pool;
+        let names: Vec<String> = member_names(&cf);
+        assert!(names.contains(&"a".to_owned()));
+        assert!(!names.contains(&"lambda$main$0".to_owned()));
+
+        let Some(AttributeInfo::BootstrapMethods { methods, .. }) =
+            find_attribute(&cf.attributes, AttributeKind::BootstrapMethods)
+        else {
+            unreachable!()
+        };
+        let handles: Vec<String> = methods
+            .iter()
+            .flat_map(|method| method.bootstrap_arguments.iter())
+            .filter_map(|idx| match cp[*idx - 1] {
+                ConstantPoolInfo::MethodHandle {
+                    reference_index, ..
+                } => Some(cp.get_method_ref(reference_index)),
+                _ => None,
+            })
+            .collect();
+        assert!(handles.contains(&"MethodConstants.a:()V".to_owned()));
+        for method in cf.methods.iter() {
+            assert_eq!(verify_method(cp, method), Ok(()));
+        }
+    }
+
+    #[test]
+    fn nestmates_are_left_untouched() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Sealed.class");
+        assert_eq!(
+            write_class_file(&renamed(&parse_class_file_from_bytes(bytes).unwrap())),
+            bytes
+        );
+    }
+
+    #[test]
+    fn native_methods_keep_their_names() {
+        let mut cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/MethodConstants.class"))
+                .unwrap();
+        for method in cf.methods.iter_mut() {
+            if method.access_flags.contains(MethodAccessFlag::Private) {
+                method.access_flags = MethodAccessFlags::from(
+                    method.access_flags.to_u16() | MethodAccessFlag::Native as u16,
+                );
+            }
+        }
+        assert_eq!(member_names(&renamed(&cf)), member_names(&cf));
+    }
+
+    #[test]
+    fn strategy_gives_the_new_names() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Employee.class")).unwrap();
+        let mut result: ClassFile = cf.clone();
+        rename_identifiers(&mut result, &|name| format!("{name}$"));
+        let names: Vec<String> = member_names(&result);
+        assert_eq!(names[..2], ["id$", "firstName$"]);
+        assert!(names.contains(&"getFirstName".to_owned()));
+    }
+
+    fn stripped_assertions(cf: &ClassFile) -> ClassFile {
+        let mut result: ClassFile = cf.clone();
+        strip_assertions(&mut result);
+        result
+    }
+
+    fn mnemonics(cf: &ClassFile, method_name: &str) -> Vec<&'static str> {
+        let method = cf.methods_named(method_name).next().unwrap();
+        match find_attribute(&method.attributes, AttributeKind::Code) {
+            Some(AttributeInfo::Code { code, .. }) => {
+                code.iter().map(|(_, i)| i.mnemonic()).collect()
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn assertions_are_removed() {
+        let original: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Assertions.class")).unwrap();
+        assert!(uses_assertions(&original));
+
+        let cf: ClassFile =
+            parse_class_file_from_bytes(&write_class_file(&stripped_assertions(&original)))
+                .unwrap();
+        assert!(!uses_assertions(&cf));
+        assert_eq!(cf.fields.len(), original.fields.len() - 1);
+        assert_eq!(
+            mnemonics(&cf, "getValue"),
+            vec!["aload_0", "getfield", "ireturn"]
+        );
+        assert_eq!(
+            mnemonics(&cf, "<init>"),
+            vec![
+                "aload_0",
+                "invokespecial",
+                "aload_0",
+                "iload_1",
+                "putfield",
+                "return"
+            ]
+        );
+        assert_eq!(mnemonics(&cf, "<clinit>"), vec!["return"]);
+        for method in cf.methods.iter() {
+            assert_eq!(verify_method(&cf.constant_pool, method), Ok(()));
+        }
+    }
+
+    #[test]
+    fn class_without_assertions_is_unchanged() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Employee.class");
+        assert_eq!(
+            write_class_file(&stripped_assertions(
+                &parse_class_file_from_bytes(bytes).unwrap()
+            )),
+            bytes
+        );
+    }
+
+    fn stripped_debug(cf: &ClassFile) -> ClassFile {
+        let mut result: ClassFile = cf.clone();
+        strip_debug(&mut result);
+        result
+    }
+
+    #[test]
+    fn debug_attributes_are_removed() {
+        let original: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Employee.class")).unwrap();
+        assert!(original.source_file().is_some());
+
+        let bytes: Vec<u8> = write_class_file(&stripped_debug(&original));
+        let cf: ClassFile = parse_class_file_from_bytes(&bytes).unwrap();
+        assert!(cf.source_file().is_none());
+        assert_eq!(cf.constant_pool.len(), original.constant_pool.len());
+        for method in cf.methods.iter() {
+            let Some(AttributeInfo::Code { attributes, .. }) =
+                find_attribute(&method.attributes, AttributeKind::Code)
+            else {
+                continue;
+            };
+            assert!(attributes.iter().all(|a| !is_debug_attribute(a)));
+            assert_eq!(verify_method(&cf.constant_pool, method), Ok(()));
+        }
+        assert_eq!(write_class_file(&cf), bytes);
+    }
+
+    #[test]
+    fn local_variable_type_tables_are_removed() {
+        let kinds = |cf: &ClassFile| -> Vec<AttributeKind> {
+            let mut kinds: Vec<AttributeKind> = Vec::new();
+            for method in cf.methods.iter() {
+                if let Some(AttributeInfo::Code { attributes, .. }) =
+                    find_attribute(&method.attributes, AttributeKind::Code)
+                {
+                    kinds.extend(attributes.iter().map(AttributeInfo::kind));
+                }
+            }
+            kinds
+        };
+        let original: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Parameters.class")).unwrap();
+        assert!(kinds(&original).contains(&AttributeKind::LocalVariableTable));
+        assert!(kinds(&original).contains(&AttributeKind::LocalVariableTypeTable));
+
+        let cf: ClassFile =
+            parse_class_file_from_bytes(&write_class_file(&stripped_debug(&original))).unwrap();
+        assert!(!kinds(&cf).contains(&AttributeKind::LocalVariableTable));
+        assert!(!kinds(&cf).contains(&AttributeKind::LocalVariableTypeTable));
+        assert!(!kinds(&cf).contains(&AttributeKind::LineNumberTable));
+        // the names of the parameters are not debug information
+        for (method, original_method) in cf.methods.iter().zip(original.methods.iter()) {
+            assert_eq!(
+                find_attribute(&method.attributes, AttributeKind::MethodParameters).is_some(),
+                find_attribute(&original_method.attributes, AttributeKind::MethodParameters)
+                    .is_some()
+            );
+        }
+    }
+}
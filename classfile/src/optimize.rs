@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+use crate::{
+    classfile::ClassFile,
+    constant_pool::{ConstantPool, ConstantPoolInfo},
+    remapping::{has_unknown_attributes, rebuild_constant_pool},
+};
+
+/**
+ * Merges structurally-identical constant pool entries into a single one and rewrites all references to it. Classes
+ * with attributes kept as raw bytes are left unchanged, since the indices inside those attributes cannot be rewritten.
+ */
+pub fn dedup_constant_pool(cf: &mut ClassFile) {
+    if has_unknown_attributes(cf) {
+        return;
+    }
+    // Merging some entries (like two identical Utf8) may make other entries identical (like two Class pointing to
+    // them), so we repeat until nothing changes.
+    loop {
+        let (remapping, num_duplicates) = find_duplicates(&cf.constant_pool);
+        if num_duplicates == 0 {
+            return;
+        }
+        *cf = rebuild_constant_pool(cf, &remapping);
+    }
+}
+
+/// Returns the old-to-new remapping which merges all duplicated entries and the number of duplicates found.
+fn find_duplicates(cp: &ConstantPool) -> (HashMap<u16, u16>, usize) {
+    let mut canonical: HashMap<&ConstantPoolInfo, u16> = HashMap::new();
+    let mut remapping: HashMap<u16, u16> = HashMap::new();
+    let mut num_duplicates: usize = 0;
+    let mut next_index: u16 = 1;
+    for i in 0..cp.len() {
+        let entry: &ConstantPoolInfo = &cp[i as u16];
+        let old_index: u16 = (i + 1) as u16;
+        match entry {
+            ConstantPoolInfo::Null {} => {}
+            _ => match canonical.get(entry) {
+                Some(new_index) => {
+                    remapping.insert(old_index, *new_index);
+                    num_duplicates += 1;
+                }
+                None => {
+                    canonical.insert(entry, next_index);
+                    remapping.insert(old_index, next_index);
+                    next_index += match entry {
+                        ConstantPoolInfo::Long { .. } | ConstantPoolInfo::Double { .. } => 2,
+                        _ => 1,
+                    };
+                }
+            },
+        }
+    }
+    (remapping, num_duplicates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{classfile::parse_class_file_from_bytes, writer::write_class_file};
+
+    /// Appends a copy of the name of the class and a Class entry pointing to it, and makes this_class point to them.
+    fn duplicate_this_class(cf: &mut ClassFile) {
+        let ConstantPoolInfo::Class { name_index } = cf.constant_pool[cf.this_class - 1] else {
+            unreachable!()
+        };
+        let name: ConstantPoolInfo = cf.constant_pool[name_index - 1].clone();
+        let duplicated_name_index: u16 = cf.constant_pool.add(name).unwrap();
+        cf.this_class = cf
+            .constant_pool
+            .add(ConstantPoolInfo::Class {
+                name_index: duplicated_name_index,
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn no_duplicates_is_unchanged() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Employee.class");
+        let mut cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        dedup_constant_pool(&mut cf);
+        assert_eq!(write_class_file(&cf), bytes);
+    }
+
+    #[test]
+    fn duplicated_entries_collapse() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Employee.class");
+        let original: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        let mut cf: ClassFile = original.clone();
+
+        // duplicate the name of each method and the class entry referenced by this_class
+        for method in cf.methods.iter_mut() {
+            let name: ConstantPoolInfo = cf.constant_pool[method.name_index - 1].clone();
+            method.name_index = cf.constant_pool.add(name).unwrap();
+        }
+        duplicate_this_class(&mut cf);
+        assert!(cf.constant_pool.len() > original.constant_pool.len());
+
+        dedup_constant_pool(&mut cf);
+        assert_eq!(cf.constant_pool.len(), original.constant_pool.len());
+        assert_eq!(cf.class_name(), "Employee");
+        for (a, b) in original.methods.iter().zip(cf.methods.iter()) {
+            assert_eq!(
+                original.constant_pool.get_utf8_content(a.name_index),
+                cf.constant_pool.get_utf8_content(b.name_index)
+            );
+        }
+        assert_eq!(write_class_file(&cf), bytes);
+    }
+
+    #[test]
+    fn unknown_attributes_are_left_untouched() {
+        let mut cf: ClassFile = parse_class_file_from_bytes(include_bytes!(
+            "../../e2e/data/InvisibleAnnotations.class"
+        ))
+        .unwrap();
+        duplicate_this_class(&mut cf);
+        let bytes: Vec<u8> = write_class_file(&cf);
+        dedup_constant_pool(&mut cf);
+        assert_eq!(write_class_file(&cf), bytes);
+    }
+}
@@ -0,0 +1,701 @@
+use std::collections::HashMap;
+
+use crate::{
+    attributes::{Annotation, AttributeInfo, ElementValue, StackMapFrame, VerificationTypeInfo},
+    bytecode::BytecodeInstruction,
+    classfile::ClassFile,
+    constant_pool::{ConstantPool, ConstantPoolInfo},
+};
+
+/**
+ * Rewrites every constant pool index inside the given class file (including the ones inside the constant pool
+ * entries themselves) with the result of the given function. Indices which are allowed to be zero, like the
+ * `super_class` of java.lang.Object, are left untouched when zero.
+ */
+pub fn remap_constant_pool_indices(cf: &mut ClassFile, f: &mut dyn FnMut(u16) -> u16) {
+    for entry in cf.constant_pool.entries.iter_mut() {
+        remap_constant_pool_entry(entry, f);
+    }
+    remap_indices_outside_constant_pool(cf, f);
+}
+
+/**
+ * Like [`remap_constant_pool_indices`], but leaves the constant pool entries untouched.
+ */
+fn remap_indices_outside_constant_pool(cf: &mut ClassFile, f: &mut dyn FnMut(u16) -> u16) {
+    cf.this_class = f(cf.this_class);
+    cf.super_class = remap_optional(cf.super_class, f);
+    for interface in cf.interfaces.iter_mut() {
+        *interface = f(*interface);
+    }
+    for field in cf.fields.iter_mut() {
+        field.name_index = f(field.name_index);
+        field.descriptor_index = f(field.descriptor_index);
+        remap_attributes(&mut field.attributes, f);
+    }
+    for method in cf.methods.iter_mut() {
+        method.name_index = f(method.name_index);
+        method.descriptor_index = f(method.descriptor_index);
+        remap_attributes(&mut method.attributes, f);
+    }
+    remap_attributes(&mut cf.attributes, f);
+}
+
+/**
+ * Returns a new class file whose constant pool contains the entry at each old index (1-based) in the given mapping
+ * at its new index, with all references rewritten accordingly. Many old indices can be mapped to the same new index,
+ * as long as the entries are identical after remapping. Every new index must be covered, except for the slots right
+ * after Long and Double entries.
+ */
+pub fn rebuild_constant_pool(cf: &ClassFile, remapping: &HashMap<u16, u16>) -> ClassFile {
+    let mut result: ClassFile = cf.clone();
+    remap_constant_pool_indices(&mut result, &mut |idx| {
+        *remapping
+            .get(&idx)
+            .unwrap_or_else(|| panic!("Constant pool index #{idx} has no remapping."))
+    });
+
+    let new_len: usize = remapping
+        .iter()
+        .map(|(old, new)| {
+            let is_wide: bool = matches!(
+                result.constant_pool[old - 1],
+                ConstantPoolInfo::Long { .. } | ConstantPoolInfo::Double { .. }
+            );
+            (*new as usize) + if is_wide { 1 } else { 0 }
+        })
+        .max()
+        .unwrap_or(0);
+    let mut entries: Vec<Option<ConstantPoolInfo>> = vec![None; new_len];
+    for (old, new) in remapping.iter() {
+        let entry: &ConstantPoolInfo = &result.constant_pool[old - 1];
+        assert!(
+            !matches!(entry, ConstantPoolInfo::Null {}),
+            "Constant pool index #{old} is not a valid entry."
+        );
+        let new_idx: usize = (*new as usize) - 1;
+        if matches!(
+            entry,
+            ConstantPoolInfo::Long { .. } | ConstantPoolInfo::Double { .. }
+        ) {
+            entries[new_idx + 1] = Some(ConstantPoolInfo::Null {});
+        }
+        entries[new_idx] = Some(entry.clone());
+    }
+
+    result.constant_pool = ConstantPool {
+        entries: entries
+            .into_iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                entry.unwrap_or_else(|| panic!("No entry was remapped to index #{}.", i + 1))
+            })
+            .collect(),
+    };
+    result
+}
+
+/**
+ * Returns the same class with the order of its constant pool entries reversed, to check that the rest of the code does
+ * not depend on where the entries are.
+ */
+#[cfg(test)]
+pub(crate) fn reverse_constant_pool(cf: &ClassFile) -> ClassFile {
+    let cp: &ConstantPool = &cf.constant_pool;
+    let mut remapping: HashMap<u16, u16> = HashMap::new();
+    let mut next_index: u16 = 1;
+    for i in (0..cp.len()).rev() {
+        match cp[i as u16] {
+            ConstantPoolInfo::Null {} => {}
+            ConstantPoolInfo::Long { .. } | ConstantPoolInfo::Double { .. } => {
+                remapping.insert((i + 1) as u16, next_index);
+                next_index += 2;
+            }
+            _ => {
+                remapping.insert((i + 1) as u16, next_index);
+                next_index += 1;
+            }
+        }
+    }
+    rebuild_constant_pool(cf, &remapping)
+}
+
+/**
+ * Returns a new class file without the constant pool entries which are not referenced by the rest of the class, either
+ * directly or through other entries, together with the old-to-new remapping of the kept entries, as computed by
+ * [`ConstantPool::compact`]. Classes with attributes kept as raw bytes are returned unchanged, with every entry mapped
+ * to itself, since the entries referenced only from those attributes cannot be found.
+ */
+pub fn compact_constant_pool(cf: &ClassFile) -> (ClassFile, HashMap<u16, u16>) {
+    if has_unknown_attributes(cf) {
+        let identity: HashMap<u16, u16> = (1..=cf.constant_pool.len() as u16)
+            .filter(|idx| !matches!(cf.constant_pool[idx - 1], ConstantPoolInfo::Null {}))
+            .map(|idx| (idx, idx))
+            .collect();
+        return (cf.clone(), identity);
+    }
+    let mut result: ClassFile = cf.clone();
+    let mut roots: Vec<u16> = Vec::new();
+    remap_indices_outside_constant_pool(&mut result, &mut |idx| {
+        roots.push(idx);
+        idx
+    });
+    let remapping: HashMap<u16, u16> = result.constant_pool.compact(&roots);
+    remap_indices_outside_constant_pool(&mut result, &mut |idx| remapping[&idx]);
+    (result, remapping)
+}
+
+/**
+ * Returns true if the class, one of its fields or methods, or one of their Code attributes has an attribute kept as
+ * raw bytes. The constant pool indices inside such attributes are not known, so the existing entries of the class
+ * cannot be moved without corrupting them: new entries can only be appended.
+ */
+pub fn has_unknown_attributes(cf: &ClassFile) -> bool {
+    let is_unknown = |attribute: &AttributeInfo| match attribute {
+        AttributeInfo::Unknown { .. } => true,
+        AttributeInfo::Code { attributes, .. } => attributes
+            .iter()
+            .any(|attribute| matches!(attribute, AttributeInfo::Unknown { .. })),
+        _ => false,
+    };
+    cf.attributes.iter().any(is_unknown)
+        || cf
+            .fields
+            .iter()
+            .any(|field| field.attributes.iter().any(is_unknown))
+        || cf
+            .methods
+            .iter()
+            .any(|method| method.attributes.iter().any(is_unknown))
+}
+
+fn remap_optional(idx: u16, f: &mut dyn FnMut(u16) -> u16) -> u16 {
+    if idx == 0 { 0 } else { f(idx) }
+}
+
+/**
+ * Rewrites the constant pool indices referenced by the given entry with the result of the given function.
+ */
+pub fn remap_constant_pool_entry(entry: &mut ConstantPoolInfo, f: &mut dyn FnMut(u16) -> u16) {
+    match entry {
+        ConstantPoolInfo::Null {}
+        | ConstantPoolInfo::Utf8 { .. }
+        | ConstantPoolInfo::Integer { .. }
+        | ConstantPoolInfo::Float { .. }
+        | ConstantPoolInfo::Long { .. }
+        | ConstantPoolInfo::Double { .. } => {}
+        ConstantPoolInfo::String { string_index } => *string_index = f(*string_index),
+        ConstantPoolInfo::Class { name_index }
+        | ConstantPoolInfo::Module { name_index }
+        | ConstantPoolInfo::Package { name_index } => *name_index = f(*name_index),
+        ConstantPoolInfo::FieldRef {
+            class_index,
+            name_and_type_index,
+        }
+        | ConstantPoolInfo::MethodRef {
+            class_index,
+            name_and_type_index,
+        }
+        | ConstantPoolInfo::InterfaceMethodRef {
+            class_index,
+            name_and_type_index,
+        } => {
+            *class_index = f(*class_index);
+            *name_and_type_index = f(*name_and_type_index);
+        }
+        ConstantPoolInfo::NameAndType {
+            name_index,
+            descriptor_index,
+        } => {
+            *name_index = f(*name_index);
+            *descriptor_index = f(*descriptor_index);
+        }
+        ConstantPoolInfo::MethodType { descriptor_index } => {
+            *descriptor_index = f(*descriptor_index)
+        }
+        ConstantPoolInfo::MethodHandle {
+            reference_index, ..
+        } => *reference_index = f(*reference_index),
+        // the bootstrap method index refers to the BootstrapMethods attribute, not to the constant pool
+        ConstantPoolInfo::Dynamic {
+            name_and_type_index,
+            ..
+        }
+        | ConstantPoolInfo::InvokeDynamic {
+            name_and_type_index,
+            ..
+        } => *name_and_type_index = f(*name_and_type_index),
+    }
+}
+
+fn remap_attributes(attributes: &mut [AttributeInfo], f: &mut dyn FnMut(u16) -> u16) {
+    for attribute in attributes.iter_mut() {
+        remap_attribute(attribute, f);
+    }
+}
+
+fn remap_attribute(attribute: &mut AttributeInfo, f: &mut dyn FnMut(u16) -> u16) {
+    match attribute {
+        AttributeInfo::Code {
+            name_index,
+            code,
+            exception_table,
+            attributes,
+            ..
+        } => {
+            *name_index = f(*name_index);
+            for (_, instruction) in code.iter_mut() {
+                remap_instruction(instruction, f);
+            }
+            for entry in exception_table.iter_mut() {
+                entry.catch_type = remap_optional(entry.catch_type, f);
+            }
+            remap_attributes(attributes, f);
+        }
+        AttributeInfo::LineNumberTable { name_index, .. } => *name_index = f(*name_index),
+        AttributeInfo::LocalVariableTable {
+            name_index,
+            local_variable_table,
+        } => {
+            *name_index = f(*name_index);
+            for entry in local_variable_table.iter_mut() {
+                entry.name_index = f(entry.name_index);
+                entry.descriptor_index = f(entry.descriptor_index);
+            }
+        }
+        AttributeInfo::LocalVariableTypeTable {
+            name_index,
+            local_variable_type_table,
+        } => {
+            *name_index = f(*name_index);
+            for entry in local_variable_type_table.iter_mut() {
+                entry.name_index = f(entry.name_index);
+                entry.signature_index = f(entry.signature_index);
+            }
+        }
+        AttributeInfo::StackMapTable {
+            name_index,
+            stack_map_table,
+        } => {
+            *name_index = f(*name_index);
+            for frame in stack_map_table.iter_mut() {
+                remap_stack_map_frame(frame, f);
+            }
+        }
+        AttributeInfo::SourceFile {
+            name_index,
+            source_file_index,
+        } => {
+            *name_index = f(*name_index);
+            *source_file_index = f(*source_file_index);
+        }
+        AttributeInfo::BootstrapMethods {
+            name_index,
+            methods,
+        } => {
+            *name_index = f(*name_index);
+            for method in methods.iter_mut() {
+                method.bootstrap_method_ref = f(method.bootstrap_method_ref);
+                for arg in method.bootstrap_arguments.iter_mut() {
+                    *arg = f(*arg);
+                }
+            }
+        }
+        AttributeInfo::InnerClasses {
+            name_index,
+            classes,
+        } => {
+            *name_index = f(*name_index);
+            for class in classes.iter_mut() {
+                class.inner_class_info_index = f(class.inner_class_info_index);
+                class.outer_class_info_index = remap_optional(class.outer_class_info_index, f);
+                class.inner_name_index = remap_optional(class.inner_name_index, f);
+            }
+        }
+        AttributeInfo::MethodParameters {
+            name_index,
+            parameters,
+        } => {
+            *name_index = f(*name_index);
+            for parameter in parameters.iter_mut() {
+                parameter.name_index = remap_optional(parameter.name_index, f);
+            }
+        }
+        AttributeInfo::Record {
+            name_index,
+            components,
+        } => {
+            *name_index = f(*name_index);
+            for component in components.iter_mut() {
+                component.name_index = f(component.name_index);
+                component.descriptor_index = f(component.descriptor_index);
+                remap_attributes(&mut component.attributes, f);
+            }
+        }
+        AttributeInfo::Signature {
+            name_index,
+            signature_index,
+        } => {
+            *name_index = f(*name_index);
+            *signature_index = f(*signature_index);
+        }
+        AttributeInfo::NestMembers {
+            name_index,
+            classes,
+        } => {
+            *name_index = f(*name_index);
+            for class in classes.iter_mut() {
+                *class = f(*class);
+            }
+        }
+        AttributeInfo::RuntimeVisibleAnnotations {
+            name_index,
+            annotations,
+        } => {
+            *name_index = f(*name_index);
+            for annotation in annotations.iter_mut() {
+                remap_annotation(annotation, f);
+            }
+        }
+        AttributeInfo::ConstantValue {
+            name_index,
+            constant_value_index,
+        } => {
+            *name_index = f(*name_index);
+            *constant_value_index = f(*constant_value_index);
+        }
+        AttributeInfo::Exceptions {
+            name_index,
+            exception_indices,
+        } => {
+            *name_index = f(*name_index);
+            for exception in exception_indices.iter_mut() {
+                *exception = f(*exception);
+            }
+        }
+        AttributeInfo::EnclosingMethod {
+            name_index,
+            class_index,
+            method_index,
+        } => {
+            *name_index = f(*name_index);
+            *class_index = f(*class_index);
+            *method_index = remap_optional(*method_index, f);
+        }
+        AttributeInfo::NestHost {
+            name_index,
+            host_class_index,
+        } => {
+            *name_index = f(*name_index);
+            *host_class_index = f(*host_class_index);
+        }
+        AttributeInfo::PermittedSubclasses {
+            name_index,
+            classes,
+        } => {
+            *name_index = f(*name_index);
+            for class in classes.iter_mut() {
+                *class = f(*class);
+            }
+        }
+        AttributeInfo::Module {
+            name_index,
+            module_name_index,
+            module_version_index,
+            requires,
+            exports,
+            opens,
+            uses,
+            provides,
+            ..
+        } => {
+            *name_index = f(*name_index);
+            *module_name_index = f(*module_name_index);
+            *module_version_index = remap_optional(*module_version_index, f);
+            for entry in requires.iter_mut() {
+                entry.requires_index = f(entry.requires_index);
+                entry.requires_version_index = remap_optional(entry.requires_version_index, f);
+            }
+            for entry in exports.iter_mut() {
+                entry.exports_index = f(entry.exports_index);
+                for module in entry.exports_to_index.iter_mut() {
+                    *module = f(*module);
+                }
+            }
+            for entry in opens.iter_mut() {
+                entry.opens_index = f(entry.opens_index);
+                for module in entry.opens_to_index.iter_mut() {
+                    *module = f(*module);
+                }
+            }
+            for service in uses.iter_mut() {
+                *service = f(*service);
+            }
+            for entry in provides.iter_mut() {
+                entry.provides_index = f(entry.provides_index);
+                for implementation in entry.provides_with_index.iter_mut() {
+                    *implementation = f(*implementation);
+                }
+            }
+        }
+        AttributeInfo::ModulePackages {
+            name_index,
+            packages,
+        } => {
+            *name_index = f(*name_index);
+            for package in packages.iter_mut() {
+                *package = f(*package);
+            }
+        }
+        AttributeInfo::ModuleMainClass {
+            name_index,
+            main_class_index,
+        } => {
+            *name_index = f(*name_index);
+            *main_class_index = f(*main_class_index);
+        }
+        AttributeInfo::Deprecated { name_index }
+        | AttributeInfo::Synthetic { name_index }
+        | AttributeInfo::Unknown { name_index, .. } => {
+            *name_index = f(*name_index);
+        }
+    }
+}
+
+pub(crate) fn remap_instruction(
+    instruction: &mut BytecodeInstruction,
+    f: &mut dyn FnMut(u16) -> u16,
+) {
+    match instruction {
+        BytecodeInstruction::Ldc {
+            constant_pool_index,
+        } => {
+            let new_index: u16 = f(*constant_pool_index as u16);
+            *constant_pool_index = u8::try_from(new_index).unwrap_or_else(|_| {
+                panic!("Constant pool index #{new_index} does not fit into an ldc instruction.")
+            });
+        }
+        BytecodeInstruction::LdcW {
+            constant_pool_index,
+        }
+        | BytecodeInstruction::Ldc2W {
+            constant_pool_index,
+        }
+        | BytecodeInstruction::ANewArray {
+            constant_pool_index,
+        }
+        | BytecodeInstruction::MultiANewArray {
+            constant_pool_index,
+            ..
+        }
+        | BytecodeInstruction::New {
+            constant_pool_index,
+        }
+        | BytecodeInstruction::InvokeDynamic {
+            constant_pool_index,
+        }
+        | BytecodeInstruction::InvokeInterface {
+            constant_pool_index,
+            ..
+        }
+        | BytecodeInstruction::CheckCast {
+            constant_pool_index,
+        }
+        | BytecodeInstruction::Instanceof {
+            constant_pool_index,
+        } => *constant_pool_index = f(*constant_pool_index),
+        BytecodeInstruction::GetStatic { field_ref_index }
+        | BytecodeInstruction::PutStatic { field_ref_index }
+        | BytecodeInstruction::GetField { field_ref_index }
+        | BytecodeInstruction::PutField { field_ref_index } => {
+            *field_ref_index = f(*field_ref_index)
+        }
+        BytecodeInstruction::InvokeSpecial { method_ref_index }
+        | BytecodeInstruction::InvokeStatic { method_ref_index }
+        | BytecodeInstruction::InvokeVirtual { method_ref_index } => {
+            *method_ref_index = f(*method_ref_index)
+        }
+        _ => {}
+    }
+}
+
+fn remap_stack_map_frame(frame: &mut StackMapFrame, f: &mut dyn FnMut(u16) -> u16) {
+    match frame {
+        StackMapFrame::SameFrame { .. }
+        | StackMapFrame::ChopFrame { .. }
+        | StackMapFrame::SameFrameExtended { .. } => {}
+        StackMapFrame::SameLocals1StackItemFrame { stack, .. }
+        | StackMapFrame::SameLocals1StackItemFrameExtended { stack, .. } => {
+            remap_verification_type_info(stack, f)
+        }
+        StackMapFrame::AppendFrame { locals, .. } => {
+            for local in locals.iter_mut() {
+                remap_verification_type_info(local, f);
+            }
+        }
+        StackMapFrame::FullFrame { locals, stack, .. } => {
+            for vti in locals.iter_mut().chain(stack.iter_mut()) {
+                remap_verification_type_info(vti, f);
+            }
+        }
+    }
+}
+
+fn remap_verification_type_info(vti: &mut VerificationTypeInfo, f: &mut dyn FnMut(u16) -> u16) {
+    if let VerificationTypeInfo::ObjectVariable {
+        constant_pool_index,
+    } = vti
+    {
+        *constant_pool_index = f(*constant_pool_index);
+    }
+}
+
+fn remap_annotation(annotation: &mut Annotation, f: &mut dyn FnMut(u16) -> u16) {
+    annotation.type_index = f(annotation.type_index);
+    for pair in annotation.element_value_pairs.iter_mut() {
+        pair.element_name_index = f(pair.element_name_index);
+        remap_element_value(&mut pair.value, f);
+    }
+}
+
+fn remap_element_value(value: &mut ElementValue, f: &mut dyn FnMut(u16) -> u16) {
+    match value {
+        ElementValue::Byte { const_value_index }
+        | ElementValue::Char { const_value_index }
+        | ElementValue::Double { const_value_index }
+        | ElementValue::Float { const_value_index }
+        | ElementValue::Int { const_value_index }
+        | ElementValue::Long { const_value_index }
+        | ElementValue::Short { const_value_index }
+        | ElementValue::Boolean { const_value_index }
+        | ElementValue::String { const_value_index } => *const_value_index = f(*const_value_index),
+        ElementValue::Enum {
+            type_name_index,
+            const_name_index,
+        } => {
+            *type_name_index = f(*type_name_index);
+            *const_name_index = f(*const_name_index);
+        }
+        ElementValue::Class { class_info_index } => *class_info_index = f(*class_info_index),
+        ElementValue::Annotation { value } => remap_annotation(value, f),
+        ElementValue::Array { values } => {
+            for v in values.iter_mut() {
+                remap_element_value(v, f);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        attributes::AttributeKind, classfile::parse_class_file_from_bytes, writer::write_class_file,
+    };
+
+    #[test]
+    fn unknown_attributes() {
+        assert!(!has_unknown_attributes(
+            &parse_class_file_from_bytes(include_bytes!("../../e2e/data/Employee.class")).unwrap()
+        ));
+        // CLASS retention annotations are kept in RuntimeInvisibleAnnotations, which are not modeled
+        let cf: ClassFile = parse_class_file_from_bytes(include_bytes!(
+            "../../e2e/data/InvisibleAnnotations.class"
+        ))
+        .unwrap();
+        assert!(has_unknown_attributes(&cf));
+
+        let mut without_class_attributes: ClassFile = cf.clone();
+        without_class_attributes
+            .attributes
+            .retain(|attribute| attribute.kind() != AttributeKind::Unknown);
+        assert!(has_unknown_attributes(&without_class_attributes));
+    }
+
+    #[test]
+    fn compact_constant_pool_is_unchanged() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Employee.class");
+        let (cf, remapping) = compact_constant_pool(&parse_class_file_from_bytes(bytes).unwrap());
+        assert_eq!(write_class_file(&cf), bytes);
+        assert!(remapping.iter().all(|(old, new)| old == new));
+    }
+
+    #[test]
+    fn unused_entries_are_removed() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Employee.class");
+        let original: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        let original_len: u16 = original.constant_pool.len() as u16;
+
+        let mut cf: ClassFile = original.clone();
+        let unused_name: u16 = cf
+            .constant_pool
+            .add(ConstantPoolInfo::Utf8 {
+                bytes: b"Unused".to_vec(),
+            })
+            .unwrap();
+        cf.constant_pool
+            .add(ConstantPoolInfo::Long {
+                high_bytes: 1,
+                low_bytes: 2,
+            })
+            .unwrap();
+        cf.constant_pool
+            .add(ConstantPoolInfo::Class {
+                name_index: unused_name,
+            })
+            .unwrap();
+        // a used entry after the unused ones moves back
+        let name_index: u16 = cf
+            .constant_pool
+            .add(ConstantPoolInfo::Utf8 {
+                bytes: b"Renamed".to_vec(),
+            })
+            .unwrap();
+        assert_eq!(name_index, original_len + 5);
+        cf.fields[0].name_index = name_index;
+
+        let (compacted, remapping) = compact_constant_pool(&cf);
+        let num_entries: usize = (0..original_len)
+            .filter(|i| !matches!(original.constant_pool[*i], ConstantPoolInfo::Null {}))
+            .count();
+        assert_eq!(remapping.len(), num_entries + 1);
+        assert!(!remapping.contains_key(&unused_name));
+        assert_eq!(remapping[&name_index], original_len + 1);
+
+        let mut reparsed: ClassFile =
+            parse_class_file_from_bytes(&write_class_file(&compacted)).unwrap();
+        assert_eq!(
+            reparsed
+                .constant_pool
+                .get_utf8_content(reparsed.fields[0].name_index),
+            "Renamed"
+        );
+
+        // with the original name back, the class is the original one
+        reparsed.fields[0].name_index = original.fields[0].name_index;
+        assert_eq!(write_class_file(&compact_constant_pool(&reparsed).0), bytes);
+    }
+
+    #[test]
+    fn unknown_attributes_keep_the_constant_pool() {
+        // the CLASS-retention annotations are kept as raw bytes, so their entries look unused
+        let bytes: &[u8] = include_bytes!("../../e2e/data/InvisibleAnnotations.class");
+        let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        assert!(has_unknown_attributes(&cf));
+        let (compacted, remapping) = compact_constant_pool(&cf);
+        assert_eq!(write_class_file(&compacted), bytes);
+        assert!(remapping.iter().all(|(old, new)| old == new));
+
+        let mut with_unused_entry: ClassFile = cf.clone();
+        with_unused_entry
+            .constant_pool
+            .add(ConstantPoolInfo::Utf8 {
+                bytes: b"Unused".to_vec(),
+            })
+            .unwrap();
+        assert_eq!(
+            write_class_file(&compact_constant_pool(&with_unused_entry).0),
+            write_class_file(&with_unused_entry)
+        );
+    }
+}
@@ -0,0 +1,1045 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::{
+    access_flags::MethodAccessFlag,
+    attributes::{AttributeInfo, AttributeKind, ExceptionTableEntry, find_attribute},
+    bytecode::BytecodeInstruction,
+    constant_pool::{ConstantPool, ConstantPoolInfo},
+    methods::MethodInfo,
+};
+
+/// The computational type category of a value on the operand stack.
+/// Long and double values are category 2 and take up two stack words, every other value is category 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueCategory {
+    One,
+    Two,
+}
+
+impl ValueCategory {
+    fn words(self) -> usize {
+        match self {
+            ValueCategory::One => 1,
+            ValueCategory::Two => 2,
+        }
+    }
+
+    fn of(type_char: char) -> ValueCategory {
+        match type_char {
+            'J' | 'D' => ValueCategory::Two,
+            _ => ValueCategory::One,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct VerificationError {
+    /// The bytecode offset of the offending instruction.
+    pub position: u32,
+    pub message: String,
+}
+
+/// Checks that every instruction of the given method finds operands of the right category on the stack and that its
+/// monitors are balanced.
+pub fn verify_method(cp: &ConstantPool, method: &MethodInfo) -> Result<(), VerificationError> {
+    match find_attribute(&method.attributes, AttributeKind::Code) {
+        Some(AttributeInfo::Code {
+            code,
+            exception_table,
+            ..
+        }) => {
+            simulate_operand_stack(cp, code, exception_table)?;
+            check_monitor_balance(code, exception_table).map_err(|offsets| VerificationError {
+                position: offsets[0],
+                message: "monitorenter and monitorexit are not balanced.".to_owned(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Returns the maximum depth of the operand stack, in words, reached while executing the given code.
+pub fn compute_max_stack(
+    cp: &ConstantPool,
+    code: &[(u32, BytecodeInstruction)],
+    exception_table: &[ExceptionTableEntry],
+) -> Result<u16, VerificationError> {
+    let states = simulate_operand_stack(cp, code, exception_table)?;
+    let max_words: usize = states
+        .values()
+        .map(|stack| stack.iter().map(|c| c.words()).sum::<usize>())
+        .max()
+        .unwrap_or(0);
+    Ok(max_words.try_into().unwrap())
+}
+
+/// Returns the number of local variable slots needed by the given method: the ones holding its arguments (and
+/// `this`, for instance methods) plus every one accessed by its code.
+pub fn compute_max_locals(cp: &ConstantPool, method: &MethodInfo) -> u16 {
+    let (arguments, _) = method_categories(&cp.get_utf8_content(method.descriptor_index));
+    let mut max_locals: usize = arguments.iter().map(|c| c.words()).sum();
+    if !method.access_flags.contains(MethodAccessFlag::Static) {
+        max_locals += 1;
+    }
+
+    if let Some(AttributeInfo::Code { code, .. }) =
+        find_attribute(&method.attributes, AttributeKind::Code)
+    {
+        for (_, instruction) in code.iter() {
+            let (index, category): (u16, ValueCategory) = match instruction {
+                BytecodeInstruction::ALoad {
+                    local_variable_index,
+                }
+                | BytecodeInstruction::AStore {
+                    local_variable_index,
+                }
+                | BytecodeInstruction::ILoad {
+                    local_variable_index,
+                }
+                | BytecodeInstruction::IStore {
+                    local_variable_index,
+                }
+                | BytecodeInstruction::FLoad {
+                    local_variable_index,
+                }
+                | BytecodeInstruction::FStore {
+                    local_variable_index,
+                }
+                | BytecodeInstruction::Ret {
+                    local_variable_index,
+                }
+                | BytecodeInstruction::IInc {
+                    index: local_variable_index,
+                    ..
+                } => (*local_variable_index, ValueCategory::One),
+                BytecodeInstruction::LLoad {
+                    local_variable_index,
+                }
+                | BytecodeInstruction::LStore {
+                    local_variable_index,
+                }
+                | BytecodeInstruction::DLoad {
+                    local_variable_index,
+                }
+                | BytecodeInstruction::DStore {
+                    local_variable_index,
+                } => (*local_variable_index, ValueCategory::Two),
+                _ => continue,
+            };
+            max_locals = max_locals.max(usize::from(index) + category.words());
+        }
+    }
+
+    max_locals.try_into().unwrap()
+}
+
+/// Simulates the operand stack over every reachable path of the given code and returns the shape of the stack
+/// (from bottom to top) right before each reachable instruction.
+pub fn simulate_operand_stack(
+    cp: &ConstantPool,
+    code: &[(u32, BytecodeInstruction)],
+    exception_table: &[ExceptionTableEntry],
+) -> Result<BTreeMap<u32, Vec<ValueCategory>>, VerificationError> {
+    let indices: HashMap<u32, usize> = code
+        .iter()
+        .enumerate()
+        .map(|(i, (position, _))| (*position, i))
+        .collect();
+    let mut states: BTreeMap<u32, Vec<ValueCategory>> = BTreeMap::new();
+    let mut worklist: Vec<u32> = Vec::new();
+
+    if let Some((first, _)) = code.first() {
+        states.insert(*first, Vec::new());
+        worklist.push(*first);
+    }
+    for entry in exception_table.iter() {
+        let handler: u32 = entry.handler_pc.into();
+        merge(
+            &mut states,
+            &mut worklist,
+            handler,
+            handler,
+            &[ValueCategory::One],
+        )?;
+    }
+
+    while let Some(position) = worklist.pop() {
+        let index: usize = *indices.get(&position).unwrap();
+        let instruction: &BytecodeInstruction = &code[index].1;
+        let mut stack: Vec<ValueCategory> = states[&position].clone();
+        execute(cp, &mut stack, position, instruction)?;
+
+        let next: Option<u32> = code.get(index + 1).map(|(p, _)| *p);
+        if let BytecodeInstruction::Jsr { .. } | BytecodeInstruction::JsrW { .. } = instruction {
+            // Old class files (major version < 50) are checked by type inference instead of a StackMapTable: the
+            // subroutine starts with the return address on top of the stack and, once it executes ret, execution
+            // continues after the jsr with the stack of the caller.
+            match next {
+                Some(next) => merge(&mut states, &mut worklist, position, next, &stack)?,
+                None => {
+                    return Err(VerificationError {
+                        position,
+                        message: "Execution falls off the end of the code.".to_owned(),
+                    });
+                }
+            }
+            stack.push(ValueCategory::One);
+        }
+        for target in successors(instruction, position, next)? {
+            if !indices.contains_key(&target) {
+                return Err(VerificationError {
+                    position,
+                    message: format!("Jump target {target} is not the start of an instruction."),
+                });
+            }
+            merge(&mut states, &mut worklist, position, target, &stack)?;
+        }
+    }
+
+    Ok(states)
+}
+
+/// Checks that every path through the given code releases the monitors it acquires before returning, or before
+/// throwing an exception which is not caught by a catch-all handler. Monitors are only counted, not told apart. The
+/// handlers of an instruction are entered with the monitors held right before it. On imbalance, returns the sorted
+/// offsets of the instructions releasing a monitor which is not held, exiting with monitors still held, or reaching
+/// an instruction with a different count than another path.
+pub fn check_monitor_balance(
+    code: &[(u32, BytecodeInstruction)],
+    exception_table: &[ExceptionTableEntry],
+) -> Result<(), Vec<u32>> {
+    let indices: HashMap<u32, usize> = code
+        .iter()
+        .enumerate()
+        .map(|(i, (position, _))| (*position, i))
+        .collect();
+    let covers = |entry: &ExceptionTableEntry, position: u32| -> bool {
+        u32::from(entry.start_pc) <= position && position < u32::from(entry.end_pc)
+    };
+    let mut held: BTreeMap<u32, usize> = BTreeMap::new();
+    let mut worklist: Vec<u32> = Vec::new();
+    let mut offending: BTreeSet<u32> = BTreeSet::new();
+
+    if let Some((first, _)) = code.first() {
+        held.insert(*first, 0);
+        worklist.push(*first);
+    }
+
+    while let Some(position) = worklist.pop() {
+        let index: usize = indices[&position];
+        let instruction: &BytecodeInstruction = &code[index].1;
+        let before: usize = held[&position];
+        // handlers are tried in order, so the ones after a catch-all are never reached
+        for entry in exception_table.iter().filter(|e| covers(e, position)) {
+            let handler: u32 = entry.handler_pc.into();
+            reach(
+                &mut held,
+                &mut worklist,
+                &mut offending,
+                position,
+                handler,
+                before,
+            );
+            if entry.catch_type == 0 {
+                break;
+            }
+        }
+
+        let after: usize = match instruction {
+            BytecodeInstruction::MonitorEnter {} => before + 1,
+            BytecodeInstruction::MonitorExit {} => match before.checked_sub(1) {
+                Some(after) => after,
+                None => {
+                    offending.insert(position);
+                    continue;
+                }
+            },
+            _ => before,
+        };
+        let is_exit: bool = match instruction {
+            BytecodeInstruction::Return {}
+            | BytecodeInstruction::IReturn {}
+            | BytecodeInstruction::LReturn {}
+            | BytecodeInstruction::FReturn {}
+            | BytecodeInstruction::DReturn {}
+            | BytecodeInstruction::AReturn {} => true,
+            BytecodeInstruction::AThrow {} => !exception_table
+                .iter()
+                .any(|e| e.catch_type == 0 && covers(e, position)),
+            _ => false,
+        };
+        if is_exit && after != 0 {
+            offending.insert(position);
+        }
+
+        let next: Option<u32> = code.get(index + 1).map(|(p, _)| *p);
+        if let (BytecodeInstruction::Jsr { .. } | BytecodeInstruction::JsrW { .. }, Some(next)) =
+            (instruction, next)
+        {
+            // the subroutine is expected to leave the monitors as it found them
+            reach(
+                &mut held,
+                &mut worklist,
+                &mut offending,
+                position,
+                next,
+                after,
+            );
+        }
+        let Ok(targets) = successors(instruction, position, next) else {
+            offending.insert(position);
+            continue;
+        };
+        for target in targets {
+            if indices.contains_key(&target) {
+                reach(
+                    &mut held,
+                    &mut worklist,
+                    &mut offending,
+                    position,
+                    target,
+                    after,
+                );
+            } else {
+                offending.insert(position);
+            }
+        }
+    }
+
+    if offending.is_empty() {
+        Ok(())
+    } else {
+        Err(offending.into_iter().collect())
+    }
+}
+
+fn reach(
+    held: &mut BTreeMap<u32, usize>,
+    worklist: &mut Vec<u32>,
+    offending: &mut BTreeSet<u32>,
+    position: u32,
+    target: u32,
+    count: usize,
+) {
+    match held.get(&target) {
+        Some(existing) if *existing == count => {}
+        Some(_) => {
+            offending.insert(position);
+        }
+        None => {
+            held.insert(target, count);
+            worklist.push(target);
+        }
+    }
+}
+
+fn merge(
+    states: &mut BTreeMap<u32, Vec<ValueCategory>>,
+    worklist: &mut Vec<u32>,
+    position: u32,
+    target: u32,
+    stack: &[ValueCategory],
+) -> Result<(), VerificationError> {
+    match states.get(&target) {
+        Some(existing) if existing == stack => Ok(()),
+        Some(existing) => Err(VerificationError {
+            position,
+            message: format!(
+                "Stack shape {stack:?} does not match the shape {existing:?} already recorded at {target}."
+            ),
+        }),
+        None => {
+            states.insert(target, stack.to_vec());
+            worklist.push(target);
+            Ok(())
+        }
+    }
+}
+
+fn successors(
+    instruction: &BytecodeInstruction,
+    position: u32,
+    next: Option<u32>,
+) -> Result<Vec<u32>, VerificationError> {
+    let relative = |offset: i32| -> u32 { (i64::from(position) + i64::from(offset)) as u32 };
+    let fallthrough = || -> Result<u32, VerificationError> {
+        next.ok_or_else(|| VerificationError {
+            position,
+            message: "Execution falls off the end of the code.".to_owned(),
+        })
+    };
+
+    Ok(match instruction {
+        BytecodeInstruction::Return {}
+        | BytecodeInstruction::IReturn {}
+        | BytecodeInstruction::LReturn {}
+        | BytecodeInstruction::FReturn {}
+        | BytecodeInstruction::DReturn {}
+        | BytecodeInstruction::AReturn {}
+        | BytecodeInstruction::AThrow {}
+        | BytecodeInstruction::Ret { .. } => Vec::new(),
+        BytecodeInstruction::GoTo { offset } | BytecodeInstruction::Jsr { offset } => {
+            vec![relative((*offset).into())]
+        }
+        BytecodeInstruction::GoToW { offset } | BytecodeInstruction::JsrW { offset } => {
+            vec![relative(*offset)]
+        }
+        BytecodeInstruction::IfAcmpEq { offset }
+        | BytecodeInstruction::IfAcmpNe { offset }
+        | BytecodeInstruction::IfIcmpEq { offset }
+        | BytecodeInstruction::IfIcmpNe { offset }
+        | BytecodeInstruction::IfIcmpLt { offset }
+        | BytecodeInstruction::IfIcmpGe { offset }
+        | BytecodeInstruction::IfIcmpGt { offset }
+        | BytecodeInstruction::IfIcmpLe { offset }
+        | BytecodeInstruction::IfEq { offset }
+        | BytecodeInstruction::IfNe { offset }
+        | BytecodeInstruction::IfLt { offset }
+        | BytecodeInstruction::IfGe { offset }
+        | BytecodeInstruction::IfGt { offset }
+        | BytecodeInstruction::IfLe { offset }
+        | BytecodeInstruction::IfNull { offset }
+        | BytecodeInstruction::IfNonNull { offset } => {
+            vec![fallthrough()?, relative((*offset).into())]
+        }
+        BytecodeInstruction::TableSwitch {
+            default, offsets, ..
+        } => std::iter::once(default)
+            .chain(offsets.iter())
+            .map(|offset| relative(*offset))
+            .collect(),
+        BytecodeInstruction::LookupSwitch { default, pairs, .. } => std::iter::once(*default)
+            .chain(pairs.iter().map(|pair| pair.offset))
+            .map(relative)
+            .collect(),
+        _ => vec![fallthrough()?],
+    })
+}
+
+/// Pops values from the top of the stack until exactly the given number of words has been removed, and returns
+/// them from bottom to top. This is how the JVM specification describes the forms of the untyped stack
+/// instructions (pop2, dup_x2, dup2, dup2_x1, dup2_x2...): the operation is allowed as long as it does not split a
+/// category 2 value in half.
+fn pop_words(
+    stack: &mut Vec<ValueCategory>,
+    position: u32,
+    instruction: &BytecodeInstruction,
+    words: usize,
+) -> Result<Vec<ValueCategory>, VerificationError> {
+    let mut split: usize = stack.len();
+    let mut count: usize = 0;
+    while count < words {
+        if split == 0 {
+            return Err(underflow(position, instruction));
+        }
+        split -= 1;
+        count += stack[split].words();
+    }
+    if count != words {
+        return Err(VerificationError {
+            position,
+            message: format!(
+                "{} would split a category 2 value on the stack {:?}.",
+                instruction.mnemonic(),
+                stack
+            ),
+        });
+    }
+    Ok(stack.split_off(split))
+}
+
+fn underflow(position: u32, instruction: &BytecodeInstruction) -> VerificationError {
+    VerificationError {
+        position,
+        message: format!("{} pops from an empty stack.", instruction.mnemonic()),
+    }
+}
+
+/// Pops the given categories (listed from the top of the stack downwards) and then pushes the result, if any.
+fn apply(
+    stack: &mut Vec<ValueCategory>,
+    position: u32,
+    instruction: &BytecodeInstruction,
+    pops: &[ValueCategory],
+    push: Option<ValueCategory>,
+) -> Result<(), VerificationError> {
+    for expected in pops.iter() {
+        match stack.pop() {
+            None => return Err(underflow(position, instruction)),
+            Some(actual) if actual != *expected => {
+                return Err(VerificationError {
+                    position,
+                    message: format!(
+                        "{} expected a category {} value but found a category {} one.",
+                        instruction.mnemonic(),
+                        expected.words(),
+                        actual.words()
+                    ),
+                });
+            }
+            Some(_) => {}
+        }
+    }
+    stack.extend(push);
+    Ok(())
+}
+
+fn member_descriptor(cp: &ConstantPool, cp_index: u16) -> String {
+    match &cp[cp_index - 1] {
+        ConstantPoolInfo::FieldRef {
+            name_and_type_index,
+            ..
+        }
+        | ConstantPoolInfo::MethodRef {
+            name_and_type_index,
+            ..
+        }
+        | ConstantPoolInfo::InterfaceMethodRef {
+            name_and_type_index,
+            ..
+        }
+        | ConstantPoolInfo::Dynamic {
+            name_and_type_index,
+            ..
+        }
+        | ConstantPoolInfo::InvokeDynamic {
+            name_and_type_index,
+            ..
+        } => match &cp[*name_and_type_index - 1] {
+            ConstantPoolInfo::NameAndType {
+                descriptor_index, ..
+            } => cp.get_utf8_content(*descriptor_index),
+            _ => panic!(
+                "Expected entry #{name_and_type_index} to be of NameAndType type but it wasn't."
+            ),
+        },
+        _ => panic!("Expected entry #{cp_index} to be a member reference but it wasn't."),
+    }
+}
+
+/// Returns the categories of the arguments and of the return value (None for void) of the given method descriptor.
+fn method_categories(descriptor: &str) -> (Vec<ValueCategory>, Option<ValueCategory>) {
+    let mut it = descriptor.chars();
+    assert_eq!(
+        it.next(),
+        Some('('),
+        "Invalid method descriptor '{descriptor}'."
+    );
+    let mut arguments: Vec<ValueCategory> = Vec::new();
+    loop {
+        match it.next() {
+            Some(')') => break,
+            Some('L') => {
+                it.by_ref().find(|c| *c == ';');
+                arguments.push(ValueCategory::One);
+            }
+            Some('[') => {
+                let mut c = it.next();
+                while c == Some('[') {
+                    c = it.next();
+                }
+                if c == Some('L') {
+                    it.by_ref().find(|c| *c == ';');
+                }
+                arguments.push(ValueCategory::One);
+            }
+            Some(c) => arguments.push(ValueCategory::of(c)),
+            None => panic!("Invalid method descriptor '{descriptor}'."),
+        }
+    }
+    let return_type: Option<ValueCategory> = match it.next() {
+        Some('V') => None,
+        Some(c) => Some(ValueCategory::of(c)),
+        None => panic!("Invalid method descriptor '{descriptor}'."),
+    };
+    (arguments, return_type)
+}
+
+fn execute(
+    cp: &ConstantPool,
+    stack: &mut Vec<ValueCategory>,
+    position: u32,
+    instruction: &BytecodeInstruction,
+) -> Result<(), VerificationError> {
+    use ValueCategory::{One, Two};
+
+    match instruction {
+        BytecodeInstruction::Pop {} => pop_words(stack, position, instruction, 1).map(|_| ()),
+        BytecodeInstruction::Pop2 {} => pop_words(stack, position, instruction, 2).map(|_| ()),
+        BytecodeInstruction::Dup {} => {
+            let a = pop_words(stack, position, instruction, 1)?;
+            stack.extend(&a);
+            stack.extend(a);
+            Ok(())
+        }
+        BytecodeInstruction::DupX1 {} | BytecodeInstruction::DupX2 {} => {
+            let a = pop_words(stack, position, instruction, 1)?;
+            let below: usize = if matches!(instruction, BytecodeInstruction::DupX1 {}) {
+                1
+            } else {
+                2
+            };
+            let b = pop_words(stack, position, instruction, below)?;
+            stack.extend(&a);
+            stack.extend(b);
+            stack.extend(a);
+            Ok(())
+        }
+        BytecodeInstruction::Dup2 {} => {
+            let a = pop_words(stack, position, instruction, 2)?;
+            stack.extend(&a);
+            stack.extend(a);
+            Ok(())
+        }
+        BytecodeInstruction::Dup2X1 {} | BytecodeInstruction::Dup2X2 {} => {
+            let a = pop_words(stack, position, instruction, 2)?;
+            let below: usize = if matches!(instruction, BytecodeInstruction::Dup2X1 {}) {
+                1
+            } else {
+                2
+            };
+            let b = pop_words(stack, position, instruction, below)?;
+            stack.extend(&a);
+            stack.extend(b);
+            stack.extend(a);
+            Ok(())
+        }
+        BytecodeInstruction::Swap {} => {
+            let a = pop_words(stack, position, instruction, 1)?;
+            let b = pop_words(stack, position, instruction, 1)?;
+            stack.extend(a);
+            stack.extend(b);
+            Ok(())
+        }
+
+        BytecodeInstruction::AConstNull {}
+        | BytecodeInstruction::IConst { .. }
+        | BytecodeInstruction::FConst { .. }
+        | BytecodeInstruction::BiPush { .. }
+        | BytecodeInstruction::SiPush { .. }
+        | BytecodeInstruction::Ldc { .. }
+        | BytecodeInstruction::LdcW { .. }
+        | BytecodeInstruction::ALoad { .. }
+        | BytecodeInstruction::ILoad { .. }
+        | BytecodeInstruction::FLoad { .. }
+        | BytecodeInstruction::New { .. } => apply(stack, position, instruction, &[], Some(One)),
+        BytecodeInstruction::LConst { .. }
+        | BytecodeInstruction::DConst { .. }
+        | BytecodeInstruction::Ldc2W { .. }
+        | BytecodeInstruction::LLoad { .. }
+        | BytecodeInstruction::DLoad { .. } => apply(stack, position, instruction, &[], Some(Two)),
+
+        BytecodeInstruction::AStore { .. }
+        | BytecodeInstruction::IStore { .. }
+        | BytecodeInstruction::FStore { .. }
+        | BytecodeInstruction::AThrow {}
+        | BytecodeInstruction::MonitorEnter {}
+        | BytecodeInstruction::MonitorExit {}
+        | BytecodeInstruction::IReturn {}
+        | BytecodeInstruction::FReturn {}
+        | BytecodeInstruction::AReturn {}
+        | BytecodeInstruction::IfEq { .. }
+        | BytecodeInstruction::IfNe { .. }
+        | BytecodeInstruction::IfLt { .. }
+        | BytecodeInstruction::IfGe { .. }
+        | BytecodeInstruction::IfGt { .. }
+        | BytecodeInstruction::IfLe { .. }
+        | BytecodeInstruction::IfNull { .. }
+        | BytecodeInstruction::IfNonNull { .. }
+        | BytecodeInstruction::TableSwitch { .. }
+        | BytecodeInstruction::LookupSwitch { .. } => {
+            apply(stack, position, instruction, &[One], None)
+        }
+        BytecodeInstruction::LStore { .. }
+        | BytecodeInstruction::DStore { .. }
+        | BytecodeInstruction::LReturn {}
+        | BytecodeInstruction::DReturn {} => apply(stack, position, instruction, &[Two], None),
+        BytecodeInstruction::IfAcmpEq { .. }
+        | BytecodeInstruction::IfAcmpNe { .. }
+        | BytecodeInstruction::IfIcmpEq { .. }
+        | BytecodeInstruction::IfIcmpNe { .. }
+        | BytecodeInstruction::IfIcmpLt { .. }
+        | BytecodeInstruction::IfIcmpGe { .. }
+        | BytecodeInstruction::IfIcmpGt { .. }
+        | BytecodeInstruction::IfIcmpLe { .. } => {
+            apply(stack, position, instruction, &[One, One], None)
+        }
+        BytecodeInstruction::Nop {}
+        | BytecodeInstruction::Return {}
+        | BytecodeInstruction::GoTo { .. }
+        | BytecodeInstruction::GoToW { .. }
+        | BytecodeInstruction::Ret { .. }
+        | BytecodeInstruction::IInc { .. } => Ok(()),
+        // the return address is pushed only on the path entering the subroutine
+        BytecodeInstruction::Jsr { .. } | BytecodeInstruction::JsrW { .. } => Ok(()),
+
+        BytecodeInstruction::IaLoad {}
+        | BytecodeInstruction::FaLoad {}
+        | BytecodeInstruction::AaLoad {}
+        | BytecodeInstruction::BaLoad {}
+        | BytecodeInstruction::CaLoad {}
+        | BytecodeInstruction::SaLoad {} => {
+            apply(stack, position, instruction, &[One, One], Some(One))
+        }
+        BytecodeInstruction::LaLoad {} | BytecodeInstruction::DaLoad {} => {
+            apply(stack, position, instruction, &[One, One], Some(Two))
+        }
+        BytecodeInstruction::IaStore {}
+        | BytecodeInstruction::FaStore {}
+        | BytecodeInstruction::AaStore {}
+        | BytecodeInstruction::BaStore {}
+        | BytecodeInstruction::CaStore {}
+        | BytecodeInstruction::SaStore {} => {
+            apply(stack, position, instruction, &[One, One, One], None)
+        }
+        BytecodeInstruction::LaStore {} | BytecodeInstruction::DaStore {} => {
+            apply(stack, position, instruction, &[Two, One, One], None)
+        }
+
+        BytecodeInstruction::NewArray { .. }
+        | BytecodeInstruction::ANewArray { .. }
+        | BytecodeInstruction::ArrayLength {}
+        | BytecodeInstruction::CheckCast { .. }
+        | BytecodeInstruction::Instanceof { .. }
+        | BytecodeInstruction::I2F {}
+        | BytecodeInstruction::F2I {}
+        | BytecodeInstruction::I2B {}
+        | BytecodeInstruction::I2C {}
+        | BytecodeInstruction::I2S {}
+        | BytecodeInstruction::INeg {}
+        | BytecodeInstruction::FNeg {} => apply(stack, position, instruction, &[One], Some(One)),
+        BytecodeInstruction::I2L {}
+        | BytecodeInstruction::I2D {}
+        | BytecodeInstruction::F2L {}
+        | BytecodeInstruction::F2D {} => apply(stack, position, instruction, &[One], Some(Two)),
+        BytecodeInstruction::L2I {}
+        | BytecodeInstruction::L2F {}
+        | BytecodeInstruction::D2I {}
+        | BytecodeInstruction::D2F {} => apply(stack, position, instruction, &[Two], Some(One)),
+        BytecodeInstruction::L2D {}
+        | BytecodeInstruction::D2L {}
+        | BytecodeInstruction::LNeg {}
+        | BytecodeInstruction::DNeg {} => apply(stack, position, instruction, &[Two], Some(Two)),
+
+        BytecodeInstruction::IAdd {}
+        | BytecodeInstruction::ISub {}
+        | BytecodeInstruction::IMul {}
+        | BytecodeInstruction::IDiv {}
+        | BytecodeInstruction::IRem {}
+        | BytecodeInstruction::IAnd {}
+        | BytecodeInstruction::IShl {}
+        | BytecodeInstruction::IShr {}
+        | BytecodeInstruction::IUshr {}
+        | BytecodeInstruction::IOr {}
+        | BytecodeInstruction::IXor {}
+        | BytecodeInstruction::FAdd {}
+        | BytecodeInstruction::FMul {}
+        | BytecodeInstruction::FDiv {}
+        | BytecodeInstruction::FRem {}
+        | BytecodeInstruction::FSub {}
+        | BytecodeInstruction::FCmpL {}
+        | BytecodeInstruction::FCmpG {} => {
+            apply(stack, position, instruction, &[One, One], Some(One))
+        }
+        BytecodeInstruction::LAdd {}
+        | BytecodeInstruction::LSub {}
+        | BytecodeInstruction::LMul {}
+        | BytecodeInstruction::LDiv {}
+        | BytecodeInstruction::LRem {}
+        | BytecodeInstruction::LAnd {}
+        | BytecodeInstruction::LOr {}
+        | BytecodeInstruction::LXor {}
+        | BytecodeInstruction::DAdd {}
+        | BytecodeInstruction::DMul {}
+        | BytecodeInstruction::DDiv {}
+        | BytecodeInstruction::DRem {}
+        | BytecodeInstruction::DSub {} => {
+            apply(stack, position, instruction, &[Two, Two], Some(Two))
+        }
+        BytecodeInstruction::LShl {}
+        | BytecodeInstruction::LShr {}
+        | BytecodeInstruction::LUshr {} => {
+            apply(stack, position, instruction, &[One, Two], Some(Two))
+        }
+        BytecodeInstruction::LCmp {}
+        | BytecodeInstruction::DCmpL {}
+        | BytecodeInstruction::DCmpG {} => {
+            apply(stack, position, instruction, &[Two, Two], Some(One))
+        }
+
+        BytecodeInstruction::GetStatic { field_ref_index } => {
+            let field: ValueCategory = ValueCategory::of(
+                member_descriptor(cp, *field_ref_index)
+                    .chars()
+                    .next()
+                    .unwrap(),
+            );
+            apply(stack, position, instruction, &[], Some(field))
+        }
+        BytecodeInstruction::PutStatic { field_ref_index } => {
+            let field: ValueCategory = ValueCategory::of(
+                member_descriptor(cp, *field_ref_index)
+                    .chars()
+                    .next()
+                    .unwrap(),
+            );
+            apply(stack, position, instruction, &[field], None)
+        }
+        BytecodeInstruction::GetField { field_ref_index } => {
+            let field: ValueCategory = ValueCategory::of(
+                member_descriptor(cp, *field_ref_index)
+                    .chars()
+                    .next()
+                    .unwrap(),
+            );
+            apply(stack, position, instruction, &[One], Some(field))
+        }
+        BytecodeInstruction::MultiANewArray { dimensions, .. } => apply(
+            stack,
+            position,
+            instruction,
+            &vec![One; usize::from(*dimensions)],
+            Some(One),
+        ),
+        BytecodeInstruction::PutField { field_ref_index } => {
+            let field: ValueCategory = ValueCategory::of(
+                member_descriptor(cp, *field_ref_index)
+                    .chars()
+                    .next()
+                    .unwrap(),
+            );
+            apply(stack, position, instruction, &[field, One], None)
+        }
+        BytecodeInstruction::InvokeSpecial {
+            method_ref_index: cp_index,
+        }
+        | BytecodeInstruction::InvokeVirtual {
+            method_ref_index: cp_index,
+        }
+        | BytecodeInstruction::InvokeInterface {
+            constant_pool_index: cp_index,
+            ..
+        }
+        | BytecodeInstruction::InvokeStatic {
+            method_ref_index: cp_index,
+        }
+        | BytecodeInstruction::InvokeDynamic {
+            constant_pool_index: cp_index,
+        } => {
+            let (mut pops, push) = method_categories(&member_descriptor(cp, *cp_index));
+            pops.reverse();
+            if !matches!(
+                instruction,
+                BytecodeInstruction::InvokeStatic { .. }
+                    | BytecodeInstruction::InvokeDynamic { .. }
+            ) {
+                pops.push(One);
+            }
+            apply(stack, position, instruction, &pops, push)
+        }
+
+        BytecodeInstruction::Unknown { opcode } => Err(VerificationError {
+            position,
+            message: format!("Unknown opcode 0x{opcode:02x}."),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classfile::{ClassFile, parse_class_file_from_bytes};
+
+    fn check(
+        code: Vec<BytecodeInstruction>,
+    ) -> Result<BTreeMap<u32, Vec<ValueCategory>>, VerificationError> {
+        let cp = ConstantPool {
+            entries: Vec::new(),
+        };
+        let code: Vec<(u32, BytecodeInstruction)> = code
+            .into_iter()
+            .enumerate()
+            .map(|(i, instruction)| (i as u32, instruction))
+            .collect();
+        simulate_operand_stack(&cp, &code, &[])
+    }
+
+    #[test]
+    fn unknown_opcode() {
+        let error = check(vec![
+            BytecodeInstruction::IConst { constant: 1 },
+            BytecodeInstruction::Unknown { opcode: 0xca },
+            BytecodeInstruction::Return {},
+        ])
+        .err()
+        .unwrap();
+        assert_eq!(error.position, 1);
+        assert_eq!(error.message, "Unknown opcode 0xca.");
+    }
+
+    #[test]
+    fn valid_dup_x1() {
+        let states = check(vec![
+            BytecodeInstruction::IConst { constant: 1 },
+            BytecodeInstruction::IConst { constant: 2 },
+            BytecodeInstruction::DupX1 {},
+            BytecodeInstruction::Pop {},
+            BytecodeInstruction::Pop2 {},
+            BytecodeInstruction::Return {},
+        ])
+        .unwrap();
+        assert_eq!(states[&3], vec![ValueCategory::One; 3]);
+    }
+
+    #[test]
+    fn dup_x1_over_category_2_value() {
+        let error = check(vec![
+            BytecodeInstruction::LConst { constant: 0 },
+            BytecodeInstruction::IConst { constant: 1 },
+            BytecodeInstruction::DupX1 {},
+            BytecodeInstruction::Return {},
+        ])
+        .unwrap_err();
+        assert_eq!(error.position, 2);
+    }
+
+    #[test]
+    fn dup2_x2_with_category_2_values() {
+        let states = check(vec![
+            BytecodeInstruction::DConst { constant: 1.0 },
+            BytecodeInstruction::LConst { constant: 1 },
+            BytecodeInstruction::Dup2X2 {},
+            BytecodeInstruction::Return {},
+        ])
+        .unwrap();
+        assert_eq!(
+            states[&3],
+            vec![ValueCategory::Two, ValueCategory::Two, ValueCategory::Two]
+        );
+    }
+
+    #[test]
+    fn fixtures_verify() {
+        let fixtures: [&[u8]; 14] = [
+            include_bytes!("../../e2e/data/Arrays.class"),
+            include_bytes!("../../e2e/data/Calculator.class"),
+            include_bytes!("../../e2e/data/Employee.class"),
+            include_bytes!("../../e2e/data/HelloWorld.class"),
+            include_bytes!("../../e2e/data/List.class"),
+            include_bytes!("../../e2e/data/Math.class"),
+            include_bytes!("../../e2e/data/MultiArrays.class"),
+            include_bytes!("../../e2e/data/OldSubroutine.class"),
+            include_bytes!("../../e2e/data/SecuritySettings$1.class"),
+            include_bytes!("../../e2e/data/Shape.class"),
+            include_bytes!("../../e2e/data/Stream.class"),
+            include_bytes!("../../e2e/data/Stripped.class"),
+            include_bytes!("../../e2e/data/Synchronized.class"),
+            include_bytes!("../../e2e/data/TimeUnit.class"),
+        ];
+        for bytes in fixtures {
+            let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+            for method in cf.methods.iter() {
+                assert_eq!(verify_method(&cf.constant_pool, method), Ok(()));
+            }
+        }
+    }
+
+    #[test]
+    fn computed_sizes_match_javac() {
+        let fixtures: [&[u8]; 5] = [
+            include_bytes!("../../e2e/data/Calculator.class"),
+            include_bytes!("../../e2e/data/Employee.class"),
+            include_bytes!("../../e2e/data/Math.class"),
+            include_bytes!("../../e2e/data/MultiArrays.class"),
+            include_bytes!("../../e2e/data/TimeUnit.class"),
+        ];
+        for bytes in fixtures {
+            let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+            for method in cf.methods.iter() {
+                let Some(AttributeInfo::Code {
+                    max_stack,
+                    max_locals,
+                    code,
+                    exception_table,
+                    ..
+                }) = find_attribute(&method.attributes, AttributeKind::Code)
+                else {
+                    continue;
+                };
+                assert_eq!(
+                    compute_max_stack(&cf.constant_pool, code, exception_table),
+                    Ok(*max_stack)
+                );
+                assert_eq!(compute_max_locals(&cf.constant_pool, method), *max_locals);
+            }
+        }
+    }
+
+    /// Returns the code and the exception table of `Synchronized.incrementTwice`, with the monitorexit at the given
+    /// offset (if any) replaced by a pop, which has the same size and the same effect on the stack.
+    fn synchronized_blocks(
+        removed_exit: Option<u32>,
+    ) -> (Vec<(u32, BytecodeInstruction)>, Vec<ExceptionTableEntry>) {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Synchronized.class");
+        let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        let method: &MethodInfo = cf.methods_named("incrementTwice").next().unwrap();
+        let Some(AttributeInfo::Code {
+            code,
+            exception_table,
+            ..
+        }) = find_attribute(&method.attributes, AttributeKind::Code)
+        else {
+            unreachable!();
+        };
+        let code: Vec<(u32, BytecodeInstruction)> = code
+            .iter()
+            .map(|(position, instruction)| match instruction {
+                BytecodeInstruction::MonitorExit {} if Some(*position) == removed_exit => {
+                    (*position, BytecodeInstruction::Pop {})
+                }
+                _ => (*position, instruction.clone()),
+            })
+            .collect();
+        (code, exception_table.clone())
+    }
+
+    #[test]
+    fn balanced_synchronized_blocks() {
+        let (code, exception_table) = synchronized_blocks(None);
+        assert_eq!(check_monitor_balance(&code, &exception_table), Ok(()));
+    }
+
+    #[test]
+    fn monitor_held_on_return() {
+        let (code, exception_table) = synchronized_blocks(Some(54));
+        assert_eq!(
+            check_monitor_balance(&code, &exception_table),
+            Err(vec![55])
+        );
+    }
+
+    #[test]
+    fn monitor_held_on_uncaught_exception() {
+        let (code, exception_table) = synchronized_blocks(Some(59));
+        assert_eq!(
+            check_monitor_balance(&code, &exception_table),
+            Err(vec![62])
+        );
+    }
+
+    #[test]
+    fn monitor_released_twice() {
+        let code: Vec<(u32, BytecodeInstruction)> = [
+            BytecodeInstruction::ALoad {
+                local_variable_index: 0,
+            },
+            BytecodeInstruction::MonitorExit {},
+            BytecodeInstruction::Return {},
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(i, instruction)| (i as u32, instruction))
+        .collect();
+        assert_eq!(check_monitor_balance(&code, &[]), Err(vec![1]));
+    }
+}
@@ -2,8 +2,9 @@ use binary_writer::{BinaryWriter, Endianness};
 
 use crate::{
     attributes::{Annotation, AttributeInfo, ElementValue, StackMapFrame, VerificationTypeInfo},
-    bytecode::{get_instruction_length, write_instruction},
+    bytecode::{encode_bytecode, has_shortest_layout},
     classfile::ClassFile,
+    code_editing::replace_instructions,
     constant_pool::{ConstantPool, ConstantPoolInfo},
     fields::FieldInfo,
     methods::MethodInfo,
@@ -75,7 +76,9 @@ fn write_constant_pool(w: &mut BinaryWriter, cp: &ConstantPool) {
             ConstantPoolInfo::String { string_index } => {
                 w.write_u16(*string_index);
             }
-            ConstantPoolInfo::Class { name_index } => {
+            ConstantPoolInfo::Class { name_index }
+            | ConstantPoolInfo::Module { name_index }
+            | ConstantPoolInfo::Package { name_index } => {
                 w.write_u16(*name_index);
             }
             ConstantPoolInfo::FieldRef {
@@ -116,7 +119,11 @@ fn write_constant_pool(w: &mut BinaryWriter, cp: &ConstantPool) {
                 w.write_u8(*reference_kind as u8);
                 w.write_u16(*reference_index);
             }
-            ConstantPoolInfo::InvokeDynamic {
+            ConstantPoolInfo::Dynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            }
+            | ConstantPoolInfo::InvokeDynamic {
                 bootstrap_method_attr_index,
                 name_and_type_index,
             } => {
@@ -147,76 +154,6 @@ fn write_methods(w: &mut BinaryWriter, methods: &[MethodInfo]) {
     }
 }
 
-/**
- * Computes the total number of bytes required to write the given attribute, excluding the initial six bytes for the name_index field and the attribute_length field.
- */
-fn get_attribute_length(attribute: &AttributeInfo) -> u32 {
-    match attribute {
-        AttributeInfo::Code {
-            code,
-            exception_table,
-            attributes,
-            ..
-        } => {
-            2 + 2
-                + 4
-                + code
-                    .iter()
-                    .map(|(_, instruction)| get_instruction_length(instruction))
-                    .sum::<u32>()
-                + 2
-                + (2 * 4) * (exception_table.len() as u32)
-                + 2
-                + attributes
-                    .iter()
-                    .map(|attr| 2 + 4 + get_attribute_length(attr))
-                    .sum::<u32>()
-        }
-        AttributeInfo::LineNumberTable {
-            line_number_table, ..
-        } => 2 + (2 * 2) * (line_number_table.len() as u32),
-        AttributeInfo::LocalVariableTable {
-            local_variable_table,
-            ..
-        } => 2 + (2 * 5) * (local_variable_table.len() as u32),
-        AttributeInfo::LocalVariableTypeTable {
-            local_variable_type_table,
-            ..
-        } => 2 + (2 * 5) * (local_variable_type_table.len() as u32),
-        AttributeInfo::StackMapTable {
-            stack_map_table, ..
-        } => {
-            2 + stack_map_table
-                .iter()
-                .map(get_stack_map_entry_length)
-                .sum::<u32>()
-        }
-        AttributeInfo::SourceFile { .. } => 2,
-        AttributeInfo::BootstrapMethods { methods, .. } => {
-            2 + methods
-                .iter()
-                .map(|m| 2 + 2 + 2 * (m.bootstrap_arguments.len() as u32))
-                .sum::<u32>()
-        }
-        AttributeInfo::InnerClasses { classes, .. } => 2 + (2 * 4) * (classes.len() as u32),
-        AttributeInfo::MethodParameters { parameters, .. } => {
-            1 + (2 * 2) * (parameters.len() as u32)
-        }
-        AttributeInfo::Record { .. } => todo!(),
-        AttributeInfo::Signature { .. } => 2,
-        AttributeInfo::NestMembers { classes, .. } => 2 + 2 * (classes.len() as u32),
-        AttributeInfo::RuntimeVisibleAnnotations { annotations, .. } => {
-            2 + annotations.iter().map(get_annotation_length).sum::<u32>()
-        }
-        AttributeInfo::ConstantValue { .. } => 2,
-        AttributeInfo::Exceptions {
-            exception_indices, ..
-        } => 2 + 2 * (exception_indices.len() as u32),
-        AttributeInfo::EnclosingMethod { .. } => 2 + 2,
-        AttributeInfo::NestHost { .. } => 2,
-    }
-}
-
 pub(crate) fn get_stack_map_entry_length(entry: &StackMapFrame) -> u32 {
     match entry {
         StackMapFrame::SameFrame { .. } => 1,
@@ -296,199 +233,240 @@ fn get_element_value_length(value: &ElementValue) -> u32 {
 
 fn write_attributes(w: &mut BinaryWriter, attributes: &[AttributeInfo]) {
     for attribute in attributes.iter() {
-        match attribute {
-            AttributeInfo::Code {
-                name_index,
-                max_stack,
-                max_locals,
-                code,
-                exception_table,
-                attributes,
-            } => {
-                w.write_u16(*name_index);
-                w.write_u32(get_attribute_length(attribute));
-                w.write_u16(*max_stack);
-                w.write_u16(*max_locals);
-                w.write_u32(
-                    code.iter()
-                        .map(|(_, instruction)| get_instruction_length(instruction))
-                        .sum(),
-                );
-                for (_, instruction) in code.iter() {
-                    write_instruction(w, instruction);
-                }
-                w.write_u16(exception_table.len().try_into().unwrap());
-                for exception in exception_table.iter() {
-                    w.write_u16(exception.start_pc);
-                    w.write_u16(exception.end_pc);
-                    w.write_u16(exception.handler_pc);
-                    w.write_u16(exception.catch_type);
-                }
-                w.write_u16(attributes.len().try_into().unwrap());
-                write_attributes(w, attributes);
-            }
-            AttributeInfo::LineNumberTable {
-                name_index,
-                line_number_table,
-            } => {
-                w.write_u16(*name_index);
-                w.write_u32(get_attribute_length(attribute));
-                w.write_u16(line_number_table.len().try_into().unwrap());
-                for entry in line_number_table.iter() {
-                    w.write_u16(entry.start_pc);
-                    w.write_u16(entry.line_number);
-                }
-            }
-            AttributeInfo::LocalVariableTable {
-                name_index,
-                local_variable_table,
-            } => {
-                w.write_u16(*name_index);
-                w.write_u32(get_attribute_length(attribute));
-                w.write_u16(local_variable_table.len().try_into().unwrap());
-                for entry in local_variable_table.iter() {
-                    w.write_u16(entry.start_pc);
-                    w.write_u16(entry.length);
-                    w.write_u16(entry.name_index);
-                    w.write_u16(entry.descriptor_index);
-                    w.write_u16(entry.index);
-                }
-            }
-            AttributeInfo::LocalVariableTypeTable {
-                name_index,
-                local_variable_type_table,
-            } => {
-                w.write_u16(*name_index);
-                w.write_u32(get_attribute_length(attribute));
-                w.write_u16(local_variable_type_table.len().try_into().unwrap());
-                for entry in local_variable_type_table {
-                    w.write_u16(entry.start_pc);
-                    w.write_u16(entry.length);
-                    w.write_u16(entry.name_index);
-                    w.write_u16(entry.descriptor_index);
-                    w.write_u16(entry.index);
-                }
-            }
-            AttributeInfo::StackMapTable {
-                name_index,
-                stack_map_table,
-            } => {
-                w.write_u16(*name_index);
-                w.write_u32(get_attribute_length(attribute));
-                w.write_u16(stack_map_table.len().try_into().unwrap());
-                for entry in stack_map_table {
-                    write_stack_map_entry(w, entry);
-                }
-            }
-            AttributeInfo::SourceFile {
-                name_index,
-                source_file_index,
-            } => {
-                w.write_u16(*name_index);
-                w.write_u32(get_attribute_length(attribute));
-                w.write_u16(*source_file_index);
-            }
-            AttributeInfo::BootstrapMethods {
-                name_index,
-                methods,
-            } => {
-                w.write_u16(*name_index);
-                w.write_u32(get_attribute_length(attribute));
-                w.write_u16(methods.len().try_into().unwrap());
-                for m in methods {
-                    w.write_u16(m.bootstrap_method_ref);
-                    w.write_u16(m.bootstrap_arguments.len().try_into().unwrap());
-                    w.write_u16_vec(&m.bootstrap_arguments);
-                }
-            }
-            AttributeInfo::InnerClasses {
-                name_index,
-                classes,
-            } => {
-                w.write_u16(*name_index);
-                w.write_u32(get_attribute_length(attribute));
-                w.write_u16(classes.len().try_into().unwrap());
-                for inner_class in classes.iter() {
-                    w.write_u16(inner_class.inner_class_info_index);
-                    w.write_u16(inner_class.outer_class_info_index);
-                    w.write_u16(inner_class.inner_name_index);
-                    w.write_u16(inner_class.inner_class_access_flags.to_u16());
-                }
-            }
-            AttributeInfo::MethodParameters {
-                name_index,
-                parameters,
-            } => {
-                w.write_u16(*name_index);
-                w.write_u32(get_attribute_length(attribute));
-                w.write_u8(parameters.len().try_into().unwrap());
-                for param in parameters {
-                    w.write_u16(param.name_index);
-                    w.write_u16(param.access_flags.to_u16());
-                }
+        w.write_u8_vec(&write_attribute(attribute));
+    }
+}
+
+/**
+ * Returns the bytes of the given attribute: its name_index, its attribute_length and then its body. The length is
+ * the one of the body actually written, so it can never disagree with it.
+ */
+pub fn write_attribute(attribute: &AttributeInfo) -> Vec<u8> {
+    let mut body: BinaryWriter = BinaryWriter::new(Endianness::Big);
+    write_attribute_body(&mut body, attribute);
+    let body: Vec<u8> = body.array();
+
+    let mut w: BinaryWriter = BinaryWriter::new(Endianness::Big);
+    w.write_u16(attribute.name_index());
+    w.write_u32(body.len().try_into().unwrap());
+    w.write_u8_vec(&body);
+    w.array()
+}
+
+fn write_attribute_body(w: &mut BinaryWriter, attribute: &AttributeInfo) {
+    match attribute {
+        AttributeInfo::Code { code, .. } if !has_shortest_layout(code) => {
+            // the instructions get shorter, so everything referring to the following ones has to move
+            let mut relaid_out: AttributeInfo = attribute.clone();
+            assert!(
+                replace_instructions(&mut relaid_out, &mut |_, _| None),
+                "Could not move the instructions of a Code attribute."
+            );
+            write_attribute_body(w, &relaid_out);
+        }
+        AttributeInfo::Code {
+            max_stack,
+            max_locals,
+            code,
+            trailing_bytes,
+            exception_table,
+            attributes,
+            ..
+        } => {
+            w.write_u16(*max_stack);
+            w.write_u16(*max_locals);
+            let bytecode: Vec<u8> = encode_bytecode(code);
+            w.write_u32((bytecode.len() + trailing_bytes.len()).try_into().unwrap());
+            w.write_u8_vec(&bytecode);
+            w.write_u8_vec(trailing_bytes);
+            w.write_u16(exception_table.len().try_into().unwrap());
+            for exception in exception_table.iter() {
+                w.write_u16(exception.start_pc);
+                w.write_u16(exception.end_pc);
+                w.write_u16(exception.handler_pc);
+                w.write_u16(exception.catch_type);
+            }
+            w.write_u16(attributes.len().try_into().unwrap());
+            write_attributes(w, attributes);
+        }
+        AttributeInfo::LineNumberTable {
+            line_number_table, ..
+        } => {
+            w.write_u16(line_number_table.len().try_into().unwrap());
+            for entry in line_number_table.iter() {
+                w.write_u16(entry.start_pc);
+                w.write_u16(entry.line_number);
             }
-            AttributeInfo::Record { .. } => todo!(),
-            AttributeInfo::Signature {
-                name_index,
-                signature_index,
-            } => {
-                w.write_u16(*name_index);
-                w.write_u32(get_attribute_length(attribute));
-                w.write_u16(*signature_index);
+        }
+        AttributeInfo::LocalVariableTable {
+            local_variable_table,
+            ..
+        } => {
+            w.write_u16(local_variable_table.len().try_into().unwrap());
+            for entry in local_variable_table.iter() {
+                w.write_u16(entry.start_pc);
+                w.write_u16(entry.length);
+                w.write_u16(entry.name_index);
+                w.write_u16(entry.descriptor_index);
+                w.write_u16(entry.index);
             }
-            AttributeInfo::NestMembers {
-                name_index,
-                classes,
-            } => {
-                w.write_u16(*name_index);
-                w.write_u32(get_attribute_length(attribute));
-                w.write_u16(classes.len().try_into().unwrap());
-                w.write_u16_vec(classes);
+        }
+        AttributeInfo::LocalVariableTypeTable {
+            local_variable_type_table,
+            ..
+        } => {
+            w.write_u16(local_variable_type_table.len().try_into().unwrap());
+            for entry in local_variable_type_table {
+                w.write_u16(entry.start_pc);
+                w.write_u16(entry.length);
+                w.write_u16(entry.name_index);
+                w.write_u16(entry.signature_index);
+                w.write_u16(entry.index);
             }
-            AttributeInfo::RuntimeVisibleAnnotations {
-                name_index,
-                annotations,
-            } => {
-                w.write_u16(*name_index);
-                w.write_u32(get_attribute_length(attribute));
-                write_annotations(w, annotations);
+        }
+        AttributeInfo::StackMapTable {
+            stack_map_table, ..
+        } => {
+            w.write_u16(stack_map_table.len().try_into().unwrap());
+            for entry in stack_map_table {
+                write_stack_map_entry(w, entry);
             }
-            AttributeInfo::ConstantValue {
-                name_index,
-                constant_value_index,
-            } => {
-                w.write_u16(*name_index);
-                w.write_u32(get_attribute_length(attribute));
-                w.write_u16(*constant_value_index);
+        }
+        AttributeInfo::SourceFile {
+            source_file_index, ..
+        } => {
+            w.write_u16(*source_file_index);
+        }
+        AttributeInfo::BootstrapMethods { methods, .. } => {
+            w.write_u16(methods.len().try_into().unwrap());
+            for m in methods {
+                w.write_u16(m.bootstrap_method_ref);
+                w.write_u16(m.bootstrap_arguments.len().try_into().unwrap());
+                w.write_u16_vec(&m.bootstrap_arguments);
             }
-            AttributeInfo::Exceptions {
-                name_index,
-                exception_indices,
-            } => {
-                w.write_u16(*name_index);
-                w.write_u32(get_attribute_length(attribute));
-                w.write_u16(exception_indices.len().try_into().unwrap());
-                w.write_u16_vec(exception_indices);
+        }
+        AttributeInfo::InnerClasses { classes, .. } => {
+            w.write_u16(classes.len().try_into().unwrap());
+            for inner_class in classes.iter() {
+                w.write_u16(inner_class.inner_class_info_index);
+                w.write_u16(inner_class.outer_class_info_index);
+                w.write_u16(inner_class.inner_name_index);
+                w.write_u16(inner_class.inner_class_access_flags.to_u16());
             }
-            AttributeInfo::EnclosingMethod {
-                name_index,
-                class_index,
-                method_index,
-            } => {
-                w.write_u16(*name_index);
-                w.write_u32(get_attribute_length(attribute));
-                w.write_u16(*class_index);
-                w.write_u16(*method_index);
+        }
+        AttributeInfo::MethodParameters { parameters, .. } => {
+            w.write_u8(parameters.len().try_into().unwrap());
+            for param in parameters {
+                w.write_u16(param.name_index);
+                w.write_u16(param.access_flags.to_u16());
             }
-            AttributeInfo::NestHost {
-                name_index,
-                host_class_index,
-            } => {
-                w.write_u16(*name_index);
-                w.write_u32(get_attribute_length(attribute));
-                w.write_u16(*host_class_index);
+        }
+        AttributeInfo::Record { components, .. } => {
+            w.write_u16(components.len().try_into().unwrap());
+            for component in components.iter() {
+                w.write_u16(component.name_index);
+                w.write_u16(component.descriptor_index);
+                w.write_u16(component.attributes.len().try_into().unwrap());
+                write_attributes(w, &component.attributes);
             }
         }
+        AttributeInfo::Signature {
+            signature_index, ..
+        } => {
+            w.write_u16(*signature_index);
+        }
+        AttributeInfo::NestMembers { classes, .. } => {
+            w.write_u16(classes.len().try_into().unwrap());
+            w.write_u16_vec(classes);
+        }
+        AttributeInfo::RuntimeVisibleAnnotations { annotations, .. } => {
+            write_annotations(w, annotations);
+        }
+        AttributeInfo::ConstantValue {
+            constant_value_index,
+            ..
+        } => {
+            w.write_u16(*constant_value_index);
+        }
+        AttributeInfo::Exceptions {
+            exception_indices, ..
+        } => {
+            w.write_u16(exception_indices.len().try_into().unwrap());
+            w.write_u16_vec(exception_indices);
+        }
+        AttributeInfo::EnclosingMethod {
+            class_index,
+            method_index,
+            ..
+        } => {
+            w.write_u16(*class_index);
+            w.write_u16(*method_index);
+        }
+        AttributeInfo::NestHost {
+            host_class_index, ..
+        } => {
+            w.write_u16(*host_class_index);
+        }
+        AttributeInfo::PermittedSubclasses { classes, .. } => {
+            w.write_u16(classes.len().try_into().unwrap());
+            w.write_u16_vec(classes);
+        }
+        AttributeInfo::Module {
+            module_name_index,
+            module_flags,
+            module_version_index,
+            requires,
+            exports,
+            opens,
+            uses,
+            provides,
+            ..
+        } => {
+            w.write_u16(*module_name_index);
+            w.write_u16(module_flags.to_u16());
+            w.write_u16(*module_version_index);
+            w.write_u16(requires.len().try_into().unwrap());
+            for entry in requires.iter() {
+                w.write_u16(entry.requires_index);
+                w.write_u16(entry.requires_flags.to_u16());
+                w.write_u16(entry.requires_version_index);
+            }
+            w.write_u16(exports.len().try_into().unwrap());
+            for entry in exports.iter() {
+                w.write_u16(entry.exports_index);
+                w.write_u16(entry.exports_flags.to_u16());
+                w.write_u16(entry.exports_to_index.len().try_into().unwrap());
+                w.write_u16_vec(&entry.exports_to_index);
+            }
+            w.write_u16(opens.len().try_into().unwrap());
+            for entry in opens.iter() {
+                w.write_u16(entry.opens_index);
+                w.write_u16(entry.opens_flags.to_u16());
+                w.write_u16(entry.opens_to_index.len().try_into().unwrap());
+                w.write_u16_vec(&entry.opens_to_index);
+            }
+            w.write_u16(uses.len().try_into().unwrap());
+            w.write_u16_vec(uses);
+            w.write_u16(provides.len().try_into().unwrap());
+            for entry in provides.iter() {
+                w.write_u16(entry.provides_index);
+                w.write_u16(entry.provides_with_index.len().try_into().unwrap());
+                w.write_u16_vec(&entry.provides_with_index);
+            }
+        }
+        AttributeInfo::ModulePackages { packages, .. } => {
+            w.write_u16(packages.len().try_into().unwrap());
+            w.write_u16_vec(packages);
+        }
+        AttributeInfo::ModuleMainClass {
+            main_class_index, ..
+        } => {
+            w.write_u16(*main_class_index);
+        }
+        AttributeInfo::Deprecated { .. } | AttributeInfo::Synthetic { .. } => {}
+        AttributeInfo::Unknown { info, .. } => {
+            w.write_u8_vec(info);
+        }
     }
 }
 
@@ -635,3 +613,340 @@ fn write_element_value(w: &mut BinaryWriter, value: &ElementValue) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use binary_reader::BinaryReader;
+
+    use super::*;
+    use crate::{
+        attributes::{
+            AttributeKind, ExceptionTableEntry, find_attribute, parse_class_attributes,
+            parse_field_attributes, parse_method_attributes,
+        },
+        bytecode::{BytecodeInstruction, parse_bytecode},
+        classfile::parse_class_file_from_bytes,
+    };
+
+    /// Checks that the header of the written attribute holds its name_index and the size of the rest.
+    fn check_header(attribute: &AttributeInfo) -> Vec<u8> {
+        let bytes: Vec<u8> = write_attribute(attribute);
+        assert_eq!(
+            u16::from_be_bytes([bytes[0], bytes[1]]),
+            attribute.name_index()
+        );
+        assert_eq!(
+            u32::from_be_bytes([bytes[2], bytes[3], bytes[4], bytes[5]]) as usize,
+            bytes.len() - 6,
+            "Wrong attribute_length for a {} attribute.",
+            attribute.kind()
+        );
+        if let AttributeInfo::Code { attributes, .. } = attribute {
+            for attribute in attributes.iter() {
+                check_header(attribute);
+            }
+        }
+        bytes
+    }
+
+    type AttributeParser = fn(&mut BinaryReader, &ConstantPool, usize) -> Vec<AttributeInfo>;
+
+    /// Writes the given attribute and parses it back with the given parser, expecting to write the same bytes again.
+    fn roundtrip(cp: &ConstantPool, attribute: &AttributeInfo, parser: AttributeParser) {
+        let bytes: Vec<u8> = check_header(attribute);
+        let mut reader = BinaryReader::new(&bytes, binary_reader::Endianness::Big);
+        assert_eq!(write_attribute(&parser(&mut reader, cp, 1)[0]), bytes);
+    }
+
+    fn collect_kinds(attribute: &AttributeInfo, kinds: &mut Vec<AttributeKind>) {
+        if !kinds.contains(&attribute.kind()) {
+            kinds.push(attribute.kind());
+        }
+        if let AttributeInfo::Code { attributes, .. } = attribute {
+            for attribute in attributes.iter() {
+                collect_kinds(attribute, kinds);
+            }
+        }
+    }
+
+    #[test]
+    fn class_files_roundtrip() {
+        let data_dir: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../e2e/data");
+        let mut count: usize = 0;
+        for entry in std::fs::read_dir(data_dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path
+                .extension()
+                .is_none_or(|extension| extension != "class")
+            {
+                continue;
+            }
+            let bytes: Vec<u8> = std::fs::read(&path).unwrap();
+            assert!(
+                write_class_file(&parse_class_file_from_bytes(&bytes).unwrap()) == bytes,
+                "{} was not written back to the same bytes.",
+                path.display()
+            );
+            count += 1;
+        }
+        assert!(count > 0, "No class files in {data_dir}.");
+    }
+
+    #[test]
+    fn attributes_roundtrip_with_the_right_length() {
+        let fixtures: [&[u8]; 10] = [
+            include_bytes!("../../e2e/data/Annotated.class"),
+            include_bytes!("../../e2e/data/Employee.class"),
+            include_bytes!("../../e2e/data/Legacy.class"),
+            include_bytes!("../../e2e/data/module-info.class"),
+            include_bytes!("../../e2e/data/Nesting.class"),
+            include_bytes!("../../e2e/data/Parameters.class"),
+            include_bytes!("../../e2e/data/Point.class"),
+            include_bytes!("../../e2e/data/Sealed.class"),
+            include_bytes!("../../e2e/data/SecuritySettings$1.class"),
+            include_bytes!("../../e2e/data/TimeUnit.class"),
+        ];
+        let mut kinds: Vec<AttributeKind> = Vec::new();
+        for bytes in fixtures {
+            let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+            let cp: &ConstantPool = &cf.constant_pool;
+            for attribute in cf.attributes.iter() {
+                roundtrip(cp, attribute, parse_class_attributes);
+                collect_kinds(attribute, &mut kinds);
+            }
+            for field in cf.fields.iter() {
+                for attribute in field.attributes.iter() {
+                    roundtrip(cp, attribute, parse_field_attributes);
+                    collect_kinds(attribute, &mut kinds);
+                }
+            }
+            for method in cf.methods.iter() {
+                for attribute in method.attributes.iter() {
+                    roundtrip(cp, attribute, parse_method_attributes);
+                    collect_kinds(attribute, &mut kinds);
+                }
+            }
+        }
+
+        // none of the fixtures has attributes unknown to the parser
+        for kind in [
+            AttributeKind::Code,
+            AttributeKind::LineNumberTable,
+            AttributeKind::LocalVariableTable,
+            AttributeKind::LocalVariableTypeTable,
+            AttributeKind::StackMapTable,
+            AttributeKind::SourceFile,
+            AttributeKind::BootstrapMethods,
+            AttributeKind::InnerClasses,
+            AttributeKind::MethodParameters,
+            AttributeKind::Record,
+            AttributeKind::Signature,
+            AttributeKind::NestMembers,
+            AttributeKind::RuntimeVisibleAnnotations,
+            AttributeKind::ConstantValue,
+            AttributeKind::Exceptions,
+            AttributeKind::EnclosingMethod,
+            AttributeKind::NestHost,
+            AttributeKind::PermittedSubclasses,
+            AttributeKind::Module,
+            AttributeKind::ModulePackages,
+            AttributeKind::ModuleMainClass,
+            AttributeKind::Deprecated,
+        ] {
+            assert!(
+                kinds.contains(&kind),
+                "No {kind} attribute in the fixtures."
+            );
+        }
+    }
+
+    #[test]
+    fn marker_attributes() {
+        let mut cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Legacy.class")).unwrap();
+        let name_index: u16 = cf
+            .constant_pool
+            .add(ConstantPoolInfo::Utf8 {
+                bytes: b"Synthetic".to_vec(),
+            })
+            .unwrap();
+        let synthetic: AttributeInfo = AttributeInfo::Synthetic { name_index };
+        assert_eq!(&check_header(&synthetic)[2..], [0, 0, 0, 0]);
+        for parser in [
+            parse_class_attributes as AttributeParser,
+            parse_field_attributes,
+            parse_method_attributes,
+        ] {
+            roundtrip(&cf.constant_pool, &synthetic, parser);
+        }
+
+        for attributes in [
+            &cf.attributes,
+            &cf.fields[0].attributes,
+            &cf.methods[1].attributes,
+        ] {
+            assert!(find_attribute(attributes, AttributeKind::Deprecated).is_some());
+        }
+        assert!(find_attribute(&cf.methods[0].attributes, AttributeKind::Deprecated).is_none());
+    }
+
+    #[test]
+    fn unknown_attribute_length() {
+        let attribute: AttributeInfo = AttributeInfo::Unknown {
+            name_index: 7,
+            info: vec![1, 2, 3],
+        };
+        assert_eq!(check_header(&attribute), [0, 7, 0, 0, 0, 3, 1, 2, 3]);
+    }
+
+    #[test]
+    fn unknown_field_attribute_roundtrip() {
+        let mut cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Employee.class")).unwrap();
+        let name_index: u16 = cf.fields[0].name_index;
+        cf.fields[0].attributes.push(AttributeInfo::Unknown {
+            name_index,
+            info: vec![0xca, 0xfe],
+        });
+
+        let reparsed: ClassFile = parse_class_file_from_bytes(&write_class_file(&cf)).unwrap();
+        let Some(AttributeInfo::Unknown { name_index, info }) =
+            find_attribute(&reparsed.fields[0].attributes, AttributeKind::Unknown)
+        else {
+            panic!("Expected the field to keep its unknown attribute.");
+        };
+        assert_eq!(reparsed.constant_pool.get_utf8_content(*name_index), "id");
+        assert_eq!(info, &[0xca, 0xfe]);
+    }
+
+    #[test]
+    fn unknown_attributes_roundtrip() {
+        let mut cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/HelloWorld.class")).unwrap();
+        let name_index: u16 = cf
+            .constant_pool
+            .add(ConstantPoolInfo::Utf8 {
+                bytes: b"VendorData".to_vec(),
+            })
+            .unwrap();
+        let unknown = |info: &[u8]| AttributeInfo::Unknown {
+            name_index,
+            info: info.to_vec(),
+        };
+        cf.attributes.push(unknown(&[1]));
+        cf.attributes.push(unknown(&[2, 2]));
+        cf.methods[1].attributes.push(unknown(&[3]));
+        let Some(AttributeInfo::Code { attributes, .. }) = cf.methods[1]
+            .attributes
+            .iter_mut()
+            .find(|attribute| attribute.kind() == AttributeKind::Code)
+        else {
+            panic!("Expected main to have code.");
+        };
+        attributes.push(unknown(&[]));
+
+        let reparsed: ClassFile = parse_class_file_from_bytes(&write_class_file(&cf)).unwrap();
+        let infos = |attributes: &[AttributeInfo]| -> Vec<Vec<u8>> {
+            attributes
+                .iter()
+                .filter_map(|attribute| match attribute {
+                    AttributeInfo::Unknown { name_index, info } => {
+                        assert_eq!(
+                            reparsed.constant_pool.get_utf8_content(*name_index),
+                            "VendorData"
+                        );
+                        Some(info.clone())
+                    }
+                    _ => None,
+                })
+                .collect()
+        };
+        assert_eq!(infos(&reparsed.attributes), [vec![1], vec![2, 2]]);
+        assert_eq!(infos(&reparsed.methods[1].attributes), [vec![3]]);
+        let Some(AttributeInfo::Code { attributes, .. }) =
+            find_attribute(&reparsed.methods[1].attributes, AttributeKind::Code)
+        else {
+            panic!("Expected main to have code.");
+        };
+        assert_eq!(infos(attributes), [Vec::<u8>::new()]);
+    }
+
+    #[test]
+    fn trailing_bytes_roundtrip() {
+        let mut cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/HelloWorld.class")).unwrap();
+        let Some(AttributeInfo::Code { trailing_bytes, .. }) = cf.methods[1]
+            .attributes
+            .iter_mut()
+            .find(|attribute| attribute.kind() == AttributeKind::Code)
+        else {
+            panic!("Expected main to have code.");
+        };
+        assert!(trailing_bytes.is_empty());
+        trailing_bytes.extend([0xff, 0x00, 0xfe]);
+
+        let reparsed: ClassFile = parse_class_file_from_bytes(&write_class_file(&cf)).unwrap();
+        let Some(AttributeInfo::Code {
+            code,
+            trailing_bytes,
+            ..
+        }) = find_attribute(&reparsed.methods[1].attributes, AttributeKind::Code)
+        else {
+            panic!("Expected main to have code.");
+        };
+        assert_eq!(code.len(), 4);
+        assert_eq!(trailing_bytes, &[0xff, 0x00, 0xfe]);
+    }
+    #[test]
+    fn longer_encodings_are_shortened() {
+        let mut cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/HelloWorld.class")).unwrap();
+        // iconst_0; ifeq 9; wide aload 0; pop; return
+        let bytes: [u8; 10] = [0x03, 0x99, 0x00, 0x08, 0xc4, 0x19, 0x00, 0x00, 0x57, 0xb1];
+        let parsed_code: Vec<(u32, BytecodeInstruction)> = parse_bytecode(
+            &mut BinaryReader::new(&bytes, binary_reader::Endianness::Big),
+            &cf.constant_pool,
+        );
+        let Some(AttributeInfo::Code {
+            code,
+            exception_table,
+            attributes,
+            ..
+        }) = cf.methods[1]
+            .attributes
+            .iter_mut()
+            .find(|attribute| attribute.kind() == AttributeKind::Code)
+        else {
+            panic!("Expected main to have code.");
+        };
+        *code = parsed_code;
+        *exception_table = vec![ExceptionTableEntry {
+            start_pc: 4,
+            end_pc: 9,
+            handler_pc: 9,
+            catch_type: 0,
+        }];
+        attributes.clear();
+
+        let reparsed: ClassFile = parse_class_file_from_bytes(&write_class_file(&cf)).unwrap();
+        let Some(AttributeInfo::Code {
+            code,
+            exception_table,
+            ..
+        }) = find_attribute(&reparsed.methods[1].attributes, AttributeKind::Code)
+        else {
+            panic!("Expected main to have code.");
+        };
+        let positions: Vec<u32> = code.iter().map(|(position, _)| *position).collect();
+        assert_eq!(positions, [0, 1, 4, 5, 6]);
+        assert!(matches!(code[1].1, BytecodeInstruction::IfEq { offset: 5 }));
+        assert_eq!(
+            (
+                exception_table[0].start_pc,
+                exception_table[0].end_pc,
+                exception_table[0].handler_pc
+            ),
+            (4, 6, 6)
+        );
+    }
+}
@@ -53,6 +53,106 @@ const TEST_CASES: &[TestCase] = &[
         name: "Calculator",
         executable: true,
     },
+    TestCase {
+        name: "Shape",
+        executable: false,
+    },
+    TestCase {
+        name: "Stripped",
+        executable: false,
+    },
+    TestCase {
+        name: "Assertions",
+        executable: true,
+    },
+    TestCase {
+        name: "DefaultMethods",
+        executable: false,
+    },
+    TestCase {
+        name: "Sealed",
+        executable: false,
+    },
+    TestCase {
+        name: "Sealed$Circle",
+        executable: false,
+    },
+    TestCase {
+        name: "Annotated",
+        executable: false,
+    },
+    TestCase {
+        name: "Nesting",
+        executable: false,
+    },
+    TestCase {
+        name: "Conversions",
+        executable: true,
+    },
+    TestCase {
+        name: "Parameters",
+        executable: false,
+    },
+    TestCase {
+        name: "OldSubroutine",
+        executable: true,
+    },
+    TestCase {
+        name: "Constructors",
+        executable: false,
+    },
+    TestCase {
+        name: "MethodConstants",
+        executable: true,
+    },
+    TestCase {
+        name: "Synchronized",
+        executable: true,
+    },
+    TestCase {
+        name: "Point",
+        executable: true,
+    },
+    TestCase {
+        name: "Literals",
+        executable: true,
+    },
+    TestCase {
+        name: "GenericFields",
+        executable: true,
+    },
+    TestCase {
+        name: "FloatLocals",
+        executable: true,
+    },
+    TestCase {
+        name: "MultiArrays",
+        executable: true,
+    },
+    TestCase {
+        name: "WideLocals",
+        executable: true,
+    },
+    TestCase {
+        name: "Condy",
+        executable: true,
+    },
+    TestCase {
+        name: "Legacy",
+        executable: false,
+    },
+    TestCase {
+        name: "Finals",
+        executable: false,
+    },
+    TestCase {
+        name: "Modifiers",
+        executable: false,
+    },
+    TestCase {
+        name: "module-info",
+        executable: false,
+    },
 ];
 
 struct TestEnv {
@@ -129,7 +229,7 @@ fn run(cmd: &mut Command) -> (Output, String) {
     (output, cmdline)
 }
 
-fn jobf_shuffle(env: &TestEnv, input: &Path, output: &Path, flag: &str) -> Result<(), String> {
+fn jobf_transform(env: &TestEnv, input: &Path, output: &Path, flag: &str) -> Result<(), String> {
     let (out, cmdline) = run(Command::new(&env.jobf)
         .arg("--input")
         .arg(input)
@@ -204,8 +304,13 @@ fn fail(failures: &mut Vec<String>, name: &str, reason: &str) {
     println!("{reason}");
 }
 
-fn run_javap_tests(env: &TestEnv) -> Vec<String> {
-    println!("\nJavap output tests");
+fn run_javap_tests(
+    env: &TestEnv,
+    label: &str,
+    system_args: &[&str],
+    our_args: &[&str],
+) -> Vec<String> {
+    println!("\nJavap output tests ({label})");
 
     let mut failures = vec![];
 
@@ -213,10 +318,11 @@ fn run_javap_tests(env: &TestEnv) -> Vec<String> {
         let class_file = env.class_file(case.name);
 
         let (expected_out, expected_cmd) = run(Command::new(&env.system_javap)
-            .args(["-l", "-v", "-p"])
+            .args(system_args)
             .arg(&class_file));
 
-        let (actual_out, actual_cmd) = run(Command::new(&env.our_javap).arg(&class_file));
+        let (actual_out, actual_cmd) =
+            run(Command::new(&env.our_javap).args(our_args).arg(&class_file));
 
         if !expected_out.status.success() {
             fail(
@@ -300,7 +406,7 @@ fn run_roundtrip_tests(env: &TestEnv) -> Vec<String> {
     failures
 }
 
-fn run_shuffle_javap_tests(env: &TestEnv, label: &str, shuffle_flag: &str) -> Vec<String> {
+fn run_transformed_javap_tests(env: &TestEnv, label: &str, flag: &str) -> Vec<String> {
     println!("\nEncoding after {label} roundtrip tests");
 
     let mut failures = vec![];
@@ -308,9 +414,9 @@ fn run_shuffle_javap_tests(env: &TestEnv, label: &str, shuffle_flag: &str) -> Ve
     for case in TEST_CASES {
         let class_file = env.class_file(case.name);
         let tmp = TempDir::new().unwrap();
-        let shuffled = env.tmp_class_file(&tmp, case.name);
+        let transformed = env.tmp_class_file(&tmp, case.name);
 
-        if let Err(detail) = jobf_shuffle(env, &class_file, &shuffled, shuffle_flag) {
+        if let Err(detail) = jobf_transform(env, &class_file, &transformed, flag) {
             fail(
                 &mut failures,
                 case.name,
@@ -321,9 +427,9 @@ fn run_shuffle_javap_tests(env: &TestEnv, label: &str, shuffle_flag: &str) -> Ve
 
         let (expected_out, expected_cmd) = run(Command::new(&env.system_javap)
             .args(["-l", "-v", "-p"])
-            .arg(&shuffled));
+            .arg(&transformed));
 
-        let (actual_out, actual_cmd) = run(Command::new(&env.our_javap).arg(&shuffled));
+        let (actual_out, actual_cmd) = run(Command::new(&env.our_javap).arg(&transformed));
 
         if !expected_out.status.success() {
             fail(
@@ -362,7 +468,7 @@ fn run_shuffle_javap_tests(env: &TestEnv, label: &str, shuffle_flag: &str) -> Ve
     failures
 }
 
-fn run_shuffle_execution_tests(env: &TestEnv, label: &str, shuffle_flag: &str) -> Vec<String> {
+fn run_transformed_execution_tests(env: &TestEnv, label: &str, flag: &str) -> Vec<String> {
     println!("\nExecution after {label} tests");
 
     let mut failures = vec![];
@@ -370,7 +476,7 @@ fn run_shuffle_execution_tests(env: &TestEnv, label: &str, shuffle_flag: &str) -
     for case in TEST_CASES.iter().filter(|c| c.executable) {
         let class_file = env.class_file(case.name);
         let tmp = TempDir::new().unwrap();
-        let shuffled = env.tmp_class_file(&tmp, case.name);
+        let transformed = env.tmp_class_file(&tmp, case.name);
 
         let (original, original_cmd) = run(Command::new(&env.system_java)
             .arg("-cp")
@@ -389,7 +495,7 @@ fn run_shuffle_execution_tests(env: &TestEnv, label: &str, shuffle_flag: &str) -
             continue;
         }
 
-        if let Err(detail) = jobf_shuffle(env, &class_file, &shuffled, shuffle_flag) {
+        if let Err(detail) = jobf_transform(env, &class_file, &transformed, flag) {
             fail(
                 &mut failures,
                 case.name,
@@ -448,28 +554,89 @@ fn main() {
     println!(" Tested jobf:    {}", env.jobf.display());
 
     let mut failures: Vec<String> = vec![];
-    failures.extend(run_javap_tests(&env));
+    failures.extend(run_javap_tests(&env, "verbose", &["-l", "-v", "-p"], &[]));
+    failures.extend(run_javap_tests(&env, "api", &[], &["--api"]));
     failures.extend(run_roundtrip_tests(&env));
-    failures.extend(run_shuffle_javap_tests(
+    failures.extend(run_transformed_javap_tests(
         &env,
         "field-shuffle",
         "--shuffle-fields=true",
     ));
-    failures.extend(run_shuffle_execution_tests(
+    failures.extend(run_transformed_execution_tests(
         &env,
         "field-shuffle",
         "--shuffle-fields=true",
     ));
-    failures.extend(run_shuffle_javap_tests(
+    failures.extend(run_transformed_javap_tests(
         &env,
         "method-shuffle",
         "--shuffle-methods=true",
     ));
-    failures.extend(run_shuffle_execution_tests(
+    failures.extend(run_transformed_execution_tests(
         &env,
         "method-shuffle",
         "--shuffle-methods=true",
     ));
+    failures.extend(run_transformed_javap_tests(
+        &env,
+        "strip-assertions",
+        "--strip-assertions=true",
+    ));
+    failures.extend(run_transformed_execution_tests(
+        &env,
+        "strip-assertions",
+        "--strip-assertions=true",
+    ));
+    failures.extend(run_transformed_javap_tests(
+        &env,
+        "strip-debug",
+        "--strip-debug=true",
+    ));
+    failures.extend(run_transformed_execution_tests(
+        &env,
+        "strip-debug",
+        "--strip-debug=true",
+    ));
+    failures.extend(run_transformed_javap_tests(
+        &env,
+        "rename-identifiers",
+        "--rename-identifiers=true",
+    ));
+    failures.extend(run_transformed_execution_tests(
+        &env,
+        "rename-identifiers",
+        "--rename-identifiers=true",
+    ));
+    failures.extend(run_transformed_javap_tests(
+        &env,
+        "encrypt-strings",
+        "--encrypt-strings=true",
+    ));
+    failures.extend(run_transformed_execution_tests(
+        &env,
+        "encrypt-strings",
+        "--encrypt-strings=true",
+    ));
+    failures.extend(run_transformed_javap_tests(
+        &env,
+        "compact-constant-pool",
+        "--compact-constant-pool=true",
+    ));
+    failures.extend(run_transformed_execution_tests(
+        &env,
+        "compact-constant-pool",
+        "--compact-constant-pool=true",
+    ));
+    failures.extend(run_transformed_javap_tests(
+        &env,
+        "canonicalize",
+        "--canonicalize=true",
+    ));
+    failures.extend(run_transformed_execution_tests(
+        &env,
+        "canonicalize",
+        "--canonicalize=true",
+    ));
 
     if failures.is_empty() {
         println!("\n{GREEN}All tests passed.{RESET}");
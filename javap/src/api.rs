@@ -0,0 +1,230 @@
+use classfile::access_flags::{FieldAccessFlag, MethodAccessFlag};
+use classfile::attributes::{AttributeInfo, AttributeKind, find_attribute};
+use classfile::classfile::ClassFile;
+use classfile::constant_pool::ConstantPool;
+
+use crate::javap::{
+    get_class_declaration, get_field_declaration, get_method_declaration, load_class_file,
+};
+
+pub(crate) fn print_class_api(filenames: &[String]) {
+    for filename in filenames {
+        let cf: ClassFile = load_class_file(filename);
+        print!("{}", class_api(&cf));
+    }
+}
+
+/**
+ * Renders the declarations of the class and of its non-private members, without any body, like `javap` without
+ * options.
+ */
+pub(crate) fn class_api(cf: &ClassFile) -> String {
+    let cp = &cf.constant_pool;
+    let mut out: String = String::new();
+
+    if let Some(source_file) = cf.source_file() {
+        out += &format!("Compiled from \"{source_file}\"\n");
+    }
+    out += &format!("{} {{\n", without_object_bounds(&get_class_declaration(cf)));
+    if let Some(module) = find_attribute(&cf.attributes, AttributeKind::Module) {
+        out += &module_directives(cp, module);
+    }
+    for field in cf
+        .fields
+        .iter()
+        .filter(|f| !f.access_flags.contains(FieldAccessFlag::Private))
+    {
+        out += &format!("  {};\n", get_field_declaration(cp, field));
+    }
+    for method in cf
+        .methods
+        .iter()
+        .filter(|m| !m.access_flags.contains(MethodAccessFlag::Private))
+    {
+        out += &format!(
+            "  {};\n",
+            without_object_bounds(&get_method_declaration(cp, cf, method, false))
+        );
+    }
+    out += "}\n";
+
+    out
+}
+
+/**
+ * Renders the directives of a module declaration, like `requires transitive java.logging;`, in the order javap
+ * prints them.
+ */
+fn module_directives(cp: &ConstantPool, module: &AttributeInfo) -> String {
+    let AttributeInfo::Module {
+        requires,
+        exports,
+        opens,
+        uses,
+        provides,
+        ..
+    } = module
+    else {
+        unreachable!()
+    };
+    let package_name = |idx: u16| cp.get_package_name(idx).replace('/', ".");
+    let class_name = |idx: u16| cp.get_class_name(idx).replace('/', ".");
+    // the targets of a directive are listed one per line
+    let targets = |names: Vec<String>| -> String {
+        names
+            .iter()
+            .map(|name| format!("\n    {name}"))
+            .collect::<Vec<String>>()
+            .join(",")
+    };
+
+    let mut out: String = String::new();
+    for entry in requires {
+        let modifiers: String = entry.requires_flags.modifier_repr();
+        out += "  requires ";
+        if !modifiers.is_empty() {
+            out += &(modifiers + " ");
+        }
+        out += &format!("{};\n", cp.get_module_name(entry.requires_index));
+    }
+    for (directive, package_index, to_indices) in exports
+        .iter()
+        .map(|entry| ("exports", entry.exports_index, &entry.exports_to_index))
+        .chain(
+            opens
+                .iter()
+                .map(|entry| ("opens", entry.opens_index, &entry.opens_to_index)),
+        )
+    {
+        out += &format!("  {directive} {}", package_name(package_index));
+        if !to_indices.is_empty() {
+            out += " to";
+            out += &targets(
+                to_indices
+                    .iter()
+                    .map(|idx| cp.get_module_name(*idx))
+                    .collect(),
+            );
+        }
+        out += ";\n";
+    }
+    for class_index in uses {
+        out += &format!("  uses {};\n", class_name(*class_index));
+    }
+    for entry in provides {
+        // javap prints two spaces after 'provides'
+        out += &format!(
+            "  provides  {} with{};\n",
+            class_name(entry.provides_index),
+            targets(
+                entry
+                    .provides_with_index
+                    .iter()
+                    .map(|idx| class_name(*idx))
+                    .collect()
+            )
+        );
+    }
+    out
+}
+
+/**
+ * Removes the implicit `extends java.lang.Object` bound of type parameters and superclass of generic classes, which
+ * `javap` only shows in verbose mode. Wildcards keep their bound.
+ */
+fn without_object_bounds(declaration: &str) -> String {
+    const BOUND: &str = " extends java.lang.Object";
+    let mut out: String = String::new();
+    let mut rest: &str = declaration;
+    while let Some(index) = rest.find(BOUND) {
+        let after: &str = &rest[index + BOUND.len()..];
+        let is_type_parameter: bool =
+            !rest[..index].ends_with('?') && (after.starts_with('>') || after.starts_with(','));
+        let is_superclass: bool = after.is_empty() || after.starts_with(" implements ");
+        out += &rest[..index];
+        if !is_type_parameter && !is_superclass {
+            out += BOUND;
+        }
+        rest = after;
+    }
+    out += rest;
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use classfile::classfile::parse_class_file_from_bytes;
+
+    use super::*;
+
+    #[test]
+    fn api_stub() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Parameters.class")).unwrap();
+        assert_eq!(
+            class_api(&cf),
+            [
+                "Compiled from \"Parameters.java\"",
+                "public class Parameters {",
+                "  public Parameters(java.lang.String);",
+                "  public int f(int, java.lang.String);",
+                "  public static <T> void generic(java.util.Map<java.lang.String, java.util.List<T>>, long[], java.lang.String...);",
+                "  public void none();",
+                "}",
+                ""
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn generic_class_stub() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/GenericFields.class"))
+                .unwrap();
+        assert_eq!(
+            class_api(&cf),
+            [
+                "Compiled from \"GenericFields.java\"",
+                "public class GenericFields<T extends java.lang.Comparable<T>> {",
+                "  protected java.util.Map<java.lang.String, java.util.List<? extends java.lang.Number>> index;",
+                "  T value;",
+                "  java.util.List<T>[] buckets;",
+                "  int count;",
+                "  public GenericFields();",
+                "  public static void main(java.lang.String[]);",
+                "}",
+                ""
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn module_stub() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/module-info.class"))
+                .unwrap();
+        assert_eq!(
+            class_api(&cf),
+            [
+                "Compiled from \"module-info.java\"",
+                "module com.example {",
+                "  requires java.base;",
+                "  requires transitive java.logging;",
+                "  requires static java.sql;",
+                "  exports com.example.api;",
+                "  exports com.example.spi to",
+                "    java.logging,",
+                "    java.sql;",
+                "  opens com.example.impl;",
+                "  uses java.lang.Runnable;",
+                "  provides  java.lang.Runnable with",
+                "    com.example.impl.Task;",
+                "}",
+                ""
+            ]
+            .join("\n")
+        );
+    }
+}
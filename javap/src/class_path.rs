@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use classfile::classfile::{ClassFile, parse_class_file_from_bytes};
+use zip::ZipArchive;
+
+/**
+ * A place where the bytes of a class can be looked up by its binary name, like `java/lang/Object`.
+ */
+trait ClassSource {
+    fn load(&self, binary_name: &str) -> Option<Vec<u8>>;
+}
+
+/// A directory containing class files laid out by package, like `java/lang/Object.class`.
+struct DirectorySource {
+    root: PathBuf,
+}
+
+impl ClassSource for DirectorySource {
+    fn load(&self, binary_name: &str) -> Option<Vec<u8>> {
+        fs::read(self.root.join(format!("{binary_name}.class"))).ok()
+    }
+}
+
+/// A jar file, whose class files are all read when it is opened.
+struct JarSource {
+    classes: HashMap<String, Vec<u8>>,
+}
+
+impl JarSource {
+    fn open(path: &Path) -> zip::result::ZipResult<JarSource> {
+        let mut archive = ZipArchive::new(File::open(path)?)?;
+        let mut classes: HashMap<String, Vec<u8>> = HashMap::new();
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i)?;
+            let Some(binary_name) = entry.name().strip_suffix(".class").map(str::to_owned) else {
+                continue;
+            };
+            let mut bytes: Vec<u8> = Vec::new();
+            entry.read_to_end(&mut bytes)?;
+            classes.insert(binary_name, bytes);
+        }
+        Ok(JarSource { classes })
+    }
+}
+
+impl ClassSource for JarSource {
+    fn load(&self, binary_name: &str) -> Option<Vec<u8>> {
+        self.classes.get(binary_name).cloned()
+    }
+}
+
+/**
+ * An ordered list of directories and jar files. Like the class path of java, each class is loaded from the first
+ * source containing it.
+ */
+pub(crate) struct ClassPath {
+    sources: Vec<Box<dyn ClassSource>>,
+}
+
+impl ClassPath {
+    /**
+     * Builds the class path described by the given list of directories and jar files, separated by ':'. Like java,
+     * entries which do not exist are ignored. Files which cannot be read as a jar are ignored as well, with a warning.
+     */
+    pub(crate) fn parse(class_path: &str) -> ClassPath {
+        ClassPath {
+            sources: class_path
+                .split(':')
+                .map(Path::new)
+                .filter(|path| path.exists())
+                .filter_map(|path| -> Option<Box<dyn ClassSource>> {
+                    if path.is_dir() {
+                        return Some(Box::new(DirectorySource {
+                            root: path.to_path_buf(),
+                        }));
+                    }
+                    match JarSource::open(path) {
+                        Ok(jar) => Some(Box::new(jar)),
+                        Err(err) => {
+                            eprintln!("Ignoring '{}' due to: {err}.", path.display());
+                            None
+                        }
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the class with the given binary name, if any source of the class path contains it.
+    pub(crate) fn load(&self, binary_name: &str) -> Option<ClassFile> {
+        let bytes: Vec<u8> = self
+            .sources
+            .iter()
+            .find_map(|source| source.load(binary_name))?;
+        Some(
+            parse_class_file_from_bytes(&bytes)
+                .unwrap_or_else(|error| panic!("Could not parse {binary_name}: {error}")),
+        )
+    }
+}
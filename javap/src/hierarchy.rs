@@ -0,0 +1,134 @@
+use std::collections::HashSet;
+
+use classfile::access_flags::ClassAccessFlag;
+
+use crate::class_path::ClassPath;
+use crate::javap::{get_class_declaration, get_method_declaration};
+
+pub(crate) fn print_hierarchies(class_path: &ClassPath, class_names: &[String]) {
+    for class_name in class_names {
+        print!("{}", hierarchy(class_path, class_name));
+    }
+}
+
+/**
+ * Renders the declaration of the given class followed by its declared methods and, one level deeper, the same for
+ * its superclass and its interfaces, loaded transitively from the class path. Classes missing from the class path are
+ * marked as such, while the ones reached more than once are expanded only the first time.
+ */
+pub(crate) fn hierarchy(class_path: &ClassPath, class_name: &str) -> String {
+    let mut out: String = String::new();
+    let mut visited: HashSet<String> = HashSet::new();
+    print_class(
+        &mut out,
+        class_path,
+        &class_name.replace('.', "/"),
+        0,
+        &mut visited,
+    );
+    out
+}
+
+fn print_class(
+    out: &mut String,
+    class_path: &ClassPath,
+    binary_name: &str,
+    depth: usize,
+    visited: &mut HashSet<String>,
+) {
+    let indent: String = "  ".repeat(depth);
+    let name: String = binary_name.replace('/', ".");
+    if !visited.insert(binary_name.to_owned()) {
+        *out += &format!("{indent}{name} (see above)\n");
+        return;
+    }
+    let Some(cf) = class_path.load(binary_name) else {
+        *out += &format!("{indent}{name} (not found)\n");
+        return;
+    };
+    let cp = &cf.constant_pool;
+
+    *out += &format!("{indent}{}\n", get_class_declaration(&cf));
+    for method in cf.methods.iter() {
+        *out += &format!(
+            "{indent}  {};\n",
+            get_method_declaration(cp, &cf, method, false)
+        );
+    }
+
+    // like their declaration, interfaces do not show java.lang.Object as their superclass
+    let mut supertypes: Vec<String> = Vec::new();
+    if cf.super_class != 0 && !cf.access_flags.contains(ClassAccessFlag::Interface) {
+        supertypes.push(cp.get_class_name(cf.super_class));
+    }
+    supertypes.extend(cf.interfaces.iter().map(|idx| cp.get_class_name(*idx)));
+    for supertype in supertypes {
+        print_class(out, class_path, &supertype, depth + 1, visited);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../e2e/data");
+
+    #[test]
+    fn hierarchy_from_a_jar() {
+        let class_path: ClassPath = ClassPath::parse(&format!("{DATA_DIR}/Hierarchy.jar"));
+        assert_eq!(
+            hierarchy(&class_path, "hierarchy.Child"),
+            [
+                "public class hierarchy.Child extends hierarchy.Base implements java.lang.Runnable,hierarchy.Named",
+                "  public hierarchy.Child();",
+                "  public void run();",
+                "  public java.lang.String name();",
+                "  public abstract class hierarchy.Base implements hierarchy.Named",
+                "    protected hierarchy.Base(int);",
+                "    public abstract void run();",
+                "    public int id();",
+                "    java.lang.Object (not found)",
+                "    public interface hierarchy.Named",
+                "      public abstract java.lang.String name();",
+                "  java.lang.Runnable (not found)",
+                "  hierarchy.Named (see above)",
+                ""
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn hierarchy_from_a_directory_and_a_jar() {
+        let class_path: ClassPath =
+            ClassPath::parse(&format!("{DATA_DIR}:{DATA_DIR}/Hierarchy.jar"));
+        assert_eq!(
+            hierarchy(&class_path, "hierarchy/Named"),
+            "public interface hierarchy.Named\n  public abstract java.lang.String name();\n"
+        );
+        assert!(
+            hierarchy(&class_path, "Sealed$Circle")
+                .starts_with("public final class Sealed$Circle extends Sealed\n")
+        );
+    }
+
+    #[test]
+    fn unusable_class_path_entries_are_ignored() {
+        let class_path: ClassPath = ClassPath::parse(&format!(
+            "{DATA_DIR}/missing:{DATA_DIR}/Missing.jar:{DATA_DIR}/Shape.class:{DATA_DIR}/Hierarchy.jar"
+        ));
+        assert_eq!(
+            hierarchy(&class_path, "hierarchy/Named"),
+            "public interface hierarchy.Named\n  public abstract java.lang.String name();\n"
+        );
+    }
+
+    #[test]
+    fn missing_class() {
+        let class_path: ClassPath = ClassPath::parse(DATA_DIR);
+        assert_eq!(
+            hierarchy(&class_path, "does.not.Exist"),
+            "does.not.Exist (not found)\n"
+        );
+    }
+}
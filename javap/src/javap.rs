@@ -1,21 +1,27 @@
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::{BufReader, Read};
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
-use binary_reader::{BinaryReader, Endianness};
-use classfile::access_flags::{ClassAccessFlag, MethodAccessFlag};
+use classfile::access_flags::{
+    ClassAccessFlag, MethodAccessFlag, ModuleAccessFlag, ModuleExportsAccessFlag,
+    ModuleExportsAccessFlags, ModuleRequiresAccessFlag,
+};
 use classfile::attributes::{
-    AttributeInfo, AttributeKind, StackMapFrame, VerificationTypeInfo, find_attribute,
+    Annotation, AttributeInfo, AttributeKind, ElementValue, ExceptionTableEntry, StackMapFrame,
+    VerificationTypeInfo, find_attribute,
 };
-use classfile::bytecode::BytecodeInstruction;
-use classfile::classfile::{ClassFile, parse_class_file};
+use classfile::bytecode::{BytecodeInstruction, get_instruction_length};
+use classfile::classfile::{ClassFile, java_version_name, parse_class_file_from_bytes};
 use classfile::constant_pool::{self, ConstantPool, ConstantPoolInfo};
-use classfile::descriptor::{ClassSignature, decode_class_signature, decode_type};
+use classfile::descriptor::{
+    decode_class_signature, decode_type, parse_field_descriptor, parse_method_descriptor,
+};
 use classfile::fields::FieldInfo;
 use classfile::methods::MethodInfo;
 use classfile::reference_kind;
 use classfile::utils::absolute_no_symlinks;
+use classfile::verify;
 use date::Date;
 
 use crate::line_writer::LineWriter;
@@ -25,9 +31,62 @@ use crate::line_writer::LineWriter;
  */
 const BYTECODE_INDEX_LENGTH: usize = 4;
 
-pub(crate) fn print_class_file(filename: String) {
-    let mut lw: LineWriter = LineWriter::new();
+/**
+ * Changes to the output of `javap -l -v -p`, which the default options reproduce exactly.
+ */
+pub(crate) struct FormatOptions {
+    /// The number of spaces of each indentation level.
+    pub(crate) indent_width: usize,
+    /// Prints the size, modification date and SHA-256 checksum of the class file after its path.
+    pub(crate) file_details: bool,
+    /// Prints the `descriptor:` and `flags:` lines of each field and method.
+    pub(crate) member_details: bool,
+    /// Follows the header of each Code attribute with the maximum stack depth and number of locals computed from the
+    /// bytecode, flagging the ones differing from the declared values.
+    pub(crate) recompute: bool,
+    /// Gives the instructions without a comment a short description of what they do.
+    pub(crate) explain: bool,
+    /// Prints the names found in the MethodParameters attribute next to the parameter types.
+    pub(crate) parameter_names: bool,
+    /// Prints at most this many instructions of each method, followed by the number of the hidden ones.
+    pub(crate) max_instructions: Option<usize>,
+    /// Follows the major version with the name of the Java release which introduced it.
+    pub(crate) java_version: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            indent_width: 2,
+            file_details: true,
+            member_details: true,
+            recompute: false,
+            explain: false,
+            parameter_names: false,
+            max_instructions: None,
+            java_version: false,
+        }
+    }
+}
+
+/**
+ * Reads and parses the given class file, panicking if it cannot be read or parsed.
+ */
+pub(crate) fn load_class_file(filename: &str) -> ClassFile {
+    let file_bytes: Vec<u8> = fs::read(filename).expect("Could not read whole file");
+    parse_class_file_from_bytes(&file_bytes)
+        .unwrap_or_else(|error| panic!("Could not parse {filename}: {error}"))
+}
+
+pub(crate) fn print_class_file(filename: String, options: &FormatOptions) {
+    write_class_file(
+        &mut LineWriter::new(options.indent_width),
+        filename,
+        options,
+    );
+}
 
+fn write_class_file(lw: &mut LineWriter, filename: String, options: &FormatOptions) {
     let abs_file_path: PathBuf = absolute_no_symlinks(Path::new(&filename)).unwrap();
     let absolute_file_path: String = abs_file_path.to_str().unwrap().to_owned();
     lw.println(&("Classfile ".to_owned() + &absolute_file_path.to_string()));
@@ -46,85 +105,104 @@ pub(crate) fn print_class_file(filename: String) {
 
     let date: Date = Date::from(modified_time);
 
-    lw.print("  Last modified ")
-        .print(&date.month())
-        .print(" ")
-        .print(&date.day())
-        .print(", ")
-        .print(&date.year())
-        .print("; size ")
-        .print(&file_size.to_string())
-        .println(" bytes");
-
-    lw.print("  SHA-256 checksum ").println(
-        &digest
-            .iter()
-            .map(|x| format!("{x:02x}"))
-            .collect::<Vec<String>>()
-            .concat(),
-    );
+    lw.indent(1);
+    if options.file_details {
+        lw.print("Last modified ")
+            .print(&date.month())
+            .print(" ")
+            .print(&date.day())
+            .print(", ")
+            .print(&date.year())
+            .print("; size ")
+            .print(&file_size.to_string())
+            .println(" bytes");
+
+        lw.print("SHA-256 checksum ").println(
+            &digest
+                .iter()
+                .map(|x| format!("{x:02x}"))
+                .collect::<Vec<String>>()
+                .concat(),
+        );
+    }
 
-    let abs_file_path: PathBuf = absolute_no_symlinks(Path::new(&filename)).unwrap();
-    let file: File = File::open(&abs_file_path).expect("File does not exist");
-    let mut file_reader: BufReader<File> = BufReader::new(file);
-    let mut file_bytes: Vec<u8> = Vec::with_capacity(file_reader.capacity());
-    file_reader
-        .read_to_end(&mut file_bytes)
-        .expect("Could not read whole file");
+    let cf: ClassFile = parse_class_file_from_bytes(&file_bytes)
+        .unwrap_or_else(|error| panic!("Could not parse {absolute_file_path}: {error}"));
 
-    let mut reader = BinaryReader::new(&file_bytes, Endianness::Big);
-    let cf: ClassFile = parse_class_file(&mut reader);
+    if let Some(source_file) = cf.source_file() {
+        lw.print("Compiled from \"")
+            .print(&source_file)
+            .println("\"");
+    }
+    lw.indent(-1);
 
-    print_header(&mut lw, &cf);
-    print_constant_pool(&mut lw, &cf.constant_pool);
+    print_header(lw, &cf, options);
+    print_constant_pool(lw, &cf.constant_pool);
     lw.println("{");
     lw.indent(1);
-    print_fields(&mut lw, &cf.constant_pool, &cf.fields);
-    print_methods(&mut lw, &cf.constant_pool, &cf, cf.this_class, &cf.methods);
+    print_fields(lw, &cf.constant_pool, &cf.fields, options);
+    print_methods(
+        lw,
+        &cf.constant_pool,
+        &cf,
+        cf.this_class,
+        &cf.methods,
+        options,
+    );
     lw.indent(-1);
     lw.println("}");
-    print_class_attributes(&mut lw, &cf.constant_pool, &cf.attributes);
+    print_class_attributes(lw, &cf.constant_pool, &cf.attributes);
 }
 
-fn print_header(lw: &mut LineWriter, cf: &ClassFile) {
-    let source_file: String = cf
-        .attributes
-        .iter()
-        .filter(|attr| matches!(attr, AttributeInfo::SourceFile { .. }))
-        .map(|attr| match attr {
-            AttributeInfo::SourceFile {
-                source_file_index, ..
-            } => cf.constant_pool.get_utf8_content(*source_file_index),
-            _ => unreachable!(),
-        })
-        .next()
-        .unwrap();
-    lw.print("  Compiled from \"")
-        .print(&source_file)
-        .println("\"");
+/**
+ * Returns the first line of the declaration of the given class, like `public class A<T> extends B<T>`.
+ */
+pub(crate) fn get_class_declaration(cf: &ClassFile) -> String {
+    if let Some(AttributeInfo::Module {
+        module_name_index,
+        module_flags,
+        module_version_index,
+        ..
+    }) = find_attribute(&cf.attributes, AttributeKind::Module)
+    {
+        let mut out: String = String::new();
+        if module_flags.contains(ModuleAccessFlag::Open) {
+            out += "open ";
+        }
+        out += &format!(
+            "module {}",
+            cf.constant_pool.get_module_name(*module_name_index)
+        );
+        if *module_version_index != 0 {
+            out += &format!(
+                "@{}",
+                cf.constant_pool.get_utf8_content(*module_version_index)
+            );
+        }
+        return out;
+    }
 
     let this_class_name = cf
         .constant_pool
         .get_class_name(cf.this_class)
         .replace('/', ".");
 
-    lw.print(&cf.access_flags.modifier_repr())
-        .print(" ")
-        .print(&this_class_name);
+    let mut out: String = format!("{} {}", cf.access_flags.modifier_repr(), this_class_name);
 
     let this_class_signature = find_attribute(&cf.attributes, AttributeKind::Signature);
+    // a signature which cannot be decoded is ignored, like a missing one
     if let Some(AttributeInfo::Signature {
         signature_index, ..
     }) = this_class_signature
+        && let Ok(decoded) =
+            decode_class_signature(&cf.constant_pool.get_utf8_content(*signature_index))
     {
         let is_interface: bool = cf.access_flags.contains(ClassAccessFlag::Interface);
-        let decoded: ClassSignature =
-            decode_class_signature(&cf.constant_pool.get_utf8_content(*signature_index));
 
         let actual_super_class: String = decoded.super_class_name.clone();
 
         if !decoded.generic_type_bounds.is_empty() {
-            lw.print(&format!(
+            out += &format!(
                 "<{}>",
                 decoded
                     .generic_type_bounds
@@ -132,24 +210,59 @@ fn print_header(lw: &mut LineWriter, cf: &ClassFile) {
                     .map(|gtb| format!("{} extends {}", gtb.type_name, gtb.type_bounds.join(", ")))
                     .collect::<Vec<String>>()
                     .join(", ")
-            ));
+            );
         }
 
         if is_interface {
-            lw.print(&format!(" extends {}", decoded.interfaces.join(", ")));
+            out += &format!(" extends {}", decoded.interfaces.join(", "));
         } else {
-            lw.print(&format!(" extends {actual_super_class}"));
+            out += &format!(" extends {actual_super_class}");
+            if !decoded.interfaces.is_empty() {
+                out += &format!(" implements {}", decoded.interfaces.join(", "));
+            }
+        }
+    } else {
+        let is_interface: bool = cf.access_flags.contains(ClassAccessFlag::Interface);
+        if !is_interface && cf.super_class != 0 {
+            let super_class_name: String = cf
+                .constant_pool
+                .get_class_name(cf.super_class)
+                .replace('/', ".");
+            if super_class_name != "java.lang.Object" {
+                out += &format!(" extends {super_class_name}");
+            }
+        }
+        if !cf.interfaces.is_empty() {
+            out += if is_interface {
+                " extends "
+            } else {
+                " implements "
+            };
+            out += &cf
+                .interfaces
+                .iter()
+                .map(|idx| cf.constant_pool.get_class_name(*idx).replace('/', "."))
+                .collect::<Vec<String>>()
+                .join(",");
         }
     }
 
-    lw.println("");
+    out
+}
+
+fn print_header(lw: &mut LineWriter, cf: &ClassFile, options: &FormatOptions) {
+    lw.println(&get_class_declaration(cf));
 
     lw.indent(1);
 
+    let major_version: String = match java_version_name(cf.major_version) {
+        Some(name) if options.java_version => format!("{} (Java {name})", cf.major_version),
+        _ => cf.major_version.to_string(),
+    };
     lw.print("minor version: ")
         .println(&cf.minor_version.to_string())
         .print("major version: ")
-        .println(&cf.major_version.to_string())
+        .println(&major_version)
         .print("flags: (")
         .print(&format!("0x{:04x}", cf.access_flags.to_u16()))
         .print(") ")
@@ -159,11 +272,15 @@ fn print_header(lw: &mut LineWriter, cf: &ClassFile) {
         .tab()
         .print("// ")
         .println(&cf.constant_pool.get_class_name(cf.this_class));
-    lw.print("super_class: #")
-        .print(&cf.super_class.to_string())
-        .tab()
-        .print("// ")
-        .println(&cf.constant_pool.get_class_name(cf.super_class));
+    if cf.super_class == 0 {
+        lw.println("super_class: #0");
+    } else {
+        lw.print("super_class: #")
+            .print(&cf.super_class.to_string())
+            .tab()
+            .print("// ")
+            .println(&cf.constant_pool.get_class_name(cf.super_class));
+    }
     lw.print("interfaces: ")
         .print(&cf.interfaces.len().to_string())
         .print(", fields: ")
@@ -188,7 +305,7 @@ fn print_constant_pool(lw: &mut LineWriter, cp: &ConstantPool) {
             > In retrospect, making 8-byte constants take two constant pool entries was a poor choice.
             Source: <https://docs.oracle.com/javase/specs/jvms/se25/html/jvms-4.html#jvms-4.4.5>
         */
-        if i > 1
+        if i > 0
             && (matches!(
                 cp[(i - 1).try_into().unwrap()],
                 ConstantPoolInfo::Long { .. }
@@ -205,13 +322,17 @@ fn print_constant_pool(lw: &mut LineWriter, cp: &ConstantPool) {
             ("#".to_owned() + &(i + 1).to_string())
         ));
 
-        let entry = &cp[i.try_into().unwrap()];
+        let cp_index: u16 = (i + 1).try_into().unwrap();
+        let entry = &cp[cp_index - 1];
 
         lw.print(&format!(" = {:<18} ", entry.tag().to_string()));
 
         match entry {
             ConstantPoolInfo::Utf8 { bytes } => {
-                let content: String = constant_pool::convert_utf8(bytes).trim_end().to_owned();
+                let content: String =
+                    constant_pool::escape_for_display(&constant_pool::convert_utf8(bytes))
+                        .trim_end()
+                        .to_owned();
                 if !content.trim().is_empty() {
                     lw.print(&content);
                 }
@@ -220,28 +341,21 @@ fn print_constant_pool(lw: &mut LineWriter, cp: &ConstantPool) {
             ConstantPoolInfo::Integer { bytes } => {
                 lw.println(&(*bytes as i32).to_string());
             }
-            ConstantPoolInfo::Float { bytes } => {
-                lw.println(&format!("{}f", java_format_float(f32::from_bits(*bytes))));
+            ConstantPoolInfo::Float { .. } => {
+                lw.println(&format!("{}f", java_format_float(cp.get_float(cp_index))));
             }
-            ConstantPoolInfo::Long {
-                high_bytes,
-                low_bytes,
-            } => {
-                lw.println(&format!("{}l", get_long_value(*high_bytes, *low_bytes)));
+            ConstantPoolInfo::Long { .. } => {
+                lw.println(&format!("{}l", cp.get_long(cp_index)));
             }
-            ConstantPoolInfo::Double {
-                high_bytes,
-                low_bytes,
-            } => {
-                lw.println(&format!(
-                    "{}d",
-                    java_format_double(get_double_value(*high_bytes, *low_bytes))
-                ));
+            ConstantPoolInfo::Double { .. } => {
+                lw.println(&format!("{}d", java_format_double(cp.get_double(cp_index))));
             }
             ConstantPoolInfo::String { string_index } => {
                 lw.print(&format!("#{string_index}")).tab();
                 let string_content: String =
-                    cp.get_utf8_content(*string_index).trim_end().to_owned();
+                    constant_pool::escape_for_display(&cp.get_utf8_content(*string_index))
+                        .trim_end()
+                        .to_owned();
                 if string_content.trim().is_empty() {
                     lw.println("//");
                 } else {
@@ -311,6 +425,19 @@ fn print_constant_pool(lw: &mut LineWriter, cp: &ConstantPool) {
                         cp.get_method_ref(*reference_index)
                     ));
             }
+            ConstantPoolInfo::Dynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            } => {
+                lw.print(&format!(
+                    "#{bootstrap_method_attr_index}:#{name_and_type_index}"
+                ))
+                .tab()
+                .print("// ")
+                .println(
+                    &cp.get_dynamic_string(*bootstrap_method_attr_index, *name_and_type_index),
+                );
+            }
             ConstantPoolInfo::InvokeDynamic {
                 bootstrap_method_attr_index,
                 name_and_type_index,
@@ -327,6 +454,12 @@ fn print_constant_pool(lw: &mut LineWriter, cp: &ConstantPool) {
                     ),
                 );
             }
+            ConstantPoolInfo::Module { name_index } | ConstantPoolInfo::Package { name_index } => {
+                lw.print(&format!("#{name_index}"))
+                    .tab()
+                    .print("// ")
+                    .println(&cp.get_wrapped_utf8_content(*name_index));
+            }
             ConstantPoolInfo::Null {} => unreachable!(),
         }
     }
@@ -334,32 +467,61 @@ fn print_constant_pool(lw: &mut LineWriter, cp: &ConstantPool) {
     lw.indent(-1);
 }
 
-fn print_fields(lw: &mut LineWriter, cp: &ConstantPool, fields: &[FieldInfo]) {
+/**
+ * Returns the Java form of the given descriptor or signature, or the descriptor itself if it cannot be decoded.
+ */
+fn decode_type_or_raw(descriptor: &str) -> String {
+    decode_type(descriptor).unwrap_or_else(|_| descriptor.to_owned())
+}
+
+/**
+ * Returns the declaration of the given field, like `private final java.lang.String name`, showing its generic type from
+ * the Signature attribute, if present.
+ */
+pub(crate) fn get_field_declaration(cp: &ConstantPool, field: &FieldInfo) -> String {
+    let signature: Option<&AttributeInfo> =
+        find_attribute(&field.attributes, AttributeKind::Signature);
+    let field_type: String = match signature {
+        Some(AttributeInfo::Signature {
+            signature_index, ..
+        }) => decode_type_or_raw(&cp.get_utf8_content(*signature_index)),
+        Some(_) => unreachable!(),
+        None => {
+            let descriptor: String = cp.get_utf8_content(field.descriptor_index);
+            parse_field_descriptor(&descriptor)
+                .map_or(descriptor, |parsed| parsed.field_type.to_string())
+        }
+    };
+
+    let modifiers: String = field.access_flags.modifier_repr();
+    let declaration: String = format!("{field_type} {}", cp.get_utf8_content(field.name_index));
+    if modifiers.is_empty() {
+        declaration
+    } else {
+        modifiers + " " + &declaration
+    }
+}
+
+fn print_fields(
+    lw: &mut LineWriter,
+    cp: &ConstantPool,
+    fields: &[FieldInfo],
+    options: &FormatOptions,
+) {
     for field in fields.iter() {
         let descriptor: String = cp.get_utf8_content(field.descriptor_index);
-        let signature: Option<&AttributeInfo> =
-            find_attribute(&field.attributes, AttributeKind::Signature);
-        lw.println(&format!(
-            "{} {} {};",
-            field.access_flags.modifier_repr(),
-            match signature {
-                Some(AttributeInfo::Signature {
-                    signature_index, ..
-                }) => decode_type(&cp.get_utf8_content(*signature_index)),
-                Some(_) => unreachable!(),
-                None => decode_type(&descriptor),
-            },
-            cp.get_utf8_content(field.name_index)
-        ));
+        lw.println(&format!("{};", get_field_declaration(cp, field)));
 
         lw.indent(1);
 
-        lw.println(&format!("descriptor: {descriptor}"));
-        lw.println(&format!(
-            "flags: (0x{:04x}) {}",
-            field.access_flags.to_u16(),
-            field.access_flags.java_repr()
-        ));
+        if options.member_details {
+            lw.println(&format!("descriptor: {descriptor}"));
+            lw.println(&format!(
+                "flags: (0x{:04x}) {}",
+                field.access_flags.to_u16(),
+                field.access_flags.java_repr()
+            ));
+        }
         print_field_attributes(lw, cp, field);
 
         lw.indent(-1);
@@ -387,101 +549,159 @@ fn print_field_attributes(lw: &mut LineWriter, cp: &ConstantPool, field: &FieldI
                     get_constant_string(cp, *constant_value_index)
                 ));
             }
+            AttributeInfo::RuntimeVisibleAnnotations { annotations, .. } => {
+                print_runtime_visible_annotations(lw, cp, annotations)
+            }
+            AttributeInfo::Deprecated { .. } => {
+                lw.println("Deprecated: true");
+            }
+            AttributeInfo::Synthetic { .. } => {
+                lw.println("Synthetic: true");
+            }
+            AttributeInfo::Unknown { name_index, info } => {
+                print_unknown_attribute(lw, cp, *name_index, info)
+            }
             _ => unreachable!("Unknown field attribute {}.", attribute.kind()),
         }
     }
 }
 
-fn print_methods(
-    lw: &mut LineWriter,
+/**
+ * Prints the name and the raw bytes of an attribute not modeled by the classfile crate, sixteen bytes per line.
+ */
+fn print_unknown_attribute(lw: &mut LineWriter, cp: &ConstantPool, name_index: u16, info: &[u8]) {
+    lw.println(&format!(
+        "{}: length = 0x{:x} (unknown attribute)",
+        cp.get_utf8_content(name_index),
+        info.len()
+    ));
+    for chunk in info.chunks(16) {
+        let bytes: Vec<String> = chunk.iter().map(|b| format!("{b:02x}")).collect();
+        lw.println(&format!(" {}", bytes.join(" ")));
+    }
+}
+
+/**
+ * Returns the declaration of the given method, like `public static void main(java.lang.String[])`. With
+ * `parameter_names`, the names found in the MethodParameters attribute are printed next to the parameter types.
+ */
+pub(crate) fn get_method_declaration(
     cp: &ConstantPool,
     cf: &ClassFile,
-    this_class: u16,
-    methods: &[MethodInfo],
-) {
-    for (i, method) in methods.iter().enumerate() {
-        let method_name: String = cp.get_utf8_content(method.name_index);
-        let raw_descriptor: String = cp.get_utf8_content(method.descriptor_index);
+    method: &MethodInfo,
+    parameter_names: bool,
+) -> String {
+    let method_name: String = cp.get_utf8_content(method.name_index);
+    let raw_descriptor: String = cp.get_utf8_content(method.descriptor_index);
+
+    let signature = find_attribute(&method.attributes, AttributeKind::Signature);
+
+    let parsed_descriptor: String = match signature {
+        Some(AttributeInfo::Signature {
+            signature_index, ..
+        }) => decode_type_or_raw(&cp.get_utf8_content(*signature_index)),
+        _ => parse_method_descriptor(&raw_descriptor)
+            .map_or_else(|_| raw_descriptor.clone(), |parsed| parsed.to_string()),
+    };
 
-        let signature = find_attribute(&method.attributes, AttributeKind::Signature);
+    let modifiers: String = method.access_flags.modifier_repr();
+    let mut out: String = if modifiers.is_empty() {
+        String::new()
+    } else {
+        modifiers + " "
+    };
 
-        let parsed_descriptor: String = match signature {
-            Some(AttributeInfo::Signature {
-                signature_index, ..
-            }) => decode_type(&cp.get_utf8_content(*signature_index)),
-            _ => decode_type(&raw_descriptor),
-        };
+    let is_class_initializer: bool = method_name == "<clinit>";
 
-        if i > 0 {
-            lw.println("");
-        }
-        lw.print(&format!("{} ", method.access_flags.modifier_repr()));
+    // This obscure condition has been copied from the original javap source code
+    // https://github.com/openjdk/jdk/blob/08b25611f688ae85c05242afc4cee5b538db4f67/src/jdk.jdeps/share/classes/com/sun/tools/javap/ClassWriter.java#L493
+    if cf.access_flags.contains(ClassAccessFlag::Interface)
+        && !method.access_flags.contains(MethodAccessFlag::Abstract)
+        && !is_class_initializer
+        && !method.access_flags.contains(MethodAccessFlag::Static)
+        && !method.access_flags.contains(MethodAccessFlag::Private)
+    {
+        out += "default ";
+    }
 
-        let is_class_initializer: bool = method_name == "<clinit>";
+    let is_constructor: bool = method_name == "<init>";
 
-        // This obscure condition has been copied from the original javap source code
-        // https://github.com/openjdk/jdk/blob/08b25611f688ae85c05242afc4cee5b538db4f67/src/jdk.jdeps/share/classes/com/sun/tools/javap/ClassWriter.java#L493
-        if cf.access_flags.contains(ClassAccessFlag::Interface)
-            && !method.access_flags.contains(MethodAccessFlag::Abstract)
-            && !is_class_initializer
-            && !method.access_flags.contains(MethodAccessFlag::Static)
-            && !method.access_flags.contains(MethodAccessFlag::Private)
-        {
-            lw.print("default ");
+    if is_class_initializer {
+        // this is the 'static {}' block of the class
+        out += "{}";
+    } else {
+        let first_bracket_index = parsed_descriptor.find('(').unwrap();
+        let return_type: String = parsed_descriptor[0..first_bracket_index].to_owned();
+        let mut arguments_string: String =
+            parsed_descriptor[first_bracket_index..parsed_descriptor.len()].to_owned();
+
+        if method.access_flags.contains(MethodAccessFlag::Varargs) {
+            // replace last '[]' with '...'
+            arguments_string = arguments_string[..arguments_string.len() - 3].to_owned() + "...)";
         }
 
-        let is_constructor: bool = method_name == "<init>";
+        if parameter_names {
+            arguments_string = add_parameter_names(cp, method, &arguments_string);
+        }
 
-        if is_class_initializer {
-            // this is the 'static {}' block of the class
-            lw.print("{}");
+        if is_constructor {
+            // this is a constructor of the class: only its type parameters, if any, precede the name
+            let type_parameters: &str = return_type.strip_suffix("void").unwrap_or(&return_type);
+            let this_class_name: String = cp.get_class_name(cf.this_class).replace('/', ".");
+            out += &format!("{type_parameters}{this_class_name}{arguments_string}");
         } else {
-            let first_bracket_index = parsed_descriptor.find('(').unwrap();
-            let return_type: String = parsed_descriptor[0..first_bracket_index].to_owned();
-            let mut arguments_string: String =
-                parsed_descriptor[first_bracket_index..parsed_descriptor.len()].to_owned();
-
-            if method.access_flags.contains(MethodAccessFlag::Varargs) {
-                // replace last '[]' with '...'
-                arguments_string =
-                    arguments_string[..arguments_string.len() - 3].to_owned() + "...)";
-            }
-
-            if is_constructor {
-                // this is a constructor of the class
-                let this_class_name: String = cp.get_class_name(this_class).replace('/', ".");
-                lw.print(&format!("{this_class_name}{arguments_string}"));
-            } else {
-                lw.print(&format!("{return_type} {method_name}{arguments_string}"));
-            }
+            out += &format!("{return_type} {method_name}{arguments_string}");
         }
+    }
 
-        if let Some(AttributeInfo::Exceptions {
-            exception_indices, ..
-        }) = find_attribute(&method.attributes, AttributeKind::Exceptions)
-        {
-            lw.print(&format!(
-                " throws {}",
-                exception_indices
-                    .iter()
-                    .map(|exc_idx| cp.get_class_name(*exc_idx).replace('/', "."))
-                    .collect::<Vec<String>>()
-                    .join(", ")
-            ));
-        }
+    if let Some(AttributeInfo::Exceptions {
+        exception_indices, ..
+    }) = find_attribute(&method.attributes, AttributeKind::Exceptions)
+    {
+        out += &format!(
+            " throws {}",
+            exception_indices
+                .iter()
+                .map(|exc_idx| cp.get_class_name(*exc_idx).replace('/', "."))
+                .collect::<Vec<String>>()
+                .join(", ")
+        );
+    }
 
-        lw.println(";");
+    out
+}
 
-        lw.indent(1);
+fn print_methods(
+    lw: &mut LineWriter,
+    cp: &ConstantPool,
+    cf: &ClassFile,
+    this_class: u16,
+    methods: &[MethodInfo],
+    options: &FormatOptions,
+) {
+    for (i, method) in methods.iter().enumerate() {
+        let raw_descriptor: String = cp.get_utf8_content(method.descriptor_index);
 
-        lw.println(&format!("descriptor: {raw_descriptor}"));
+        if i > 0 {
+            lw.println("");
+        }
         lw.println(&format!(
-            "flags: (0x{:04x}) {}",
-            method.access_flags.to_u16(),
-            method.access_flags.java_repr()
+            "{};",
+            get_method_declaration(cp, cf, method, options.parameter_names)
         ));
 
-        print_method_attributes(lw, cp, this_class, method);
+        lw.indent(1);
+
+        if options.member_details {
+            lw.println(&format!("descriptor: {raw_descriptor}"));
+            lw.println(&format!(
+                "flags: (0x{:04x}) {}",
+                method.access_flags.to_u16(),
+                method.access_flags.java_repr()
+            ));
+        }
+
+        print_method_attributes(lw, cp, this_class, method, options);
 
         lw.indent(-1);
     }
@@ -503,10 +723,19 @@ where
     }
 }
 
-fn get_opcode_and_arguments_string(position: &u32, instruction: &BytecodeInstruction) -> String {
+pub(crate) fn get_opcode_and_arguments_string(
+    position: &u32,
+    instruction: &BytecodeInstruction,
+) -> String {
     match instruction {
+        BytecodeInstruction::Nop {} => "nop".to_owned(),
         BytecodeInstruction::Dup {} => "dup".to_owned(),
+        BytecodeInstruction::DupX1 {} => "dup_x1".to_owned(),
+        BytecodeInstruction::DupX2 {} => "dup_x2".to_owned(),
         BytecodeInstruction::Dup2 {} => "dup2".to_owned(),
+        BytecodeInstruction::Dup2X1 {} => "dup2_x1".to_owned(),
+        BytecodeInstruction::Dup2X2 {} => "dup2_x2".to_owned(),
+        BytecodeInstruction::Swap {} => "swap".to_owned(),
         BytecodeInstruction::AConstNull {} => "aconst_null".to_owned(),
         BytecodeInstruction::IConst { constant } => {
             if *constant == -1 {
@@ -553,7 +782,7 @@ fn get_opcode_and_arguments_string(position: &u32, instruction: &BytecodeInstruc
             if *local_variable_index <= 3 {
                 "aload_".to_owned() + &local_variable_index.to_string()
             } else {
-                "aload         ".to_owned() + &local_variable_index.to_string()
+                format!("{:<14}{local_variable_index}", instruction.mnemonic())
             }
         }
         BytecodeInstruction::AStore {
@@ -562,7 +791,7 @@ fn get_opcode_and_arguments_string(position: &u32, instruction: &BytecodeInstruc
             if *local_variable_index <= 3 {
                 "astore_".to_owned() + &local_variable_index.to_string()
             } else {
-                "astore        ".to_owned() + &local_variable_index.to_string()
+                format!("{:<14}{local_variable_index}", instruction.mnemonic())
             }
         }
         BytecodeInstruction::ILoad {
@@ -571,7 +800,7 @@ fn get_opcode_and_arguments_string(position: &u32, instruction: &BytecodeInstruc
             if *local_variable_index <= 3 {
                 "iload_".to_owned() + &local_variable_index.to_string()
             } else {
-                "iload         ".to_owned() + &local_variable_index.to_string()
+                format!("{:<14}{local_variable_index}", instruction.mnemonic())
             }
         }
         BytecodeInstruction::IStore {
@@ -580,7 +809,7 @@ fn get_opcode_and_arguments_string(position: &u32, instruction: &BytecodeInstruc
             if *local_variable_index <= 3 {
                 "istore_".to_owned() + &local_variable_index.to_string()
             } else {
-                "istore        ".to_owned() + &local_variable_index.to_string()
+                format!("{:<14}{local_variable_index}", instruction.mnemonic())
             }
         }
         BytecodeInstruction::LLoad {
@@ -589,7 +818,7 @@ fn get_opcode_and_arguments_string(position: &u32, instruction: &BytecodeInstruc
             if *local_variable_index <= 3 {
                 "lload_".to_owned() + &local_variable_index.to_string()
             } else {
-                "lload         ".to_owned() + &local_variable_index.to_string()
+                format!("{:<14}{local_variable_index}", instruction.mnemonic())
             }
         }
         BytecodeInstruction::LStore {
@@ -598,7 +827,7 @@ fn get_opcode_and_arguments_string(position: &u32, instruction: &BytecodeInstruc
             if *local_variable_index <= 3 {
                 "lstore_".to_owned() + &local_variable_index.to_string()
             } else {
-                "lstore        ".to_owned() + &local_variable_index.to_string()
+                format!("{:<14}{local_variable_index}", instruction.mnemonic())
             }
         }
         BytecodeInstruction::FLoad {
@@ -607,7 +836,7 @@ fn get_opcode_and_arguments_string(position: &u32, instruction: &BytecodeInstruc
             if *local_variable_index <= 3 {
                 "fload_".to_owned() + &local_variable_index.to_string()
             } else {
-                "fload         ".to_owned() + &local_variable_index.to_string()
+                format!("{:<14}{local_variable_index}", instruction.mnemonic())
             }
         }
         BytecodeInstruction::FStore {
@@ -616,7 +845,7 @@ fn get_opcode_and_arguments_string(position: &u32, instruction: &BytecodeInstruc
             if *local_variable_index <= 3 {
                 "fstore_".to_owned() + &local_variable_index.to_string()
             } else {
-                "fstore        ".to_owned() + &local_variable_index.to_string()
+                format!("{:<14}{local_variable_index}", instruction.mnemonic())
             }
         }
         BytecodeInstruction::DLoad {
@@ -625,7 +854,7 @@ fn get_opcode_and_arguments_string(position: &u32, instruction: &BytecodeInstruc
             if *local_variable_index <= 3 {
                 "dload_".to_owned() + &local_variable_index.to_string()
             } else {
-                "dload         ".to_owned() + &local_variable_index.to_string()
+                format!("{:<14}{local_variable_index}", instruction.mnemonic())
             }
         }
         BytecodeInstruction::DStore {
@@ -634,7 +863,7 @@ fn get_opcode_and_arguments_string(position: &u32, instruction: &BytecodeInstruc
             if *local_variable_index <= 3 {
                 "dstore_".to_owned() + &local_variable_index.to_string()
             } else {
-                "dstore        ".to_owned() + &local_variable_index.to_string()
+                format!("{:<14}{local_variable_index}", instruction.mnemonic())
             }
         }
         BytecodeInstruction::IaLoad {} => "iaload".to_owned(),
@@ -659,7 +888,13 @@ fn get_opcode_and_arguments_string(position: &u32, instruction: &BytecodeInstruc
         BytecodeInstruction::ANewArray {
             constant_pool_index,
         } => "anewarray     #".to_owned() + &constant_pool_index.to_string(),
+        BytecodeInstruction::MultiANewArray {
+            constant_pool_index,
+            dimensions,
+        } => format!("multianewarray #{constant_pool_index},  {dimensions}"),
         BytecodeInstruction::AThrow {} => "athrow".to_owned(),
+        BytecodeInstruction::MonitorEnter {} => "monitorenter".to_owned(),
+        BytecodeInstruction::MonitorExit {} => "monitorexit".to_owned(),
         BytecodeInstruction::New {
             constant_pool_index,
         } => "new           #".to_owned() + &constant_pool_index.to_string(),
@@ -777,6 +1012,18 @@ fn get_opcode_and_arguments_string(position: &u32, instruction: &BytecodeInstruc
         BytecodeInstruction::GoTo { offset } => {
             "goto          ".to_owned() + &add_offset(*position, *offset).to_string()
         }
+        BytecodeInstruction::Jsr { offset } => {
+            "jsr           ".to_owned() + &add_offset(*position, *offset).to_string()
+        }
+        BytecodeInstruction::GoToW { offset } => {
+            "goto_w        ".to_owned() + &add_offset(*position, *offset).to_string()
+        }
+        BytecodeInstruction::JsrW { offset } => {
+            "jsr_w         ".to_owned() + &add_offset(*position, *offset).to_string()
+        }
+        BytecodeInstruction::Ret {
+            local_variable_index,
+        } => format!("{:<14}{local_variable_index}", instruction.mnemonic()),
 
         // Switches
         BytecodeInstruction::TableSwitch {
@@ -828,7 +1075,7 @@ fn get_opcode_and_arguments_string(position: &u32, instruction: &BytecodeInstruc
 
         // Arithmetic instructions
         BytecodeInstruction::IInc { index, constant } => {
-            "iinc          ".to_owned() + &index.to_string() + ", " + &constant.to_string()
+            format!("{:<14}{index}, {constant}", instruction.mnemonic())
         }
 
         BytecodeInstruction::I2L {} => "i2l".to_owned(),
@@ -886,48 +1133,55 @@ fn get_opcode_and_arguments_string(position: &u32, instruction: &BytecodeInstruc
         BytecodeInstruction::DDiv {} => "ddiv".to_owned(),
         BytecodeInstruction::DRem {} => "drem".to_owned(),
         BytecodeInstruction::DSub {} => "dsub".to_owned(),
+        BytecodeInstruction::Unknown { opcode } => format!("// unknown opcode 0x{opcode:02x}"),
     }
 }
 
-fn get_long_value(high_bytes: u32, low_bytes: u32) -> i64 {
-    (((high_bytes as u64) << 32) | (low_bytes as u64)) as i64
-}
-
-fn get_double_value(high_bytes: u32, low_bytes: u32) -> f64 {
-    f64::from_bits(((high_bytes as u64) << 32) | (low_bytes as u64))
-}
-
 fn get_constant_string(cp: &ConstantPool, constant_pool_index: u16) -> String {
     let entry = &cp[constant_pool_index - 1];
     match entry {
         ConstantPoolInfo::String { string_index } => {
-            let string_content: String = cp.get_utf8_content(*string_index).trim_end().to_owned();
+            let string_content: String =
+                constant_pool::escape_for_display(&cp.get_utf8_content(*string_index))
+                    .trim_end()
+                    .to_owned();
             if string_content.trim().is_empty() {
                 "String".to_owned()
             } else {
                 "String ".to_owned() + &string_content
             }
         }
-        ConstantPoolInfo::Integer { bytes } => "int ".to_owned() + &(*bytes as i32).to_string(),
-        ConstantPoolInfo::Long {
-            high_bytes,
-            low_bytes,
-        } => "long ".to_owned() + &get_long_value(*high_bytes, *low_bytes).to_string() + "l",
-        ConstantPoolInfo::Float { bytes } => {
-            format!("float {}f", java_format_float(f32::from_bits(*bytes)))
-        }
-        ConstantPoolInfo::Double {
-            high_bytes,
-            low_bytes,
-        } => {
-            format!(
-                "double {}d",
-                java_format_double(get_double_value(*high_bytes, *low_bytes))
-            )
-        }
+        ConstantPoolInfo::Integer { .. } => format!("int {}", cp.get_integer(constant_pool_index)),
+        ConstantPoolInfo::Long { .. } => format!("long {}l", cp.get_long(constant_pool_index)),
+        ConstantPoolInfo::Float { .. } => format!(
+            "float {}f",
+            java_format_float(cp.get_float(constant_pool_index))
+        ),
+        ConstantPoolInfo::Double { .. } => format!(
+            "double {}d",
+            java_format_double(cp.get_double(constant_pool_index))
+        ),
         ConstantPoolInfo::Class { name_index } => {
             "class ".to_owned() + &cp.get_wrapped_utf8_content(*name_index)
         }
+        ConstantPoolInfo::MethodType { descriptor_index } => {
+            "MethodType ".to_owned() + &cp.get_utf8_content(*descriptor_index)
+        }
+        ConstantPoolInfo::MethodHandle {
+            reference_kind,
+            reference_index,
+        } => format!(
+            "MethodHandle {} {}",
+            reference_kind::java_repr(*reference_kind),
+            cp.get_method_ref(*reference_index)
+        ),
+        ConstantPoolInfo::Dynamic {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+        } => {
+            "Dynamic ".to_owned()
+                + &cp.get_dynamic_string(*bootstrap_method_attr_index, *name_and_type_index)
+        }
         _ => unreachable!(
             "Unknown CP entry to get constant string from: {}.",
             entry.tag()
@@ -944,14 +1198,47 @@ fn get_method_type(cpe: &ConstantPoolInfo) -> String {
     .to_owned()
 }
 
-fn get_comment(
+/**
+ * Returns the comment of an invocation instruction: the kind of the referenced entry followed by the method, whose
+ * class is omitted when it is the current one.
+ */
+fn get_invocation_comment(cp: &ConstantPool, this_class: u16, method_ref_index: u16) -> String {
+    let method_entry = &cp[method_ref_index - 1];
+    get_method_type(method_entry)
+        + " "
+        + &match method_entry {
+            ConstantPoolInfo::MethodRef {
+                class_index,
+                name_and_type_index,
+            }
+            | ConstantPoolInfo::InterfaceMethodRef {
+                class_index,
+                name_and_type_index,
+            } => {
+                if *class_index == this_class {
+                    cp.get_name_and_type(*name_and_type_index)
+                } else {
+                    cp.get_method_ref(method_ref_index)
+                }
+            }
+            _ => unreachable!(),
+        }
+}
+
+pub(crate) fn get_comment(
     cp: &ConstantPool,
     this_class: u16,
     instruction: &BytecodeInstruction,
 ) -> Option<String> {
     match instruction {
-        BytecodeInstruction::Dup {}
+        BytecodeInstruction::Nop {}
+        | BytecodeInstruction::Dup {}
+        | BytecodeInstruction::DupX1 {}
+        | BytecodeInstruction::DupX2 {}
         | BytecodeInstruction::Dup2 {}
+        | BytecodeInstruction::Dup2X1 {}
+        | BytecodeInstruction::Dup2X2 {}
+        | BytecodeInstruction::Swap {}
         | BytecodeInstruction::AConstNull {}
         | BytecodeInstruction::IConst { .. }
         | BytecodeInstruction::LConst { .. }
@@ -985,6 +1272,8 @@ fn get_comment(
         | BytecodeInstruction::SaStore {}
         | BytecodeInstruction::NewArray { .. }
         | BytecodeInstruction::AThrow {}
+        | BytecodeInstruction::MonitorEnter {}
+        | BytecodeInstruction::MonitorExit {}
         | BytecodeInstruction::BiPush { .. }
         | BytecodeInstruction::SiPush { .. }
         | BytecodeInstruction::Pop {}
@@ -1018,6 +1307,10 @@ fn get_comment(
         | BytecodeInstruction::IfNull { .. }
         | BytecodeInstruction::IfNonNull { .. }
         | BytecodeInstruction::GoTo { .. }
+        | BytecodeInstruction::Jsr { .. }
+        | BytecodeInstruction::GoToW { .. }
+        | BytecodeInstruction::JsrW { .. }
+        | BytecodeInstruction::Ret { .. }
         | BytecodeInstruction::TableSwitch { .. }
         | BytecodeInstruction::LookupSwitch { .. }
         | BytecodeInstruction::IInc { .. }
@@ -1071,7 +1364,8 @@ fn get_comment(
         | BytecodeInstruction::DNeg {}
         | BytecodeInstruction::DDiv {}
         | BytecodeInstruction::DRem {}
-        | BytecodeInstruction::DSub {} => None,
+        | BytecodeInstruction::DSub {}
+        | BytecodeInstruction::Unknown { .. } => None,
 
         BytecodeInstruction::Ldc {
             constant_pool_index,
@@ -1086,6 +1380,10 @@ fn get_comment(
         BytecodeInstruction::ANewArray {
             constant_pool_index,
         } => Some("class ".to_owned() + &cp.get_class_name(*constant_pool_index)),
+        BytecodeInstruction::MultiANewArray {
+            constant_pool_index,
+            ..
+        } => Some("class ".to_owned() + &cp.get_class_name(*constant_pool_index)),
         BytecodeInstruction::New {
             constant_pool_index,
         } => Some("class ".to_owned() + &cp.get_class_name(*constant_pool_index)),
@@ -1160,81 +1458,10 @@ fn get_comment(
                     _ => unreachable!(),
                 },
         ),
-        BytecodeInstruction::InvokeSpecial { method_ref_index } => Some(
-            "Method ".to_owned()
-                + &match cp[method_ref_index - 1] {
-                    ConstantPoolInfo::MethodRef {
-                        class_index,
-                        name_and_type_index,
-                    } => {
-                        if class_index == this_class {
-                            cp.get_name_and_type(name_and_type_index)
-                        } else {
-                            cp.get_method_ref(*method_ref_index)
-                        }
-                    }
-                    _ => unreachable!(),
-                },
-        ),
-        BytecodeInstruction::InvokeStatic { method_ref_index } => {
-            let method_entry = &cp[method_ref_index - 1];
-            Some(
-                get_method_type(method_entry)
-                    + " "
-                    + &match method_entry {
-                        ConstantPoolInfo::MethodRef {
-                            class_index,
-                            name_and_type_index,
-                        } => {
-                            if *class_index == this_class {
-                                cp.get_name_and_type(*name_and_type_index)
-                            } else {
-                                cp.get_method_ref(*method_ref_index)
-                            }
-                        }
-                        ConstantPoolInfo::InterfaceMethodRef {
-                            class_index,
-                            name_and_type_index,
-                        } => {
-                            if *class_index == this_class {
-                                cp.get_name_and_type(*name_and_type_index)
-                            } else {
-                                cp.get_method_ref(*method_ref_index)
-                            }
-                        }
-                        _ => unreachable!(),
-                    },
-            )
-        }
-        BytecodeInstruction::InvokeVirtual { method_ref_index } => {
-            let method_entry = &cp[method_ref_index - 1];
-            Some(
-                get_method_type(method_entry)
-                    + " "
-                    + &match method_entry {
-                        ConstantPoolInfo::MethodRef {
-                            class_index,
-                            name_and_type_index,
-                        } => {
-                            if *class_index == this_class {
-                                cp.get_name_and_type(*name_and_type_index)
-                            } else {
-                                cp.get_method_ref(*method_ref_index)
-                            }
-                        }
-                        ConstantPoolInfo::InterfaceMethodRef {
-                            class_index,
-                            name_and_type_index,
-                        } => {
-                            if *class_index == this_class {
-                                cp.get_name_and_type(*name_and_type_index)
-                            } else {
-                                cp.get_method_ref(*method_ref_index)
-                            }
-                        }
-                        _ => unreachable!(),
-                    },
-            )
+        BytecodeInstruction::InvokeSpecial { method_ref_index }
+        | BytecodeInstruction::InvokeStatic { method_ref_index }
+        | BytecodeInstruction::InvokeVirtual { method_ref_index } => {
+            Some(get_invocation_comment(cp, this_class, *method_ref_index))
         }
         BytecodeInstruction::InvokeDynamic {
             constant_pool_index,
@@ -1242,36 +1469,7 @@ fn get_comment(
         BytecodeInstruction::InvokeInterface {
             constant_pool_index,
             ..
-        } => {
-            let method_entry = &cp[constant_pool_index - 1];
-            Some(
-                get_method_type(method_entry)
-                    + " "
-                    + &match method_entry {
-                        ConstantPoolInfo::MethodRef {
-                            class_index,
-                            name_and_type_index,
-                        } => {
-                            if *class_index == this_class {
-                                cp.get_name_and_type(*name_and_type_index)
-                            } else {
-                                cp.get_method_ref(*constant_pool_index)
-                            }
-                        }
-                        ConstantPoolInfo::InterfaceMethodRef {
-                            class_index,
-                            name_and_type_index,
-                        } => {
-                            if *class_index == this_class {
-                                cp.get_name_and_type(*name_and_type_index)
-                            } else {
-                                cp.get_method_ref(*constant_pool_index)
-                            }
-                        }
-                        _ => unreachable!(),
-                    },
-            )
-        }
+        } => Some(get_invocation_comment(cp, this_class, *constant_pool_index)),
     }
 }
 
@@ -1293,45 +1491,174 @@ fn get_verification_type_info_string(cp: &ConstantPool, vti: &VerificationTypeIn
     }
 }
 
-fn get_number_of_arguments(cp: &ConstantPool, method: &MethodInfo) -> u8 {
-    let descriptor: String = decode_type(&cp.get_utf8_content(method.descriptor_index));
-    let arguments: String = descriptor
-        .chars()
-        .skip(descriptor.find('(').unwrap())
-        .collect();
+/**
+ * Returns the given parenthesized list of argument types with the name of each argument, as found in the
+ * MethodParameters attribute, after its type. The list is returned unchanged when the attribute is absent or does not
+ * describe every argument.
+ */
+fn add_parameter_names(cp: &ConstantPool, method: &MethodInfo, arguments_string: &str) -> String {
+    let Some(AttributeInfo::MethodParameters { parameters, .. }) =
+        find_attribute(&method.attributes, AttributeKind::MethodParameters)
+    else {
+        return arguments_string.to_owned();
+    };
 
-    let mut args: u8 = 1;
-    if arguments == "()" {
-        args = 0;
-    } else {
-        let mut generics = 0;
-        for ch in arguments.chars() {
-            if ch == '<' {
-                generics += 1;
-            } else if ch == '>' {
-                generics -= 1;
-                if generics < 0 {
-                    panic!("Invalid generics syntax in method descriptor: '{arguments}'.",);
-                }
-            } else if ch == ',' && generics == 0 {
-                args += 1;
+    // split at the commas outside of generic type arguments
+    let inner: &str = &arguments_string[1..arguments_string.len() - 1];
+    let mut types: Vec<&str> = Vec::new();
+    let mut depth: usize = 0;
+    let mut start: usize = 0;
+    for (i, ch) in inner.char_indices() {
+        match ch {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            ',' if depth == 0 => {
+                types.push(inner[start..i].trim());
+                start = i + 1;
             }
+            _ => {}
         }
     }
-
-    if !method.access_flags.contains(MethodAccessFlag::Static) {
-        // if the method is not static, there is the implicit 'this' argument
-        args += 1;
+    if !inner.is_empty() {
+        types.push(inner[start..].trim());
+    }
+    if types.len() != parameters.len() {
+        return arguments_string.to_owned();
     }
 
-    args
-}
+    let arguments: Vec<String> = types
+        .iter()
+        .zip(parameters.iter())
+        .map(|(t, parameter)| {
+            if parameter.name_index == 0 {
+                (*t).to_owned()
+            } else {
+                format!("{t} {}", cp.get_utf8_content(parameter.name_index))
+            }
+        })
+        .collect();
+    format!("({})", arguments.join(", "))
+}
+
+/**
+ * Returns a short human-readable description of the given instruction, for the instructions whose effect is not
+ * obvious from the mnemonic alone.
+ */
+fn get_explanation(instruction: &BytecodeInstruction) -> Option<&'static str> {
+    Some(match instruction {
+        BytecodeInstruction::I2L {} => "widen int to long",
+        BytecodeInstruction::I2F {} => "convert int to float",
+        BytecodeInstruction::I2D {} => "widen int to double",
+        BytecodeInstruction::L2I {} => "narrow long to int",
+        BytecodeInstruction::L2F {} => "convert long to float",
+        BytecodeInstruction::L2D {} => "convert long to double",
+        BytecodeInstruction::F2I {} => "narrow float to int",
+        BytecodeInstruction::F2L {} => "narrow float to long",
+        BytecodeInstruction::F2D {} => "widen float to double",
+        BytecodeInstruction::D2I {} => "narrow double to int",
+        BytecodeInstruction::D2L {} => "narrow double to long",
+        BytecodeInstruction::D2F {} => "narrow double to float",
+        BytecodeInstruction::I2B {} => "narrow int to byte",
+        BytecodeInstruction::I2C {} => "narrow int to char",
+        BytecodeInstruction::I2S {} => "narrow int to short",
+        _ => return None,
+    })
+}
+
+/**
+ * Returns a line with the maximum stack depth and number of locals computed for the given method, marking the ones
+ * which differ from the declared values with a `!`.
+ */
+fn get_recomputed_sizes_string(
+    cp: &ConstantPool,
+    method: &MethodInfo,
+    code: &[(u32, BytecodeInstruction)],
+    exception_table: &[ExceptionTableEntry],
+    max_stack: u16,
+    max_locals: u16,
+) -> String {
+    let mark = |declared: u16, computed: u16| if declared == computed { "" } else { " !" };
+
+    let stack: String = match verify::compute_max_stack(cp, code, exception_table) {
+        Ok(computed) => format!("stack={computed}{}", mark(max_stack, computed)),
+        Err(err) => format!("stack=? ({} at {})", err.message, err.position),
+    };
+    let computed_locals: u16 = verify::compute_max_locals(cp, method);
+    format!(
+        "// computed: {stack}, locals={computed_locals}{}",
+        mark(max_locals, computed_locals)
+    )
+}
+
+/**
+ * Returns the number of arguments of the given method, or `?` if its descriptor cannot be parsed.
+ */
+fn get_number_of_arguments(cp: &ConstantPool, method: &MethodInfo) -> String {
+    let Ok(descriptor) = parse_method_descriptor(&cp.get_utf8_content(method.descriptor_index))
+    else {
+        return "?".to_owned();
+    };
+    let mut args: usize = descriptor.parameter_types.len();
+
+    if !method.access_flags.contains(MethodAccessFlag::Static) {
+        // if the method is not static, there is the implicit 'this' argument
+        args += 1;
+    }
+
+    args.to_string()
+}
+
+/**
+ * Returns the first `max_instructions` instructions of the given code and, if some were left out, a marker telling
+ * how many.
+ */
+fn truncate_code(
+    code: &[(u32, BytecodeInstruction)],
+    max_instructions: Option<usize>,
+) -> (&[(u32, BytecodeInstruction)], Option<String>) {
+    match max_instructions {
+        Some(max) if max < code.len() => (
+            &code[..max],
+            Some(format!(
+                "{:>BYTECODE_INDEX_LENGTH$}  ... ({} more)",
+                "",
+                code.len() - max
+            )),
+        ),
+        _ => (code, None),
+    }
+}
+
+/// Prints the undecodable bytes found after the last instruction, sixteen bytes per line.
+fn print_trailing_bytes(
+    lw: &mut LineWriter,
+    code: &[(u32, BytecodeInstruction)],
+    trailing_bytes: &[u8],
+) {
+    let position: u32 = code
+        .last()
+        .map(|(position, instruction)| position + get_instruction_length(instruction))
+        .unwrap_or(0);
+    lw.println(&format!(
+        "{position:>BYTECODE_INDEX_LENGTH$}: // {} trailing bytes",
+        trailing_bytes.len()
+    ));
+    for chunk in trailing_bytes.chunks(16) {
+        let bytes: Vec<String> = chunk.iter().map(|b| format!("{b:02x}")).collect();
+        lw.println(&format!(
+            "{:>BYTECODE_INDEX_LENGTH$}  {}",
+            "",
+            bytes.join(" ")
+        ));
+    }
+}
 
 fn print_method_attributes(
     lw: &mut LineWriter,
     cp: &ConstantPool,
     this_class: u16,
     method: &MethodInfo,
+    options: &FormatOptions,
 ) {
     for attribute in method.attributes.iter() {
         match attribute {
@@ -1339,6 +1666,7 @@ fn print_method_attributes(
                 max_stack,
                 max_locals,
                 code,
+                trailing_bytes,
                 exception_table,
                 attributes,
                 ..
@@ -1349,10 +1677,28 @@ fn print_method_attributes(
                 lw.println(&format!(
                     "stack={max_stack}, locals={max_locals}, args_size={args_size}"
                 ));
-                for (position, instruction) in code.iter() {
+                if options.recompute {
+                    lw.println(&get_recomputed_sizes_string(
+                        cp,
+                        method,
+                        code,
+                        exception_table,
+                        *max_stack,
+                        *max_locals,
+                    ));
+                }
+                let (shown, truncation_marker) = truncate_code(code, options.max_instructions);
+                for (position, instruction) in shown.iter() {
                     let opcode_and_arguments: String =
                         get_opcode_and_arguments_string(position, instruction);
-                    let comment: Option<String> = get_comment(cp, this_class, instruction);
+                    let comment: Option<String> =
+                        get_comment(cp, this_class, instruction).or_else(|| {
+                            options
+                                .explain
+                                .then(|| get_explanation(instruction))
+                                .flatten()
+                                .map(str::to_owned)
+                        });
                     match comment {
                         Some(content) => {
                             lw.print(&format!(
@@ -1368,17 +1714,26 @@ fn print_method_attributes(
                         }
                     }
                 }
+                if let Some(marker) = truncation_marker {
+                    lw.println(&marker);
+                }
+                if !trailing_bytes.is_empty() {
+                    print_trailing_bytes(lw, code, trailing_bytes);
+                }
                 if !exception_table.is_empty() {
                     lw.println("Exception table:");
                     lw.indent(1);
                     lw.println(" from    to  target type");
                     for exception in exception_table.iter() {
+                        // a zero catch_type is a catch-all handler, like the ones of finally and synchronized
+                        let catch_type: String = if exception.catch_type == 0 {
+                            "any".to_owned()
+                        } else {
+                            format!("Class {}", cp.get_class_name(exception.catch_type))
+                        };
                         lw.println(&format!(
-                            " {:5} {:5} {:5}   Class {}",
-                            exception.start_pc,
-                            exception.end_pc,
-                            exception.handler_pc,
-                            cp.get_class_name(exception.catch_type)
+                            " {:5} {:5} {:5}   {catch_type}",
+                            exception.start_pc, exception.end_pc, exception.handler_pc,
                         ));
                     }
                     lw.indent(-1);
@@ -1397,14 +1752,11 @@ fn print_method_attributes(
                     } else {
                         &cp.get_utf8_content(param.name_index)
                     };
-                    lw.print(name);
-                    if param.access_flags.to_u16() != 0 {
-                        lw.print(&format!(
-                            "                      {}",
-                            param.access_flags.modifier_repr(),
-                        ));
+                    if param.access_flags.to_u16() == 0 {
+                        lw.println(name);
+                    } else {
+                        lw.println(&format!("{name:<31}{}", param.access_flags.modifier_repr()));
                     }
-                    lw.println("");
                 }
                 lw.indent(-1);
             }
@@ -1416,18 +1768,13 @@ fn print_method_attributes(
                     .println(&format!("// {}", cp.get_utf8_content(*signature_index)));
             }
             AttributeInfo::RuntimeVisibleAnnotations { annotations, .. } => {
-                lw.println("RuntimeVisibleAnnotations:");
-                lw.indent(1);
-                for (i, annotation) in annotations.iter().enumerate() {
-                    let mut annotation_type = cp.get_utf8_content(annotation.type_index);
-                    annotation_type =
-                        annotation_type[1..annotation_type.len() - 1].replace('/', ".");
-                    lw.println(&format!("{}: #{}()", i, annotation.type_index))
-                        .indent(1)
-                        .println(&annotation_type)
-                        .indent(-1);
-                }
-                lw.indent(-1);
+                print_runtime_visible_annotations(lw, cp, annotations)
+            }
+            AttributeInfo::Deprecated { .. } => {
+                lw.println("Deprecated: true");
+            }
+            AttributeInfo::Synthetic { .. } => {
+                lw.println("Synthetic: true");
             }
             AttributeInfo::Exceptions {
                 exception_indices, ..
@@ -1442,11 +1789,176 @@ fn print_method_attributes(
                 }
                 lw.indent(-1);
             }
+            AttributeInfo::Unknown { name_index, info } => {
+                print_unknown_attribute(lw, cp, *name_index, info)
+            }
             _ => unreachable!("Unknown method attribute {}.", attribute.kind()),
         }
     }
 }
 
+fn print_runtime_visible_annotations(
+    lw: &mut LineWriter,
+    cp: &ConstantPool,
+    annotations: &[Annotation],
+) {
+    lw.println("RuntimeVisibleAnnotations:");
+    lw.indent(1);
+    for (i, annotation) in annotations.iter().enumerate() {
+        lw.println(&format!("{}: {}", i, get_annotation_raw_string(annotation)))
+            .indent(1);
+        print_annotation(lw, cp, annotation);
+        lw.println("").indent(-1);
+    }
+    lw.indent(-1);
+}
+
+/**
+ * Returns the unresolved form of the given annotation, made only of constant pool indices.
+ */
+fn get_annotation_raw_string(annotation: &Annotation) -> String {
+    format!(
+        "#{}({})",
+        annotation.type_index,
+        annotation
+            .element_value_pairs
+            .iter()
+            .map(|pair| format!(
+                "#{}={}",
+                pair.element_name_index,
+                get_element_value_raw_string(&pair.value)
+            ))
+            .collect::<Vec<String>>()
+            .join(",")
+    )
+}
+
+fn get_element_value_raw_string(value: &ElementValue) -> String {
+    match value {
+        ElementValue::Byte { const_value_index }
+        | ElementValue::Char { const_value_index }
+        | ElementValue::Double { const_value_index }
+        | ElementValue::Float { const_value_index }
+        | ElementValue::Int { const_value_index }
+        | ElementValue::Long { const_value_index }
+        | ElementValue::Short { const_value_index }
+        | ElementValue::Boolean { const_value_index }
+        | ElementValue::String { const_value_index } => {
+            format!("{}#{}", value.tag(), const_value_index)
+        }
+        ElementValue::Enum {
+            type_name_index,
+            const_name_index,
+        } => format!("e#{type_name_index}.#{const_name_index}"),
+        ElementValue::Class { class_info_index } => format!("c#{class_info_index}"),
+        ElementValue::Annotation { value } => format!("@{}", get_annotation_raw_string(value)),
+        ElementValue::Array { values } => format!(
+            "[{}]",
+            values
+                .iter()
+                .map(get_element_value_raw_string)
+                .collect::<Vec<String>>()
+                .join(",")
+        ),
+    }
+}
+
+/**
+ * Prints the given annotation with its type and every element value resolved. The last line is left open.
+ */
+fn print_annotation(lw: &mut LineWriter, cp: &ConstantPool, annotation: &Annotation) {
+    let annotation_type: String = cp.get_utf8_content(annotation.type_index);
+    lw.print(&annotation_type[1..annotation_type.len() - 1].replace('/', "."));
+    if annotation.element_value_pairs.is_empty() {
+        return;
+    }
+    lw.println("(").indent(1);
+    for pair in annotation.element_value_pairs.iter() {
+        lw.print(&format!(
+            "{}=",
+            cp.get_utf8_content(pair.element_name_index)
+        ));
+        print_element_value(lw, cp, &pair.value);
+        lw.println("");
+    }
+    lw.indent(-1).print(")");
+}
+
+fn print_element_value(lw: &mut LineWriter, cp: &ConstantPool, value: &ElementValue) {
+    match value {
+        ElementValue::Annotation { value } => {
+            lw.print("@");
+            print_annotation(lw, cp, value);
+        }
+        ElementValue::Array { values } => {
+            lw.print("[");
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    lw.print(",");
+                }
+                print_element_value(lw, cp, value);
+            }
+            lw.print("]");
+        }
+        _ => {
+            lw.print(&get_element_value_string(cp, value));
+        }
+    }
+}
+
+/**
+ * Returns the resolved form of a constant, enum or class element value, formatted according to its tag.
+ */
+fn get_element_value_string(cp: &ConstantPool, value: &ElementValue) -> String {
+    match value {
+        ElementValue::Byte { const_value_index } => {
+            format!("(byte) {}", cp.get_integer(*const_value_index))
+        }
+        ElementValue::Char { const_value_index } => {
+            let value: u32 = cp.get_integer(*const_value_index) as u32;
+            match char::from_u32(value) {
+                Some(c) => format!("'{c}'"),
+                // a lone surrogate is a valid char constant but not a valid Rust char
+                None => format!("'\\u{value:04x}'"),
+            }
+        }
+        ElementValue::Short { const_value_index } => {
+            format!("(short) {}", cp.get_integer(*const_value_index))
+        }
+        ElementValue::Int { const_value_index } => cp.get_integer(*const_value_index).to_string(),
+        ElementValue::Boolean { const_value_index } => {
+            (cp.get_integer(*const_value_index) != 0).to_string()
+        }
+        ElementValue::Long { const_value_index } => {
+            format!("{}l", cp.get_long(*const_value_index))
+        }
+        ElementValue::Float { const_value_index } => {
+            format!("{}f", java_format_float(cp.get_float(*const_value_index)))
+        }
+        ElementValue::Double { const_value_index } => {
+            format!("{}d", java_format_double(cp.get_double(*const_value_index)))
+        }
+        ElementValue::String { const_value_index } => format!(
+            "\"{}\"",
+            constant_pool::escape_for_display(&cp.get_utf8_content(*const_value_index))
+        ),
+        ElementValue::Enum {
+            type_name_index,
+            const_name_index,
+        } => format!(
+            "{}.{}",
+            cp.get_utf8_content(*type_name_index),
+            cp.get_utf8_content(*const_name_index)
+        ),
+        ElementValue::Class { class_info_index } => {
+            format!("class {}", cp.get_utf8_content(*class_info_index))
+        }
+        ElementValue::Annotation { .. } | ElementValue::Array { .. } => {
+            unreachable!("Nested element values are printed by print_element_value.")
+        }
+    }
+}
+
 fn print_code_attributes(lw: &mut LineWriter, cp: &ConstantPool, attributes: &[AttributeInfo]) {
     for attribute in attributes.iter() {
         match attribute {
@@ -1493,7 +2005,7 @@ fn print_code_attributes(lw: &mut LineWriter, cp: &ConstantPool, attributes: &[A
                         entry.length,
                         entry.index,
                         cp.get_utf8_content(entry.name_index),
-                        cp.get_utf8_content(entry.descriptor_index)
+                        cp.get_utf8_content(entry.signature_index)
                     ));
                 }
                 lw.indent(-1);
@@ -1618,11 +2130,194 @@ fn print_code_attributes(lw: &mut LineWriter, cp: &ConstantPool, attributes: &[A
                 }
                 lw.indent(-1);
             }
+            AttributeInfo::Unknown { name_index, info } => {
+                print_unknown_attribute(lw, cp, *name_index, info)
+            }
             _ => unreachable!(),
         }
     }
 }
 
+/// Returns the name of the given Module or Package entry, quoted like javap does for the ones with a dot.
+fn get_module_or_package_name(cp: &ConstantPool, cp_index: u16) -> String {
+    match &cp[cp_index - 1] {
+        ConstantPoolInfo::Module { name_index } | ConstantPoolInfo::Package { name_index } => {
+            cp.get_wrapped_utf8_content(*name_index)
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// Prints the index of a version string, followed by its content, unless it is zero.
+fn print_module_version(lw: &mut LineWriter, cp: &ConstantPool, version_index: u16) {
+    lw.print(&format!("#{version_index}"));
+    if version_index != 0 {
+        lw.tab()
+            .print("// ")
+            .print(&cp.get_utf8_content(version_index));
+    }
+    lw.println("");
+}
+
+/// Prints an entry of the `exports` or `opens` table of a Module attribute.
+fn print_module_package(
+    lw: &mut LineWriter,
+    cp: &ConstantPool,
+    package_index: u16,
+    flags: ModuleExportsAccessFlags,
+    to_indices: &[u16],
+) {
+    lw.print(&format!("#{package_index},{:x}", flags.to_u16()))
+        .tab()
+        .print("// ")
+        .print(&get_module_or_package_name(cp, package_index));
+    for (flag, repr) in [
+        (ModuleExportsAccessFlag::Mandated, " ACC_MANDATED"),
+        (ModuleExportsAccessFlag::Synthetic, " ACC_SYNTHETIC"),
+    ] {
+        if flags.contains(flag) {
+            lw.print(repr);
+        }
+    }
+    if to_indices.is_empty() {
+        lw.println("");
+        return;
+    }
+    lw.println(&format!(" to ... {}", to_indices.len()));
+    lw.indent(1);
+    for module_index in to_indices {
+        lw.print(&format!("#{module_index}"))
+            .tab()
+            .print("// ... to ")
+            .println(&get_module_or_package_name(cp, *module_index));
+    }
+    lw.indent(-1);
+}
+
+fn print_module(lw: &mut LineWriter, cp: &ConstantPool, attribute: &AttributeInfo) {
+    let AttributeInfo::Module {
+        module_name_index,
+        module_flags,
+        module_version_index,
+        requires,
+        exports,
+        opens,
+        uses,
+        provides,
+        ..
+    } = attribute
+    else {
+        unreachable!()
+    };
+
+    lw.println("Module:");
+    lw.indent(1);
+    lw.print(&format!("#{module_name_index},{:x}", module_flags.to_u16()))
+        .tab()
+        .print("// ")
+        .print(&get_module_or_package_name(cp, *module_name_index));
+    for (flag, repr) in [
+        (ModuleAccessFlag::Open, " ACC_OPEN"),
+        (ModuleAccessFlag::Mandated, " ACC_MANDATED"),
+        (ModuleAccessFlag::Synthetic, " ACC_SYNTHETIC"),
+    ] {
+        if module_flags.contains(flag) {
+            lw.print(repr);
+        }
+    }
+    lw.println("");
+    print_module_version(lw, cp, *module_version_index);
+
+    lw.print(&requires.len().to_string())
+        .tab()
+        .println("// requires");
+    lw.indent(1);
+    for entry in requires {
+        lw.print(&format!(
+            "#{},{:x}",
+            entry.requires_index,
+            entry.requires_flags.to_u16()
+        ))
+        .tab()
+        .print("// ")
+        .print(&get_module_or_package_name(cp, entry.requires_index));
+        for (flag, repr) in [
+            (ModuleRequiresAccessFlag::Transitive, " ACC_TRANSITIVE"),
+            (ModuleRequiresAccessFlag::StaticPhase, " ACC_STATIC_PHASE"),
+            (ModuleRequiresAccessFlag::Synthetic, " ACC_SYNTHETIC"),
+            (ModuleRequiresAccessFlag::Mandated, " ACC_MANDATED"),
+        ] {
+            if entry.requires_flags.contains(flag) {
+                lw.print(repr);
+            }
+        }
+        lw.println("");
+        print_module_version(lw, cp, entry.requires_version_index);
+    }
+    lw.indent(-1);
+
+    lw.print(&exports.len().to_string())
+        .tab()
+        .println("// exports");
+    lw.indent(1);
+    for entry in exports {
+        print_module_package(
+            lw,
+            cp,
+            entry.exports_index,
+            entry.exports_flags,
+            &entry.exports_to_index,
+        );
+    }
+    lw.indent(-1);
+
+    lw.print(&opens.len().to_string()).tab().println("// opens");
+    lw.indent(1);
+    for entry in opens {
+        print_module_package(
+            lw,
+            cp,
+            entry.opens_index,
+            entry.opens_flags,
+            &entry.opens_to_index,
+        );
+    }
+    lw.indent(-1);
+
+    lw.print(&uses.len().to_string()).tab().println("// uses");
+    lw.indent(1);
+    for class_index in uses {
+        lw.print(&format!("#{class_index}"))
+            .tab()
+            .print("// ")
+            .println(&cp.get_class_name(*class_index));
+    }
+    lw.indent(-1);
+
+    lw.print(&provides.len().to_string())
+        .tab()
+        .println("// provides");
+    lw.indent(1);
+    for entry in provides {
+        lw.print(&format!("#{}", entry.provides_index))
+            .tab()
+            .print("// ")
+            .print(&cp.get_class_name(entry.provides_index))
+            .println(&format!(" with ... {}", entry.provides_with_index.len()));
+        lw.indent(1);
+        for class_index in entry.provides_with_index.iter() {
+            lw.print(&format!("#{class_index}"))
+                .tab()
+                .print("// ... with ")
+                .println(&cp.get_class_name(*class_index));
+        }
+        lw.indent(-1);
+    }
+    lw.indent(-1);
+
+    lw.indent(-1);
+}
+
 fn print_class_attributes(lw: &mut LineWriter, cp: &ConstantPool, attributes: &[AttributeInfo]) {
     for attribute in attributes.iter() {
         match attribute {
@@ -1639,35 +2334,25 @@ fn print_class_attributes(lw: &mut LineWriter, cp: &ConstantPool, attributes: &[
                 lw.indent(1);
                 for class in classes.iter() {
                     let modifiers = class.inner_class_access_flags.modifier_repr();
-
-                    if class.is_anonymous() || class.is_local() {
-                        if class.inner_class_access_flags.to_u16() != 0 {
-                            lw.print(&format!("{modifiers} "));
-                        }
-                        lw.print(&format!("#{};", class.inner_class_info_index))
-                            .tab()
-                            .println(&format!(
-                                "// class {}",
-                                cp.get_class_name(class.inner_class_info_index)
-                            ));
-                    } else if class.is_member() {
-                        lw.print(&format!(
-                            "{} #{}= #{} of #{};",
-                            modifiers,
-                            class.inner_name_index,
-                            class.inner_class_info_index,
-                            class.outer_class_info_index
-                        ))
-                        .tab()
-                        .println(&format!(
-                            "// {}=class {} of class {}",
-                            cp.get_utf8_content(class.inner_name_index),
-                            cp.get_class_name(class.inner_class_info_index),
-                            cp.get_class_name(class.outer_class_info_index),
-                        ));
-                    } else {
-                        unreachable!();
+                    if !modifiers.is_empty() {
+                        lw.print(&format!("{modifiers} "));
+                    }
+                    let mut comment: String = "// ".to_owned();
+                    if class.inner_name_index != 0 {
+                        lw.print(&format!("#{}= ", class.inner_name_index));
+                        comment += &format!("{}=", cp.get_utf8_content(class.inner_name_index));
+                    }
+                    lw.print(&format!("#{}", class.inner_class_info_index));
+                    comment +=
+                        &format!("class {}", cp.get_class_name(class.inner_class_info_index));
+                    if class.outer_class_info_index != 0 {
+                        lw.print(&format!(" of #{}", class.outer_class_info_index));
+                        comment += &format!(
+                            " of class {}",
+                            cp.get_class_name(class.outer_class_info_index)
+                        );
                     }
+                    lw.print(";").tab().println(&comment);
                 }
                 lw.indent(-1);
             }
@@ -1693,11 +2378,14 @@ fn print_class_attributes(lw: &mut LineWriter, cp: &ConstantPool, attributes: &[
                     }
                     lw.indent(1);
                     lw.println("Method arguments:");
+                    lw.indent(1);
                     for arg in method.bootstrap_arguments.iter() {
-                        lw.print(&format!("  #{arg} "));
+                        lw.print(&format!("#{arg} "));
                         match cp[arg - 1] {
                             ConstantPoolInfo::String { string_index } => {
-                                lw.println(&cp.get_utf8_content(string_index));
+                                lw.println(&constant_pool::escape_for_display(
+                                    &cp.get_utf8_content(string_index),
+                                ));
                             }
                             ConstantPoolInfo::Class { name_index } => {
                                 lw.println(&cp.get_utf8_content(name_index));
@@ -1718,7 +2406,7 @@ fn print_class_attributes(lw: &mut LineWriter, cp: &ConstantPool, attributes: &[
                             _ => unreachable!(),
                         }
                     }
-                    lw.indent(-1);
+                    lw.indent(-2);
                 }
                 lw.indent(-1);
             }
@@ -1729,10 +2417,15 @@ fn print_class_attributes(lw: &mut LineWriter, cp: &ConstantPool, attributes: &[
                     let descriptor = cp.get_utf8_content(component.descriptor_index);
                     lw.println(&format!(
                         "{} {};",
-                        decode_type(&descriptor),
+                        parse_field_descriptor(&descriptor).map_or_else(
+                            |_| descriptor.clone(),
+                            |parsed| parsed.field_type.to_string()
+                        ),
                         cp.get_utf8_content(component.name_index)
                     ));
-                    lw.println(&format!("  descriptor: {descriptor}"));
+                    lw.indent(1);
+                    lw.println(&format!("descriptor: {descriptor}"));
+                    lw.indent(-1);
                     lw.println("");
                 }
                 lw.indent(-1);
@@ -1746,9 +2439,11 @@ fn print_class_attributes(lw: &mut LineWriter, cp: &ConstantPool, attributes: &[
             }
             AttributeInfo::NestMembers { classes, .. } => {
                 lw.println("NestMembers:");
+                lw.indent(1);
                 for class_index in classes {
-                    lw.println(&format!("  {}", cp.get_class_name(*class_index)));
+                    lw.println(&cp.get_class_name(*class_index));
                 }
+                lw.indent(-1);
             }
             AttributeInfo::EnclosingMethod {
                 class_index,
@@ -1769,6 +2464,46 @@ fn print_class_attributes(lw: &mut LineWriter, cp: &ConstantPool, attributes: &[
                     cp.get_class_name(*host_class_index)
                 ));
             }
+            AttributeInfo::PermittedSubclasses { classes, .. } => {
+                lw.println("PermittedSubclasses:");
+                lw.indent(1);
+                for class_index in classes {
+                    lw.println(&cp.get_class_name(*class_index));
+                }
+                lw.indent(-1);
+            }
+            AttributeInfo::Module { .. } => print_module(lw, cp, attribute),
+            AttributeInfo::ModulePackages { packages, .. } => {
+                lw.println("ModulePackages:");
+                lw.indent(1);
+                for package_index in packages {
+                    lw.print(&format!("#{package_index}"))
+                        .tab()
+                        .print("// ")
+                        .println(&cp.get_package_name(*package_index).replace('/', "."));
+                }
+                lw.indent(-1);
+            }
+            AttributeInfo::ModuleMainClass {
+                main_class_index, ..
+            } => {
+                lw.print(&format!("ModuleMainClass: #{main_class_index}"))
+                    .tab()
+                    .print("// ")
+                    .println(&cp.get_class_name(*main_class_index).replace('/', "."));
+            }
+            AttributeInfo::RuntimeVisibleAnnotations { annotations, .. } => {
+                print_runtime_visible_annotations(lw, cp, annotations)
+            }
+            AttributeInfo::Deprecated { .. } => {
+                lw.println("Deprecated: true");
+            }
+            AttributeInfo::Synthetic { .. } => {
+                lw.println("Synthetic: true");
+            }
+            AttributeInfo::Unknown { name_index, info } => {
+                print_unknown_attribute(lw, cp, *name_index, info)
+            }
             _ => unreachable!(),
         }
     }
@@ -1777,7 +2512,7 @@ fn print_class_attributes(lw: &mut LineWriter, cp: &ConstantPool, attributes: &[
 /**
  * Formats the given f64 as Java's default format.
  */
-fn java_format_double(val: f64) -> String {
+pub(crate) fn java_format_double(val: f64) -> String {
     if val.is_nan() {
         "NaN".to_owned()
     } else if val.is_infinite() {
@@ -1833,7 +2568,7 @@ fn format_scientific_f64(val: f64) -> String {
 /**
  * Formats the given f32 as Java's default format.
  */
-fn java_format_float(val: f32) -> String {
+pub(crate) fn java_format_float(val: f32) -> String {
     if val.is_nan() {
         "NaN".to_owned()
     } else if val.is_infinite() {
@@ -1890,6 +2625,10 @@ fn format_scientific_f32(val: f32) -> String {
 
 #[cfg(test)]
 mod tests {
+    use binary_reader::{BinaryReader, Endianness};
+    use classfile::access_flags::ModuleAccessFlags;
+    use classfile::bytecode::ArrayType;
+
     use super::*;
 
     #[test]
@@ -1962,4 +2701,1043 @@ mod tests {
             assert_eq!(expected, actual);
         }
     }
+
+    #[test]
+    fn file_details_describe_the_parsed_bytes() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Employee.class");
+        let mut lw: LineWriter = LineWriter::capturing(2);
+        write_class_file(
+            &mut lw,
+            concat!(env!("CARGO_MANIFEST_DIR"), "/../e2e/data/Employee.class").to_owned(),
+            &FormatOptions::default(),
+        );
+        let output: String = lw.captured();
+        let digest: String = sha::sha256(bytes)
+            .iter()
+            .map(|x| format!("{x:02x}"))
+            .collect();
+        assert!(output.contains(&format!("; size {} bytes\n", bytes.len())));
+        assert!(output.contains(&format!("  SHA-256 checksum {digest}\n")));
+        assert!(output.contains("  Compiled from \"Employee.java\"\n"));
+    }
+
+    #[test]
+    fn invokespecial_of_default_method() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/DefaultMethods.class");
+        let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        let comments: Vec<String> = cf
+            .methods
+            .iter()
+            .filter_map(
+                |m| match find_attribute(&m.attributes, AttributeKind::Code) {
+                    Some(AttributeInfo::Code { code, .. }) => Some(code),
+                    _ => None,
+                },
+            )
+            .flatten()
+            .filter(|(_, i)| matches!(i, BytecodeInstruction::InvokeSpecial { .. }))
+            .map(|(_, i)| get_comment(&cf.constant_pool, cf.this_class, i).unwrap())
+            .collect();
+        assert_eq!(
+            comments,
+            vec![
+                "Method java/lang/Object.\"<init>\":()V",
+                "InterfaceMethod Greeter.greet:()Ljava/lang/String;"
+            ]
+        );
+    }
+
+    #[test]
+    fn instance_field_access() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Employee.class");
+        let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        let Some(AttributeInfo::Code { code, .. }) =
+            find_attribute(&cf.methods[0].attributes, AttributeKind::Code)
+        else {
+            panic!("Expected a Code attribute.");
+        };
+        let (_, instruction) = code
+            .iter()
+            .find(|(_, i)| matches!(i, BytecodeInstruction::PutField { .. }))
+            .unwrap();
+        assert_eq!(
+            get_opcode_and_arguments_string(&0, instruction),
+            "putfield      #7"
+        );
+        assert_eq!(
+            get_comment(&cf.constant_pool, cf.this_class, instruction).unwrap(),
+            "Field id:I"
+        );
+        // fields of other classes keep the name of their class
+        assert_eq!(
+            get_comment(&cf.constant_pool, 0, instruction).unwrap(),
+            "Field Employee.id:I"
+        );
+
+        let getfield = BytecodeInstruction::GetField { field_ref_index: 7 };
+        assert_eq!(
+            get_opcode_and_arguments_string(&0, &getfield),
+            "getfield      #7"
+        );
+        assert_eq!(
+            get_comment(&cf.constant_pool, cf.this_class, &getfield).unwrap(),
+            "Field id:I"
+        );
+    }
+
+    #[test]
+    fn type_checks() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Sealed.class");
+        let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        let (_, instruction) = cf
+            .methods
+            .iter()
+            .filter_map(
+                |m| match find_attribute(&m.attributes, AttributeKind::Code) {
+                    Some(AttributeInfo::Code { code, .. }) => Some(code),
+                    _ => None,
+                },
+            )
+            .flatten()
+            .find(|(_, i)| matches!(i, BytecodeInstruction::Instanceof { .. }))
+            .unwrap();
+        assert_eq!(
+            get_opcode_and_arguments_string(&0, instruction),
+            "instanceof    #7"
+        );
+        assert_eq!(
+            get_comment(&cf.constant_pool, cf.this_class, instruction).unwrap(),
+            "class Sealed$Circle"
+        );
+    }
+
+    #[test]
+    fn recompute_too_small_max_stack() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/HelloWorld.class");
+        let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        let main: &MethodInfo = cf.methods_named("main").next().unwrap();
+        let Some(AttributeInfo::Code {
+            max_stack,
+            max_locals,
+            code,
+            exception_table,
+            ..
+        }) = find_attribute(&main.attributes, AttributeKind::Code)
+        else {
+            unreachable!()
+        };
+        let cp: &ConstantPool = &cf.constant_pool;
+        assert_eq!(
+            get_recomputed_sizes_string(cp, main, code, exception_table, *max_stack, *max_locals),
+            format!("// computed: stack={max_stack}, locals={max_locals}")
+        );
+        assert_eq!(
+            get_recomputed_sizes_string(cp, main, code, exception_table, 1, *max_locals),
+            format!("// computed: stack={max_stack} !, locals={max_locals}")
+        );
+    }
+
+    #[test]
+    fn annotation_element_values() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Annotated.class");
+        let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        let cp: &ConstantPool = &cf.constant_pool;
+        let method: &MethodInfo = cf.methods_named("plain").next().unwrap();
+        let Some(AttributeInfo::RuntimeVisibleAnnotations { annotations, .. }) =
+            find_attribute(&method.attributes, AttributeKind::RuntimeVisibleAnnotations)
+        else {
+            unreachable!()
+        };
+        let pairs: Vec<(String, String)> = annotations[0]
+            .element_value_pairs
+            .iter()
+            .map(|pair| {
+                (
+                    cp.get_utf8_content(pair.element_name_index),
+                    get_element_value_string(cp, &pair.value),
+                )
+            })
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("count".to_owned(), "7".to_owned()),
+                ("name".to_owned(), "\"plain\"".to_owned())
+            ]
+        );
+        assert_eq!(
+            get_annotation_raw_string(&annotations[0]),
+            "#16(#17=I#50,#19=s#49)"
+        );
+    }
+
+    #[test]
+    fn escaped_element_values() {
+        // #1 = Integer 0xd800, #2 = Utf8 "say "hi""
+        let mut bytes: Vec<u8> = vec![0x03, 0x00, 0x00, 0xd8, 0x00, 0x01, 0x00, 0x08];
+        bytes.extend(b"say \"hi\"");
+        let cp: ConstantPool =
+            constant_pool::parse_constant_pool(&mut BinaryReader::new(&bytes, Endianness::Big), 2)
+                .unwrap();
+        assert_eq!(
+            get_element_value_string(
+                &cp,
+                &ElementValue::Char {
+                    const_value_index: 1
+                }
+            ),
+            "'\\ud800'"
+        );
+        assert_eq!(
+            get_element_value_string(
+                &cp,
+                &ElementValue::String {
+                    const_value_index: 2
+                }
+            ),
+            "\"say \\\"hi\\\"\""
+        );
+    }
+
+    #[test]
+    fn narrowing_to_byte() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Conversions.class");
+        let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        let to_byte: &MethodInfo = cf.methods_named("toByte").next().unwrap();
+        let Some(AttributeInfo::Code { code, .. }) =
+            find_attribute(&to_byte.attributes, AttributeKind::Code)
+        else {
+            unreachable!()
+        };
+        let lines: Vec<(String, Option<String>)> = code
+            .iter()
+            .map(|(position, instruction)| {
+                (
+                    get_opcode_and_arguments_string(position, instruction),
+                    get_comment(&cf.constant_pool, cf.this_class, instruction),
+                )
+            })
+            .collect();
+        assert_eq!(lines[1], ("i2b".to_owned(), None));
+        assert_eq!(get_explanation(&code[1].1), Some("narrow int to byte"));
+        assert_eq!(get_explanation(&code[0].1), None);
+    }
+
+    #[test]
+    fn local_variable_type_table() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Parameters.class");
+        let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        let method: &MethodInfo = cf.methods_named("generic").next().unwrap();
+        let Some(AttributeInfo::Code { attributes, .. }) =
+            find_attribute(&method.attributes, AttributeKind::Code)
+        else {
+            unreachable!()
+        };
+        let Some(AttributeInfo::LocalVariableTypeTable {
+            local_variable_type_table,
+            ..
+        }) = find_attribute(attributes, AttributeKind::LocalVariableTypeTable)
+        else {
+            unreachable!()
+        };
+        assert_eq!(
+            cf.constant_pool
+                .get_utf8_content(local_variable_type_table[0].signature_index),
+            "Ljava/util/Map<Ljava/lang/String;Ljava/util/List<TT;>;>;"
+        );
+
+        let mut lw: LineWriter = LineWriter::capturing(2);
+        print_code_attributes(
+            &mut lw,
+            &cf.constant_pool,
+            &[
+                find_attribute(attributes, AttributeKind::LocalVariableTypeTable)
+                    .unwrap()
+                    .clone(),
+            ],
+        );
+        assert_eq!(
+            lw.captured(),
+            [
+                "LocalVariableTypeTable:",
+                "  Start  Length  Slot  Name   Signature",
+                "      0       1     0   map   Ljava/util/Map<Ljava/lang/String;Ljava/util/List<TT;>;>;",
+                ""
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn nest_attributes() {
+        let nest_attributes = |bytes: &[u8]| -> String {
+            let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+            let attributes: Vec<AttributeInfo> = cf
+                .attributes
+                .iter()
+                .filter(|attribute| {
+                    matches!(
+                        attribute.kind(),
+                        AttributeKind::NestHost | AttributeKind::NestMembers
+                    )
+                })
+                .cloned()
+                .collect();
+            let mut lw: LineWriter = LineWriter::capturing(2);
+            print_class_attributes(&mut lw, &cf.constant_pool, &attributes);
+            lw.captured()
+        };
+        assert_eq!(
+            nest_attributes(include_bytes!("../../e2e/data/Nesting.class")),
+            [
+                "NestMembers:",
+                "  Nesting$Callback",
+                "  Nesting$Inner",
+                "  Nesting$Inner$Deeper",
+                "  Nesting$1",
+                "  Nesting$1Local",
+                ""
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            nest_attributes(include_bytes!("../../e2e/data/Sealed$Circle.class")),
+            "NestHost: class Sealed\n"
+        );
+    }
+
+    #[test]
+    fn method_parameters_with_flags() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Finals.class");
+        let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        let method: &MethodInfo = cf.methods_named("f").next().unwrap();
+        let parameters_only: MethodInfo = MethodInfo {
+            attributes: vec![
+                find_attribute(&method.attributes, AttributeKind::MethodParameters)
+                    .unwrap()
+                    .clone(),
+            ],
+            ..method.clone()
+        };
+        let mut lw: LineWriter = LineWriter::capturing(2);
+        print_method_attributes(
+            &mut lw,
+            &cf.constant_pool,
+            cf.this_class,
+            &parameters_only,
+            &FormatOptions::default(),
+        );
+        assert_eq!(
+            lw.captured(),
+            [
+                "MethodParameters:",
+                "  Name                           Flags",
+                "  count                          final",
+                "  name",
+                "  values                         final",
+                ""
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn parameter_names() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Parameters.class");
+        let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        let with_names = |name: &str, arguments: &str| -> String {
+            let method: &MethodInfo = cf.methods_named(name).next().unwrap();
+            add_parameter_names(&cf.constant_pool, method, arguments)
+        };
+        assert_eq!(
+            with_names("f", "(int, java.lang.String)"),
+            "(int count, java.lang.String name)"
+        );
+        assert_eq!(
+            with_names(
+                "generic",
+                "(java.util.Map<java.lang.String, java.util.List<T>>, long[], java.lang.String...)"
+            ),
+            "(java.util.Map<java.lang.String, java.util.List<T>> map, long[] values, java.lang.String... rest)"
+        );
+        assert_eq!(with_names("none", "()"), "()");
+    }
+
+    #[test]
+    fn parameter_names_without_attribute() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Employee.class");
+        let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        for method in cf.methods.iter() {
+            assert_eq!(
+                add_parameter_names(&cf.constant_pool, method, "(int)"),
+                "(int)"
+            );
+        }
+    }
+
+    /// Returns the lines printed for the constant pool of the given class.
+    fn constant_pool_lines(cf: &ClassFile) -> Vec<String> {
+        let mut lw: LineWriter = LineWriter::capturing(2);
+        print_constant_pool(&mut lw, &cf.constant_pool);
+        lw.captured().lines().map(str::to_owned).collect()
+    }
+
+    #[test]
+    fn integer_constants() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Literals.class");
+        let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        assert!(
+            constant_pool_lines(&cf).contains(&"  #30 = Integer            -889275714".to_owned())
+        );
+        assert_eq!(get_constant_string(&cf.constant_pool, 30), "int -889275714");
+    }
+
+    #[test]
+    fn float_constants() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Literals.class");
+        let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        assert!(constant_pool_lines(&cf).contains(&"  #37 = Float              1.5f".to_owned()));
+        assert_eq!(get_constant_string(&cf.constant_pool, 37), "float 1.5f");
+    }
+
+    #[test]
+    fn double_constants() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Literals.class");
+        let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        let lines: Vec<String> = constant_pool_lines(&cf);
+        let double: usize = lines
+            .iter()
+            .position(|line| line == "  #40 = Double             3.14159d")
+            .unwrap();
+        // the second slot taken by the double is not printed
+        assert!(lines[double + 1].starts_with("  #42 = "));
+        assert_eq!(
+            get_constant_string(&cf.constant_pool, 40),
+            "double 3.14159d"
+        );
+    }
+
+    #[test]
+    fn dynamic_constants() {
+        // generated with ASM, since javac does not emit dynamic constants for plain Java code
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Condy.class");
+        let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        assert!(
+            constant_pool_lines(&cf).contains(
+                &"  #26 = Dynamic            #0:#25         // #0:MAX_VALUE:I".to_owned()
+            )
+        );
+        assert_eq!(
+            get_constant_string(&cf.constant_pool, 38),
+            "Dynamic #1:PI:D"
+        );
+    }
+
+    #[test]
+    fn module_and_package_constants() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/module-info.class");
+        // skip the magic number and the version to read the constant pool alone
+        let mut reader: BinaryReader = BinaryReader::new(&bytes[8..], Endianness::Big);
+        let cp_count: u16 = reader.read_u16().unwrap();
+        let cp: ConstantPool =
+            constant_pool::parse_constant_pool(&mut reader, (cp_count - 1).into()).unwrap();
+
+        let mut lw: LineWriter = LineWriter::capturing(2);
+        print_constant_pool(&mut lw, &cp);
+        let output: String = lw.captured();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines[2],
+            "   #2 = Class              #1             // \"module-info\""
+        );
+        assert_eq!(
+            lines[5],
+            "   #5 = Module             #4             // \"com.example\""
+        );
+        assert_eq!(
+            lines[9],
+            "   #9 = Package            #8             // com/example/api"
+        );
+    }
+
+    #[test]
+    fn method_type_and_handle_constants() {
+        // the ldc of type() and handle() were patched to load the bootstrap arguments of the lambda in main()
+        let bytes: &[u8] = include_bytes!("../../e2e/data/MethodConstants.class");
+        let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        assert_eq!(get_constant_string(&cf.constant_pool, 53), "MethodType ()V");
+        assert_eq!(
+            get_constant_string(&cf.constant_pool, 54),
+            "MethodHandle REF_invokeStatic MethodConstants.lambda$main$0:()V"
+        );
+    }
+
+    #[test]
+    fn constructor_declarations() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Constructors.class");
+        let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        let declarations: Vec<String> = cf
+            .methods
+            .iter()
+            .map(|method| get_method_declaration(&cf.constant_pool, &cf, method, false))
+            .collect();
+        assert_eq!(
+            declarations,
+            [
+                "public Constructors(int, java.lang.String)",
+                "protected <T extends java.lang.Number> Constructors(java.util.List<T>)",
+                "Constructors(long[], java.lang.Object...) throws java.lang.Exception",
+                "static {}"
+            ]
+        );
+    }
+
+    #[test]
+    fn declared_exceptions() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Shape.class");
+        let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        let cp: &ConstantPool = &cf.constant_pool;
+        let method: &MethodInfo = cf.methods_named("sides").next().unwrap();
+        assert_eq!(
+            get_method_declaration(cp, &cf, method, false),
+            "public abstract int sides() throws java.lang.Exception"
+        );
+
+        let mut lw: LineWriter = LineWriter::capturing(2);
+        print_method_attributes(
+            &mut lw,
+            cp,
+            cf.this_class,
+            method,
+            &FormatOptions::default(),
+        );
+        assert_eq!(lw.captured(), "Exceptions:\n  throws java.lang.Exception\n");
+    }
+
+    #[test]
+    fn trailing_bytes_are_printed() {
+        let mut cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/HelloWorld.class")).unwrap();
+        let Some(AttributeInfo::Code {
+            trailing_bytes,
+            attributes,
+            ..
+        }) = cf.methods[1]
+            .attributes
+            .iter_mut()
+            .find(|attribute| attribute.kind() == AttributeKind::Code)
+        else {
+            panic!("Expected main to have code.");
+        };
+        trailing_bytes.extend([0xff, 0x00, 0xfe]);
+        attributes.clear();
+
+        let mut lw: LineWriter = LineWriter::capturing(2);
+        print_method_attributes(
+            &mut lw,
+            &cf.constant_pool,
+            cf.this_class,
+            &cf.methods[1],
+            &FormatOptions::default(),
+        );
+        let captured: String = lw.captured();
+        let lines: Vec<&str> = captured.lines().collect();
+        assert_eq!(
+            lines[lines.len() - 2..],
+            ["     9: // 3 trailing bytes", "        ff 00 fe"]
+        );
+    }
+
+    #[test]
+    fn push_immediates() {
+        let cases = [
+            (
+                BytecodeInstruction::BiPush { immediate: -128 },
+                "bipush        -128",
+            ),
+            (
+                BytecodeInstruction::SiPush { immediate: 128 },
+                "sipush        128",
+            ),
+            (
+                BytecodeInstruction::SiPush { immediate: -32768 },
+                "sipush        -32768",
+            ),
+            (
+                BytecodeInstruction::SiPush { immediate: 32767 },
+                "sipush        32767",
+            ),
+        ];
+        for (instruction, expected) in cases {
+            assert_eq!(get_opcode_and_arguments_string(&0, &instruction), expected);
+        }
+    }
+
+    #[test]
+    fn comparisons() {
+        let cases = [
+            (BytecodeInstruction::LCmp {}, "lcmp"),
+            (BytecodeInstruction::FCmpL {}, "fcmpl"),
+            (BytecodeInstruction::FCmpG {}, "fcmpg"),
+            (BytecodeInstruction::DCmpL {}, "dcmpl"),
+            (BytecodeInstruction::DCmpG {}, "dcmpg"),
+        ];
+        for (instruction, expected) in cases {
+            assert_eq!(get_opcode_and_arguments_string(&0, &instruction), expected);
+            assert_eq!(instruction.mnemonic(), expected);
+        }
+    }
+
+    #[test]
+    fn null_checks() {
+        let cases = [
+            (
+                BytecodeInstruction::IfNull { offset: 7 },
+                "ifnull        17",
+            ),
+            (
+                BytecodeInstruction::IfNull { offset: -10 },
+                "ifnull        0",
+            ),
+            (
+                BytecodeInstruction::IfNonNull { offset: 7 },
+                "ifnonnull     17",
+            ),
+        ];
+        for (instruction, expected) in cases {
+            assert_eq!(get_opcode_and_arguments_string(&10, &instruction), expected);
+        }
+    }
+
+    #[test]
+    fn wide_branches() {
+        let cases = [
+            (
+                BytecodeInstruction::GoToW { offset: 70000 },
+                "goto_w        70100",
+            ),
+            (
+                BytecodeInstruction::GoToW { offset: -100 },
+                "goto_w        0",
+            ),
+            (
+                BytecodeInstruction::JsrW { offset: -40 },
+                "jsr_w         60",
+            ),
+        ];
+        for (instruction, expected) in cases {
+            assert_eq!(
+                get_opcode_and_arguments_string(&100, &instruction),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn nop() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/HelloWorld.class");
+        let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        let instruction = BytecodeInstruction::Nop {};
+        assert_eq!(get_opcode_and_arguments_string(&0, &instruction), "nop");
+        assert!(get_comment(&cf.constant_pool, cf.this_class, &instruction).is_none());
+    }
+
+    #[test]
+    fn unknown_field_attribute() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Employee.class");
+        let mut cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        let name_index: u16 = cf.fields[0].name_index;
+        cf.fields[0].attributes = vec![AttributeInfo::Unknown {
+            name_index,
+            info: (0..18).collect(),
+        }];
+        let mut lw: LineWriter = LineWriter::capturing(2);
+        print_field_attributes(&mut lw, &cf.constant_pool, &cf.fields[0]);
+        assert_eq!(
+            lw.captured(),
+            [
+                "id: length = 0x12 (unknown attribute)",
+                " 00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f",
+                " 10 11",
+                ""
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn unknown_opcode() {
+        let instruction = BytecodeInstruction::Unknown { opcode: 0xca };
+        assert_eq!(
+            get_opcode_and_arguments_string(&0, &instruction),
+            "// unknown opcode 0xca"
+        );
+    }
+
+    #[test]
+    fn long_arithmetic() {
+        let cases = [
+            (BytecodeInstruction::LSub {}, "lsub"),
+            (BytecodeInstruction::LRem {}, "lrem"),
+            (BytecodeInstruction::LNeg {}, "lneg"),
+            (BytecodeInstruction::LShl {}, "lshl"),
+            (BytecodeInstruction::LShr {}, "lshr"),
+            (BytecodeInstruction::LUshr {}, "lushr"),
+        ];
+        for (instruction, expected) in cases {
+            assert_eq!(get_opcode_and_arguments_string(&0, &instruction), expected);
+            assert_eq!(instruction.mnemonic(), expected);
+        }
+    }
+
+    #[test]
+    fn floating_point_arithmetic() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/FloatLocals.class");
+        let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        let mix: &MethodInfo = cf.methods_named("mix").next().unwrap();
+        let Some(AttributeInfo::Code { code, .. }) =
+            find_attribute(&mix.attributes, AttributeKind::Code)
+        else {
+            unreachable!()
+        };
+        let lines: Vec<String> = code
+            .iter()
+            .map(|(position, instruction)| {
+                format!(
+                    "{position}: {}",
+                    get_opcode_and_arguments_string(position, instruction)
+                )
+            })
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                "0: fload_0",
+                "1: fload_3",
+                "2: fmul",
+                "3: fstore        4",
+                "5: dload_1",
+                "6: ldc2_w        #7",
+                "9: ddiv",
+                "10: dstore        5",
+                "12: fload         4",
+                "14: ldc           #9",
+                "16: fadd",
+                "17: fstore        7",
+                "19: dload         5",
+                "21: fload         7",
+                "23: f2d",
+                "24: dsub",
+                "25: dstore        8",
+                "27: dload         8",
+                "29: fload         4",
+                "31: f2d",
+                "32: dmul",
+                "33: dreturn"
+            ]
+        );
+    }
+
+    #[test]
+    fn primitive_array_creation() {
+        let instruction = BytecodeInstruction::NewArray {
+            atype: ArrayType::Int,
+        };
+        assert_eq!(
+            get_opcode_and_arguments_string(&0, &instruction),
+            "newarray       int"
+        );
+    }
+
+    #[test]
+    fn terse_profile() {
+        let options: FormatOptions = FormatOptions {
+            indent_width: 4,
+            file_details: false,
+            member_details: false,
+            ..FormatOptions::default()
+        };
+        let mut lw: LineWriter = LineWriter::capturing(options.indent_width);
+        write_class_file(
+            &mut lw,
+            concat!(env!("CARGO_MANIFEST_DIR"), "/../e2e/data/HelloWorld.class").to_owned(),
+            &options,
+        );
+        let output: String = lw.captured();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert!(lines[0].starts_with("Classfile /"));
+        assert_eq!(lines[1], "    Compiled from \"HelloWorld.java\"");
+        assert_eq!(lines[2], "public final class HelloWorld");
+        assert!(
+            !output.contains("SHA-256") && !output.contains("Last modified"),
+            "{output}"
+        );
+        assert!(
+            !output.contains("descriptor:") && !output.contains("flags: (0x0009)"),
+            "{output}"
+        );
+        let main: usize = lines
+            .iter()
+            .position(|line| *line == "    public static void main(java.lang.String[]);")
+            .unwrap();
+        assert_eq!(lines[main + 1], "        Code:");
+        assert_eq!(
+            lines[main + 2],
+            "            stack=2, locals=1, args_size=1"
+        );
+    }
+
+    #[test]
+    fn generic_field_declarations() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/GenericFields.class");
+        let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        let declarations: Vec<String> = cf
+            .fields
+            .iter()
+            .map(|field| get_field_declaration(&cf.constant_pool, field))
+            .collect();
+        assert_eq!(
+            declarations,
+            [
+                "private final java.util.List<java.lang.String> items",
+                "protected java.util.Map<java.lang.String, java.util.List<? extends java.lang.Number>> index",
+                "T value",
+                "java.util.List<T>[] buckets",
+                "int count"
+            ]
+        );
+    }
+
+    #[test]
+    fn too_deep_descriptors_are_printed_as_they_are() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/GenericFields.class");
+        let mut cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        let descriptor: String = "[".repeat(300) + "I";
+        let field_descriptor_index: u16 = cf
+            .constant_pool
+            .add(ConstantPoolInfo::Utf8 {
+                bytes: descriptor.clone().into_bytes(),
+            })
+            .unwrap();
+        let method_descriptor_index: u16 = cf
+            .constant_pool
+            .add(ConstantPoolInfo::Utf8 {
+                bytes: format!("({descriptor})V").into_bytes(),
+            })
+            .unwrap();
+        cf.fields[4].descriptor_index = field_descriptor_index;
+        cf.methods[0].descriptor_index = method_descriptor_index;
+
+        assert_eq!(
+            get_field_declaration(&cf.constant_pool, &cf.fields[4]),
+            format!("{descriptor} count")
+        );
+        assert_eq!(
+            get_number_of_arguments(&cf.constant_pool, &cf.methods[0]),
+            "?"
+        );
+    }
+
+    #[test]
+    fn truncated_code() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/HelloWorld.class");
+        let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        let Some(AttributeInfo::Code { code, .. }) =
+            find_attribute(&cf.methods[1].attributes, AttributeKind::Code)
+        else {
+            panic!("Expected main to have code.");
+        };
+        assert_eq!(code.len(), 4);
+
+        let (shown, marker) = truncate_code(code, Some(3));
+        assert_eq!(
+            shown
+                .iter()
+                .map(|(position, _)| *position)
+                .collect::<Vec<u32>>(),
+            [0, 3, 5]
+        );
+        assert_eq!(marker, Some("      ... (1 more)".to_owned()));
+
+        let (shown, marker) = truncate_code(code, Some(1));
+        assert_eq!(shown.len(), 1);
+        assert_eq!(marker, Some("      ... (3 more)".to_owned()));
+
+        for max_instructions in [Some(4), Some(10), None] {
+            let (shown, marker) = truncate_code(code, max_instructions);
+            assert_eq!(shown.len(), 4);
+            assert_eq!(marker, None);
+        }
+    }
+
+    #[test]
+    fn java_version() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/OldSubroutine.class"))
+                .unwrap();
+        let header = |java_version: bool| -> Vec<String> {
+            let mut lw: LineWriter = LineWriter::capturing(2);
+            let options: FormatOptions = FormatOptions {
+                java_version,
+                ..FormatOptions::default()
+            };
+            print_header(&mut lw, &cf, &options);
+            lw.captured().lines().map(str::to_owned).collect()
+        };
+        assert_eq!(header(false)[2], "  major version: 49");
+        assert_eq!(header(true)[2], "  major version: 49 (Java 5)");
+    }
+
+    #[test]
+    fn declarations_from_descriptors() {
+        let declarations =
+            |bytes: &[u8]| -> Vec<String> {
+                let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+                cf.fields
+                    .iter()
+                    .map(|field| get_field_declaration(&cf.constant_pool, field))
+                    .chain(cf.methods.iter().map(|method| {
+                        get_method_declaration(&cf.constant_pool, &cf, method, false)
+                    }))
+                    .collect()
+            };
+        // the expected declarations are the ones printed by javap
+        assert_eq!(
+            declarations(include_bytes!("../../e2e/data/FloatLocals.class")),
+            [
+                "public FloatLocals()",
+                "static double mix(float, double, float)",
+                "public static void main(java.lang.String[])"
+            ]
+        );
+        assert_eq!(
+            declarations(include_bytes!("../../e2e/data/Finals.class")),
+            [
+                "public Finals()",
+                "public int f(int, java.lang.String, long[])"
+            ]
+        );
+        let arrays: Vec<String> = declarations(include_bytes!("../../e2e/data/Arrays.class"));
+        for expected in [
+            "public static void sort(short[], int, int)",
+            "public static void sort(char[])",
+            "public static int binarySearch(byte[], int, int, byte)",
+            "public static boolean equals(double[], double[])",
+        ] {
+            assert!(
+                arrays.contains(&expected.to_owned()),
+                "Expected '{expected}' to be declared."
+            );
+        }
+    }
+
+    #[test]
+    fn member_modifiers() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Modifiers.class")).unwrap();
+        let cp: &ConstantPool = &cf.constant_pool;
+        // the expected declarations are the ones printed by javap
+        assert_eq!(
+            get_class_declaration(&cf),
+            "public class Modifiers extends java.lang.Object implements java.lang.Comparable<Modifiers>"
+        );
+        let fields: Vec<String> = cf
+            .fields
+            .iter()
+            .map(|field| get_field_declaration(cp, field))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                "private volatile int state",
+                "protected transient java.lang.String cache"
+            ]
+        );
+        let methods: Vec<String> = cf
+            .methods
+            .iter()
+            .map(|method| get_method_declaration(cp, &cf, method, false))
+            .collect();
+        assert_eq!(
+            methods,
+            [
+                "public Modifiers()",
+                "public static synchronized native void load(int)",
+                "native long handle()",
+                "public synchronized void update()",
+                "public static int sum(int...)",
+                "public int compareTo(Modifiers)",
+                "public int compareTo(java.lang.Object)"
+            ]
+        );
+        assert_eq!(
+            cf.methods[6].access_flags.java_repr(),
+            "ACC_PUBLIC, ACC_BRIDGE, ACC_SYNTHETIC"
+        );
+    }
+
+    #[test]
+    fn module_attributes() {
+        let mut cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/module-info.class"))
+                .unwrap();
+        assert_eq!(get_class_declaration(&cf), "module com.example");
+
+        let mut lw: LineWriter = LineWriter::capturing(2);
+        print_class_attributes(&mut lw, &cf.constant_pool, &cf.attributes[1..]);
+        assert_eq!(
+            lw.captured(),
+            [
+                "Module:",
+                "  #5,0                                    // \"com.example\"",
+                "  #0",
+                "  3                                       // requires",
+                "    #15,8000                                // \"java.base\" ACC_MANDATED",
+                "    #16                                     // 17.0.15",
+                "    #18,20                                  // \"java.logging\" ACC_TRANSITIVE",
+                "    #16                                     // 17.0.15",
+                "    #20,40                                  // \"java.sql\" ACC_STATIC_PHASE",
+                "    #16                                     // 17.0.15",
+                "  2                                       // exports",
+                "    #9,0                                    // com/example/api",
+                "    #13,0                                   // com/example/spi to ... 2",
+                "      #18                                     // ... to \"java.logging\"",
+                "      #20                                     // ... to \"java.sql\"",
+                "  1                                       // opens",
+                "    #11,0                                   // com/example/impl",
+                "  1                                       // uses",
+                "    #22                                     // java/lang/Runnable",
+                "  1                                       // provides",
+                "    #22                                     // java/lang/Runnable with ... 1",
+                "      #7                                      // ... with com/example/impl/Task",
+                "ModulePackages:",
+                "  #9                                      // com.example.api",
+                "  #11                                     // com.example.impl",
+                "  #13                                     // com.example.spi",
+                "ModuleMainClass: #7                     // com.example.impl.Task",
+                ""
+            ]
+            .join("\n")
+        );
+
+        // an open module with a version
+        let Some(AttributeInfo::Module {
+            module_flags,
+            module_version_index,
+            ..
+        }) = cf
+            .attributes
+            .iter_mut()
+            .find(|attribute| attribute.kind() == AttributeKind::Module)
+        else {
+            unreachable!()
+        };
+        *module_flags = ModuleAccessFlags::from(ModuleAccessFlag::Open as u16);
+        *module_version_index = 16;
+        assert_eq!(
+            get_class_declaration(&cf),
+            "open module com.example@17.0.15"
+        );
+    }
 }
@@ -1,24 +1,44 @@
 const TAB_COLUMN: isize = 40;
 
-const INDENT_WIDTH: isize = 2;
-
 pub(crate) struct LineWriter {
     buffer: String,
+    indent_width: isize,
     indent_count: isize,
     pending_newline: bool,
     pending_spaces: isize,
+    /// Where the lines go instead of the standard output, if any.
+    captured: Option<String>,
 }
 
 impl LineWriter {
-    pub(crate) fn new() -> Self {
+    /// Creates a writer printing each line to the standard output as soon as it is complete.
+    pub(crate) fn new(indent_width: usize) -> Self {
         Self {
             buffer: String::new(),
+            indent_width: indent_width.try_into().unwrap(),
             indent_count: 0,
             pending_newline: false,
             pending_spaces: 0,
+            captured: None,
+        }
+    }
+
+    /// Creates a writer keeping all the lines, which can be retrieved with `captured`.
+    #[cfg(test)]
+    pub(crate) fn capturing(indent_width: usize) -> Self {
+        Self {
+            captured: Some(String::new()),
+            ..Self::new(indent_width)
         }
     }
 
+    /// Returns the lines written so far by a capturing writer.
+    #[cfg(test)]
+    pub(crate) fn captured(self) -> String {
+        self.captured
+            .expect("Expected the LineWriter to capture its output.")
+    }
+
     pub(crate) fn println(&mut self, s: &str) -> &mut Self {
         self.print(s).print("\n");
         self
@@ -54,19 +74,25 @@ impl LineWriter {
 
     fn do_println(&mut self) {
         self.pending_spaces = 0;
-        println!("{}", self.buffer);
+        match &mut self.captured {
+            Some(captured) => {
+                *captured += &self.buffer;
+                captured.push('\n');
+            }
+            None => println!("{}", self.buffer),
+        }
         self.buffer.clear();
     }
 
     pub(crate) fn tab(&mut self) -> &mut Self {
-        let col = self.indent_count * INDENT_WIDTH + TAB_COLUMN;
+        let col = self.indent_count * self.indent_width + TAB_COLUMN;
         let buf_len = self.buffer.len().try_into().unwrap();
         self.pending_spaces += if col <= buf_len { 1 } else { col - buf_len };
         self
     }
 
     fn do_indent(&mut self) {
-        self.pending_spaces += self.indent_count * INDENT_WIDTH;
+        self.pending_spaces += self.indent_count * self.indent_width;
     }
 
     pub(crate) fn indent(&mut self, delta: isize) -> &mut Self {
@@ -0,0 +1,71 @@
+use classfile::classfile::ClassFile;
+use classfile::constant_pool::{self, ConstantPoolInfo};
+
+use crate::javap::{java_format_double, java_format_float, load_class_file};
+
+pub(crate) fn print_literals(filenames: &[String]) {
+    for filename in filenames {
+        let cf: ClassFile = load_class_file(filename);
+        for literal in literals(&cf) {
+            println!("{literal}");
+        }
+    }
+}
+
+/**
+ * Returns the literal constants in the constant pool of the given class, in the order they appear, formatted as their
+ * type followed by their value. Strings are quoted and escaped, while numbers use the same suffixes as `javap`.
+ */
+pub(crate) fn literals(cf: &ClassFile) -> Vec<String> {
+    let cp = &cf.constant_pool;
+    (1..=cp.len() as u16)
+        .filter_map(|cp_index| match &cp[cp_index - 1] {
+            ConstantPoolInfo::Integer { .. } => Some(format!("int {}", cp.get_integer(cp_index))),
+            ConstantPoolInfo::Long { .. } => Some(format!("long {}l", cp.get_long(cp_index))),
+            ConstantPoolInfo::Float { .. } => Some(format!(
+                "float {}f",
+                java_format_float(cp.get_float(cp_index))
+            )),
+            ConstantPoolInfo::Double { .. } => Some(format!(
+                "double {}d",
+                java_format_double(cp.get_double(cp_index))
+            )),
+            ConstantPoolInfo::String { string_index } => Some(format!(
+                "String \"{}\"",
+                constant_pool::escape_for_display(&cp.get_utf8_content(*string_index))
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use classfile::classfile::parse_class_file_from_bytes;
+
+    use super::*;
+
+    #[test]
+    fn mixed_literals() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Literals.class")).unwrap();
+        assert_eq!(
+            literals(&cf),
+            [
+                "String \"https://example.com/api-88927571412345678901231.53.14159100000\"",
+                "String \"https://example.com/api\"",
+                "int -889275714",
+                "long 1234567890123l",
+                "float 1.5f",
+                "double 3.14159d",
+            ]
+        );
+    }
+
+    #[test]
+    fn class_without_literals() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Shape.class")).unwrap();
+        assert_eq!(literals(&cf), Vec::<String>::new());
+    }
+}
@@ -1,21 +1,117 @@
 #![forbid(unsafe_code)]
 
+mod api;
+mod class_path;
+mod hierarchy;
 mod javap;
 mod line_writer;
+mod literals;
+mod opcode_search;
+mod tree;
+mod yaml;
 
 use std::env;
 use std::io::Result;
 
-use crate::javap::print_class_file;
+use crate::api::print_class_api;
+use crate::class_path::ClassPath;
+use crate::hierarchy::print_hierarchies;
+use crate::javap::{FormatOptions, print_class_file};
+use crate::literals::print_literals;
+use crate::opcode_search::print_methods_with_opcodes;
+use crate::tree::print_inner_class_trees;
+use crate::yaml::print_class_file_yaml;
 
 /**
  * Re-implementation of java command line utility javap. Used just for testing. Original source code available here:
  * <https://github.com/openjdk/jdk/tree/master/src/jdk.jdeps/share/classes/com/sun/tools/javap>.
  */
 fn main() -> Result<()> {
-    let filename = env::args().nth(1).expect("Usage: program <filename>");
+    const USAGE: &str = "Usage: program [--api] [--literals] [--yaml] [--tree] [--recompute] [--explain] [--parameter-names] [--java-version] [--max-instructions <n>] [--indent <n>] [--no-file-details] [--no-member-details] [--methods-with-opcode <mnemonic>]... <filename>...\n       program [--classpath <path>] --hierarchy <class>...";
 
-    print_class_file(filename);
+    let mut filenames: Vec<String> = Vec::new();
+    let mut mnemonics: Vec<String> = Vec::new();
+    let mut hierarchies: Vec<String> = Vec::new();
+    let mut class_path: String = ".".to_owned();
+    let mut api: bool = false;
+    let mut literals: bool = false;
+    let mut yaml: bool = false;
+    let mut tree: bool = false;
+    let mut options: FormatOptions = FormatOptions::default();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--methods-with-opcode" => mnemonics.push(args.next().expect(USAGE)),
+            "--hierarchy" => hierarchies.push(args.next().expect(USAGE)),
+            "--classpath" => class_path = args.next().expect(USAGE),
+            "--api" => api = true,
+            "--literals" => literals = true,
+            "--yaml" => yaml = true,
+            "--tree" => tree = true,
+            "--recompute" => options.recompute = true,
+            "--explain" => options.explain = true,
+            "--parameter-names" => options.parameter_names = true,
+            "--java-version" => options.java_version = true,
+            "--indent" => options.indent_width = args.next().expect(USAGE).parse().expect(USAGE),
+            "--no-file-details" => options.file_details = false,
+            "--no-member-details" => options.member_details = false,
+            "--max-instructions" => {
+                options.max_instructions = Some(args.next().expect(USAGE).parse().expect(USAGE))
+            }
+            _ if arg.starts_with("--") => panic!("Unknown option '{arg}'.\n{USAGE}"),
+            _ => filenames.push(arg),
+        }
+    }
+    assert!(!filenames.is_empty() || !hierarchies.is_empty(), "{USAGE}");
+    let modes: usize = [
+        api,
+        literals,
+        yaml,
+        tree,
+        !mnemonics.is_empty(),
+        !hierarchies.is_empty(),
+    ]
+    .into_iter()
+    .filter(|&mode| mode)
+    .count();
+    assert!(
+        modes <= 1,
+        "Only one of --api, --literals, --yaml, --tree, --methods-with-opcode and --hierarchy can be used.\n{USAGE}"
+    );
+
+    if !hierarchies.is_empty() {
+        print_hierarchies(&ClassPath::parse(&class_path), &hierarchies);
+        return Ok(());
+    }
+
+    if !mnemonics.is_empty() {
+        print_methods_with_opcodes(&filenames, &mnemonics);
+        return Ok(());
+    }
+
+    if api {
+        print_class_api(&filenames);
+        return Ok(());
+    }
+
+    if literals {
+        print_literals(&filenames);
+        return Ok(());
+    }
+
+    if yaml {
+        print_class_file_yaml(&filenames);
+        return Ok(());
+    }
+
+    if tree {
+        print_inner_class_trees(&filenames);
+        return Ok(());
+    }
+
+    for filename in filenames {
+        print_class_file(filename, &options);
+    }
 
     Ok(())
 }
@@ -0,0 +1,80 @@
+use classfile::attributes::AttributeInfo;
+use classfile::classfile::ClassFile;
+
+use crate::javap::load_class_file;
+
+/**
+ * Prints one line for each method, across all the given class files, containing any of the given mnemonics.
+ */
+pub(crate) fn print_methods_with_opcodes(filenames: &[String], mnemonics: &[String]) {
+    for filename in filenames {
+        let cf: ClassFile = load_class_file(filename);
+        for method in find_methods_with_opcodes(&cf, mnemonics) {
+            println!("{method}");
+        }
+    }
+}
+
+/**
+ * Returns the methods of the given class which contain at least one instruction with any of the given mnemonics,
+ * formatted as `ClassName.methodName:descriptor`.
+ */
+pub(crate) fn find_methods_with_opcodes(cf: &ClassFile, mnemonics: &[String]) -> Vec<String> {
+    let class_name: String = cf.constant_pool.get_class_name(cf.this_class);
+    cf.methods
+        .iter()
+        .filter(|method| {
+            method.attributes.iter().any(|attr| match attr {
+                AttributeInfo::Code { code, .. } => code
+                    .iter()
+                    .any(|(_, instruction)| mnemonics.iter().any(|m| m == instruction.mnemonic())),
+                _ => false,
+            })
+        })
+        .map(|method| {
+            format!(
+                "{}.{}:{}",
+                class_name,
+                cf.constant_pool.get_utf8_content(method.name_index),
+                cf.constant_pool.get_utf8_content(method.descriptor_index)
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use classfile::classfile::parse_class_file_from_bytes;
+
+    use super::*;
+
+    #[test]
+    fn methods_containing_new() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Employee.class")).unwrap();
+        assert_eq!(
+            find_methods_with_opcodes(&cf, &["new".to_owned()]),
+            vec!["Employee.main:([Ljava/lang/String;)V"]
+        );
+    }
+
+    #[test]
+    fn multiple_mnemonics() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Calculator.class")).unwrap();
+        assert_eq!(
+            find_methods_with_opcodes(&cf, &["new".to_owned(), "invokespecial".to_owned()]),
+            vec![
+                "Calculator.<init>:()V",
+                "Calculator.main:([Ljava/lang/String;)V"
+            ]
+        );
+    }
+
+    #[test]
+    fn no_matches() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/HelloWorld.class")).unwrap();
+        assert!(find_methods_with_opcodes(&cf, &["monitorenter".to_owned()]).is_empty());
+    }
+}
@@ -0,0 +1,138 @@
+use classfile::attributes::{AttributeInfo, AttributeKind, InnerClassInfo, find_attribute};
+use classfile::classfile::ClassFile;
+use classfile::constant_pool::ConstantPool;
+
+use crate::javap::load_class_file;
+
+pub(crate) fn print_inner_class_trees(filenames: &[String]) {
+    for filename in filenames {
+        let cf: ClassFile = load_class_file(filename);
+        print!("{}", inner_class_tree(&cf));
+    }
+}
+
+/**
+ * A class listed in the InnerClasses attribute, together with the class declaring it.
+ */
+struct Node {
+    name: String,
+    parent: String,
+    label: String,
+}
+
+/**
+ * Renders the nesting of the classes listed in the InnerClasses attribute as an indented tree, one class per line.
+ * Local and anonymous classes have no outer class in the attribute, so they are attached to the class their binary
+ * name is derived from. Anonymous classes, having no name, are shown by their numeric tag.
+ */
+pub(crate) fn inner_class_tree(cf: &ClassFile) -> String {
+    let cp: &ConstantPool = &cf.constant_pool;
+    let nodes: Vec<Node> = match find_attribute(&cf.attributes, AttributeKind::InnerClasses) {
+        Some(AttributeInfo::InnerClasses { classes, .. }) => {
+            classes.iter().map(|info| to_node(cp, info)).collect()
+        }
+        _ => Vec::new(),
+    };
+
+    let mut roots: Vec<&str> = Vec::new();
+    let this_class: String = cp.get_class_name(cf.this_class);
+    if !nodes.iter().any(|node| node.name == this_class) {
+        roots.push(&this_class);
+    }
+    for node in nodes.iter() {
+        let parent: &str = &node.parent;
+        if !roots.contains(&parent) && !nodes.iter().any(|n| n.name == parent) {
+            roots.push(parent);
+        }
+    }
+
+    let mut out: String = String::new();
+    for root in roots {
+        out += root;
+        out += "\n";
+        print_children(&mut out, &nodes, root, 1);
+    }
+    out
+}
+
+fn print_children(out: &mut String, nodes: &[Node], parent: &str, depth: usize) {
+    for node in nodes.iter().filter(|node| node.parent == parent) {
+        *out += &"  ".repeat(depth);
+        *out += &node.label;
+        *out += "\n";
+        print_children(out, nodes, &node.name, depth + 1);
+    }
+}
+
+fn to_node(cp: &ConstantPool, info: &InnerClassInfo) -> Node {
+    let name: String = cp.get_class_name(info.inner_class_info_index);
+    let (declaring_class, suffix) = name.rsplit_once('$').unwrap_or(("", &name));
+
+    let (parent, kind): (String, &str) = if info.is_member() {
+        (cp.get_class_name(info.outer_class_info_index), "")
+    } else if info.is_anonymous() {
+        (declaring_class.to_owned(), " (anonymous)")
+    } else {
+        (declaring_class.to_owned(), " (local)")
+    };
+    let simple_name: String = if info.is_anonymous() {
+        format!("${suffix}")
+    } else {
+        cp.get_utf8_content(info.inner_name_index)
+    };
+    let modifiers: String = info.inner_class_access_flags.modifier_repr();
+
+    Node {
+        label: if modifiers.is_empty() {
+            format!("{simple_name}{kind}")
+        } else {
+            format!("{modifiers} {simple_name}{kind}")
+        },
+        name,
+        parent,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use classfile::classfile::parse_class_file_from_bytes;
+
+    use super::*;
+
+    #[test]
+    fn nested_local_and_anonymous_classes() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Nesting.class")).unwrap();
+        assert_eq!(
+            inner_class_tree(&cf),
+            [
+                "Nesting",
+                "  Local (local)",
+                "  $1 (anonymous)",
+                "  static Callback",
+                "  public static Inner",
+                "    private Deeper",
+                ""
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn tree_from_a_nested_class() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Sealed$Circle.class"))
+                .unwrap();
+        assert_eq!(
+            inner_class_tree(&cf),
+            "Sealed\n  public static final Circle\n"
+        );
+    }
+
+    #[test]
+    fn class_without_inner_classes() {
+        let cf: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Stripped.class")).unwrap();
+        assert_eq!(inner_class_tree(&cf), "Stripped\n");
+    }
+}
@@ -0,0 +1,57 @@
+use classfile::classfile::ClassFile;
+
+use crate::javap::load_class_file;
+
+pub(crate) fn print_class_file_yaml(filenames: &[String]) {
+    for filename in filenames {
+        let cf: ClassFile = load_class_file(filename);
+        print!("{}", class_file_to_yaml(&cf));
+    }
+}
+
+/**
+ * Renders the whole parsed class file as a YAML document, through its serde representation. Constant pool entries and
+ * instructions are shown with their raw indices, and the instructions of each Code attribute are listed as a map from
+ * their offset to the instruction.
+ */
+pub(crate) fn class_file_to_yaml(cf: &ClassFile) -> String {
+    serde_norway::to_string(cf).expect("Could not serialize the class file")
+}
+
+#[cfg(test)]
+mod tests {
+    use classfile::classfile::parse_class_file_from_bytes;
+    use classfile::writer::write_class_file;
+
+    use super::*;
+
+    #[test]
+    fn header_and_bytecode() {
+        let yaml: String = class_file_to_yaml(
+            &parse_class_file_from_bytes(include_bytes!("../../e2e/data/Stripped.class")).unwrap(),
+        );
+        let lines: Vec<&str> = yaml.lines().collect();
+        assert!(lines.contains(&"major_version: 61"));
+        assert!(lines.contains(&"attributes: []"));
+        assert!(lines.contains(&"    bytes: java/lang/Object"));
+        // the constructor calls Object.<init>
+        assert!(lines.contains(&"      0: !ALoad"));
+        assert!(lines.contains(&"      1: !InvokeSpecial"));
+        assert!(lines.contains(&"        method_ref_index: 1"));
+    }
+
+    #[test]
+    fn yaml_roundtrip() {
+        for bytes in [
+            include_bytes!("../../e2e/data/Calculator.class").as_slice(),
+            include_bytes!("../../e2e/data/TimeUnit.class"),
+            include_bytes!("../../e2e/data/Annotated.class"),
+            include_bytes!("../../e2e/data/InvisibleAnnotations.class"),
+        ] {
+            let yaml: String = class_file_to_yaml(&parse_class_file_from_bytes(bytes).unwrap());
+            let cf: ClassFile = serde_norway::from_str(&yaml).unwrap();
+            assert_eq!(write_class_file(&cf), bytes);
+            assert_eq!(class_file_to_yaml(&cf), yaml);
+        }
+    }
+}
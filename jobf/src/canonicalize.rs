@@ -0,0 +1,17 @@
+use classfile::{classfile::ClassFile, obfuscate::canonicalize};
+
+use crate::transformation::ClassFileTransformation;
+
+/**
+ * Rewrites the class file in a form which depends only on its contents, so that equivalent classes are written to the
+ * same bytes.
+ */
+pub(crate) struct Canonicalize {}
+
+impl ClassFileTransformation for Canonicalize {
+    fn transform(&self, cf: &ClassFile) -> ClassFile {
+        let mut result: ClassFile = cf.clone();
+        canonicalize(&mut result);
+        result
+    }
+}
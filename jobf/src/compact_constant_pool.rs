@@ -0,0 +1,14 @@
+use classfile::{classfile::ClassFile, remapping::compact_constant_pool};
+
+use crate::transformation::ClassFileTransformation;
+
+/**
+ * Removes the constant pool entries which are not referenced anymore, like the ones left behind by the other passes.
+ */
+pub(crate) struct CompactConstantPool {}
+
+impl ClassFileTransformation for CompactConstantPool {
+    fn transform(&self, cf: &ClassFile) -> ClassFile {
+        compact_constant_pool(cf).0
+    }
+}
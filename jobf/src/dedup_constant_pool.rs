@@ -0,0 +1,16 @@
+use classfile::{classfile::ClassFile, optimize::dedup_constant_pool};
+
+use crate::transformation::ClassFileTransformation;
+
+/**
+ * Merges structurally-identical constant pool entries into a single one and rewrites all references to it.
+ */
+pub(crate) struct DedupConstantPool {}
+
+impl ClassFileTransformation for DedupConstantPool {
+    fn transform(&self, cf: &ClassFile) -> ClassFile {
+        let mut result: ClassFile = cf.clone();
+        dedup_constant_pool(&mut result);
+        result
+    }
+}
@@ -0,0 +1,497 @@
+use std::collections::{BTreeMap, HashSet};
+
+use classfile::{
+    access_flags::{ClassAccessFlag, MethodAccessFlag, MethodAccessFlags},
+    attributes::AttributeInfo,
+    bytecode::{BytecodeInstruction, get_instruction_length},
+    classfile::ClassFile,
+    code_editing::replace_instructions,
+    constant_pool::{ConstantPool, ConstantPoolInfo},
+    methods::MethodInfo,
+};
+use rand::{Rng, SeedableRng, rngs::ChaCha8Rng};
+
+use crate::transformation::ClassFileTransformation;
+
+/**
+ * Replaces the string constants loaded by the code of the class with encrypted ones, decrypted at run time by a
+ * private static method added to the class. Each string is XORed with a random key of the same length, and both are
+ * stored as a single hexadecimal constant. Decrypted strings are interned, so that literals can still be compared
+ * with `==`.
+ *
+ * The old entries stay in the constant pool, as attributes like ConstantValue may still use them: use
+ * --compact-constant-pool to remove the unused ones. Interfaces and modules are left untouched, as are the methods
+ * with attributes kept as raw bytes in their Code attribute, since those may hold bytecode offsets. Strings which
+ * contain U+0000 or characters outside of the Basic Multilingual Plane are not encrypted either, because their
+ * modified UTF-8 form is not valid standard UTF-8.
+ */
+pub(crate) struct EncryptStrings {
+    seed: u64,
+}
+
+impl EncryptStrings {
+    pub fn new(seed: u64) -> Self {
+        EncryptStrings { seed }
+    }
+}
+
+impl ClassFileTransformation for EncryptStrings {
+    fn transform(&self, cf: &ClassFile) -> ClassFile {
+        if cf.access_flags.contains(ClassAccessFlag::Interface)
+            || cf.access_flags.contains(ClassAccessFlag::Module)
+        {
+            return cf.clone();
+        }
+        encrypt(cf, &mut ChaCha8Rng::seed_from_u64(self.seed)).unwrap_or_else(|| cf.clone())
+    }
+}
+
+/// Returns the encrypted class, or None if there is no string to encrypt or not enough room in the constant pool.
+fn encrypt(cf: &ClassFile, rng: &mut ChaCha8Rng) -> Option<ClassFile> {
+    let cp: &ConstantPool = &cf.constant_pool;
+
+    // the String entries loaded by the code, with their content
+    let mut plaintexts: BTreeMap<u16, String> = BTreeMap::new();
+    for code in cf.methods.iter().filter_map(editable_code) {
+        for (_, instruction) in code {
+            if let Some(string_index) = loaded_constant(instruction)
+                && let ConstantPoolInfo::String { string_index: idx } = cp[string_index - 1]
+                && let ConstantPoolInfo::Utf8 { bytes } = &cp[idx - 1]
+                && let Ok(content) = std::str::from_utf8(bytes)
+            {
+                plaintexts.insert(string_index, content.to_owned());
+            }
+        }
+    }
+    if plaintexts.is_empty() {
+        return None;
+    }
+
+    let mut result: ClassFile = cf.clone();
+    let decrypt_index: u16 = add_decrypt_method(&mut result)?;
+    let mut ciphertexts: BTreeMap<u16, u16> = BTreeMap::new();
+    for (string_index, content) in plaintexts {
+        let utf8_index: u16 = add_entry(
+            &mut result.constant_pool,
+            ConstantPoolInfo::Utf8 {
+                bytes: encrypt_string(&content, rng).into_bytes(),
+            },
+        )?;
+        let encrypted_index: u16 = add_entry(
+            &mut result.constant_pool,
+            ConstantPoolInfo::String {
+                string_index: utf8_index,
+            },
+        )?;
+        ciphertexts.insert(string_index, encrypted_index);
+    }
+
+    for method in result.methods.iter_mut() {
+        if editable_code(method).is_none() {
+            continue;
+        }
+        for attribute in method.attributes.iter_mut() {
+            if !matches!(attribute, AttributeInfo::Code { .. }) {
+                continue;
+            }
+            // a method which would become too long keeps its plaintext strings
+            replace_instructions(attribute, &mut |_, instruction| {
+                let encrypted_index: u16 = *ciphertexts.get(&loaded_constant(instruction)?)?;
+                Some(vec![
+                    load_constant(encrypted_index),
+                    BytecodeInstruction::InvokeStatic {
+                        method_ref_index: decrypt_index,
+                    },
+                ])
+            });
+        }
+    }
+    Some(result)
+}
+
+/// Returns the instructions of the method, if it has some and they can be moved.
+fn editable_code(method: &MethodInfo) -> Option<&[(u32, BytecodeInstruction)]> {
+    method
+        .attributes
+        .iter()
+        .find_map(|attribute| match attribute {
+            AttributeInfo::Code {
+                code, attributes, ..
+            } if !attributes
+                .iter()
+                .any(|a| matches!(a, AttributeInfo::Unknown { .. })) =>
+            {
+                Some(code.as_slice())
+            }
+            _ => None,
+        })
+}
+
+/// Returns the index of the constant loaded by the given ldc or ldc_w instruction.
+fn loaded_constant(instruction: &BytecodeInstruction) -> Option<u16> {
+    match instruction {
+        BytecodeInstruction::Ldc {
+            constant_pool_index,
+        } => Some((*constant_pool_index).into()),
+        BytecodeInstruction::LdcW {
+            constant_pool_index,
+        } => Some(*constant_pool_index),
+        _ => None,
+    }
+}
+
+fn load_constant(cp_index: u16) -> BytecodeInstruction {
+    match u8::try_from(cp_index) {
+        Ok(constant_pool_index) => BytecodeInstruction::Ldc {
+            constant_pool_index,
+        },
+        Err(_) => BytecodeInstruction::LdcW {
+            constant_pool_index: cp_index,
+        },
+    }
+}
+
+/**
+ * Returns the hexadecimal form of the UTF-8 bytes of the given string XORed with a random key, followed by the one of
+ * the key. A leading 0x01 byte before the encrypted bytes, and a 0x00 one before the key, keep the leading zeros of
+ * both and make the two halves the same length.
+ */
+fn encrypt_string(content: &str, rng: &mut ChaCha8Rng) -> String {
+    let bytes: &[u8] = content.as_bytes();
+    let mut key: Vec<u8> = vec![0; bytes.len()];
+    rng.fill_bytes(&mut key);
+    let hex = |bytes: &mut dyn Iterator<Item = u8>| -> String {
+        bytes.map(|b| format!("{b:02x}")).collect()
+    };
+    hex(&mut std::iter::once(0x01).chain(bytes.iter().zip(key.iter()).map(|(b, k)| b ^ k)))
+        + &hex(&mut std::iter::once(0x00).chain(key.iter().copied()))
+}
+
+/// Returns the index of an entry equal to the given one, adding it if there is none.
+fn add_entry(cp: &mut ConstantPool, entry: ConstantPoolInfo) -> Option<u16> {
+    match (0..cp.len()).find(|i| cp[*i as u16] == entry) {
+        Some(i) => Some((i + 1) as u16),
+        None => cp.add(entry).ok(),
+    }
+}
+
+fn add_utf8(cp: &mut ConstantPool, content: &str) -> Option<u16> {
+    add_entry(
+        cp,
+        ConstantPoolInfo::Utf8 {
+            bytes: content.as_bytes().to_vec(),
+        },
+    )
+}
+
+fn add_class(cp: &mut ConstantPool, name: &str) -> Option<u16> {
+    let name_index: u16 = add_utf8(cp, name)?;
+    add_entry(cp, ConstantPoolInfo::Class { name_index })
+}
+
+fn add_name_and_type(cp: &mut ConstantPool, name: &str, descriptor: &str) -> Option<u16> {
+    let name_index: u16 = add_utf8(cp, name)?;
+    let descriptor_index: u16 = add_utf8(cp, descriptor)?;
+    add_entry(
+        cp,
+        ConstantPoolInfo::NameAndType {
+            name_index,
+            descriptor_index,
+        },
+    )
+}
+
+fn add_method_ref(
+    cp: &mut ConstantPool,
+    class_index: u16,
+    name: &str,
+    descriptor: &str,
+) -> Option<u16> {
+    let name_and_type_index: u16 = add_name_and_type(cp, name, descriptor)?;
+    add_entry(
+        cp,
+        ConstantPoolInfo::MethodRef {
+            class_index,
+            name_and_type_index,
+        },
+    )
+}
+
+/**
+ * Adds the method decrypting the strings to the class and returns the index of the Methodref entry pointing to it.
+ * It is the compiled form of:
+ * ```java
+ * private static String decrypt(String s) {
+ *     byte[] b = new BigInteger(s.substring(0, s.length() / 2), 16)
+ *         .xor(new BigInteger(s.substring(s.length() / 2), 16))
+ *         .toByteArray();
+ *     return new String(b, 1, b.length - 1, StandardCharsets.UTF_8).intern();
+ * }
+ * ```
+ */
+fn add_decrypt_method(cf: &mut ClassFile) -> Option<u16> {
+    const DESCRIPTOR: &str = "(Ljava/lang/String;)Ljava/lang/String;";
+    let taken: HashSet<String> = cf
+        .methods
+        .iter()
+        .map(|method| cf.constant_pool.get_utf8_content(method.name_index))
+        .collect();
+    let mut name: String = "decrypt$".to_owned();
+    while taken.contains(&name) {
+        name.push('$');
+    }
+
+    let this_class: u16 = cf.this_class;
+    let cp: &mut ConstantPool = &mut cf.constant_pool;
+    let big_integer: u16 = add_class(cp, "java/math/BigInteger")?;
+    let string: u16 = add_class(cp, "java/lang/String")?;
+    let big_integer_init: u16 =
+        add_method_ref(cp, big_integer, "<init>", "(Ljava/lang/String;I)V")?;
+    let length: u16 = add_method_ref(cp, string, "length", "()I")?;
+    let prefix: u16 = add_method_ref(cp, string, "substring", "(II)Ljava/lang/String;")?;
+    let suffix: u16 = add_method_ref(cp, string, "substring", "(I)Ljava/lang/String;")?;
+    let xor: u16 = add_method_ref(
+        cp,
+        big_integer,
+        "xor",
+        "(Ljava/math/BigInteger;)Ljava/math/BigInteger;",
+    )?;
+    let to_byte_array: u16 = add_method_ref(cp, big_integer, "toByteArray", "()[B")?;
+    let standard_charsets: u16 = add_class(cp, "java/nio/charset/StandardCharsets")?;
+    let utf_8_name_and_type: u16 = add_name_and_type(cp, "UTF_8", "Ljava/nio/charset/Charset;")?;
+    let utf_8: u16 = add_entry(
+        cp,
+        ConstantPoolInfo::FieldRef {
+            class_index: standard_charsets,
+            name_and_type_index: utf_8_name_and_type,
+        },
+    )?;
+    let string_init: u16 =
+        add_method_ref(cp, string, "<init>", "([BIILjava/nio/charset/Charset;)V")?;
+    let intern: u16 = add_method_ref(cp, string, "intern", "()Ljava/lang/String;")?;
+    let decrypt: u16 = add_method_ref(cp, this_class, &name, DESCRIPTOR)?;
+    let code_name_index: u16 = add_utf8(cp, "Code")?;
+    let name_index: u16 = add_utf8(cp, &name)?;
+    let descriptor_index: u16 = add_utf8(cp, DESCRIPTOR)?;
+
+    let radix = || BytecodeInstruction::BiPush { immediate: 16 };
+    let half_length = || {
+        [
+            BytecodeInstruction::ALoad {
+                local_variable_index: 0,
+            },
+            BytecodeInstruction::InvokeVirtual {
+                method_ref_index: length,
+            },
+            BytecodeInstruction::IConst { constant: 2 },
+            BytecodeInstruction::IDiv {},
+        ]
+    };
+    let mut instructions: Vec<BytecodeInstruction> = vec![
+        BytecodeInstruction::New {
+            constant_pool_index: big_integer,
+        },
+        BytecodeInstruction::Dup {},
+        BytecodeInstruction::ALoad {
+            local_variable_index: 0,
+        },
+        BytecodeInstruction::IConst { constant: 0 },
+    ];
+    instructions.extend(half_length());
+    instructions.extend([
+        BytecodeInstruction::InvokeVirtual {
+            method_ref_index: prefix,
+        },
+        radix(),
+        BytecodeInstruction::InvokeSpecial {
+            method_ref_index: big_integer_init,
+        },
+        BytecodeInstruction::New {
+            constant_pool_index: big_integer,
+        },
+        BytecodeInstruction::Dup {},
+        BytecodeInstruction::ALoad {
+            local_variable_index: 0,
+        },
+    ]);
+    instructions.extend(half_length());
+    instructions.extend([
+        BytecodeInstruction::InvokeVirtual {
+            method_ref_index: suffix,
+        },
+        radix(),
+        BytecodeInstruction::InvokeSpecial {
+            method_ref_index: big_integer_init,
+        },
+        BytecodeInstruction::InvokeVirtual {
+            method_ref_index: xor,
+        },
+        BytecodeInstruction::InvokeVirtual {
+            method_ref_index: to_byte_array,
+        },
+        BytecodeInstruction::AStore {
+            local_variable_index: 1,
+        },
+        BytecodeInstruction::New {
+            constant_pool_index: string,
+        },
+        BytecodeInstruction::Dup {},
+        BytecodeInstruction::ALoad {
+            local_variable_index: 1,
+        },
+        BytecodeInstruction::IConst { constant: 1 },
+        BytecodeInstruction::ALoad {
+            local_variable_index: 1,
+        },
+        BytecodeInstruction::ArrayLength {},
+        BytecodeInstruction::IConst { constant: 1 },
+        BytecodeInstruction::ISub {},
+        BytecodeInstruction::GetStatic {
+            field_ref_index: utf_8,
+        },
+        BytecodeInstruction::InvokeSpecial {
+            method_ref_index: string_init,
+        },
+        BytecodeInstruction::InvokeVirtual {
+            method_ref_index: intern,
+        },
+        BytecodeInstruction::AReturn {},
+    ]);
+    let mut position: u32 = 0;
+    let code: Vec<(u32, BytecodeInstruction)> = instructions
+        .into_iter()
+        .map(|instruction| {
+            let start: u32 = position;
+            position += get_instruction_length(&instruction);
+            (start, instruction)
+        })
+        .collect();
+
+    cf.methods.push(MethodInfo {
+        access_flags: MethodAccessFlags::from(
+            MethodAccessFlag::Private as u16
+                | MethodAccessFlag::Static as u16
+                | MethodAccessFlag::Synthetic as u16,
+        ),
+        name_index,
+        descriptor_index,
+        attributes: vec![AttributeInfo::Code {
+            name_index: code_name_index,
+            max_stack: 6,
+            max_locals: 2,
+            code,
+            trailing_bytes: Vec::new(),
+            exception_table: Vec::new(),
+            attributes: Vec::new(),
+        }],
+    });
+    Some(decrypt)
+}
+
+#[cfg(test)]
+mod tests {
+    use classfile::{
+        classfile::parse_class_file_from_bytes, remapping::compact_constant_pool,
+        verify::verify_method, writer::write_class_file,
+    };
+
+    use super::*;
+
+    /// Does what the added method does at run time.
+    fn decrypt(s: &str) -> String {
+        let bytes: Vec<u8> = (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect();
+        let (data, key) = bytes.split_at(bytes.len() / 2);
+        assert_eq!((data[0], key[0]), (0x01, 0x00));
+        let plaintext: Vec<u8> = data.iter().zip(key.iter()).map(|(d, k)| d ^ k).collect();
+        String::from_utf8(plaintext[1..].to_vec()).unwrap()
+    }
+
+    fn utf8_contents(cf: &ClassFile) -> Vec<String> {
+        (0..cf.constant_pool.len())
+            .filter_map(|i| match &cf.constant_pool[i as u16] {
+                ConstantPoolInfo::Utf8 { bytes } => Some(String::from_utf8_lossy(bytes).into()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn strings_are_encrypted() {
+        let mut rng: ChaCha8Rng = ChaCha8Rng::seed_from_u64(42);
+        for content in ["", "\0", "Hello, World!", "àèìòù", "\u{ffff}"] {
+            let encrypted: String = encrypt_string(content, &mut rng);
+            assert!(!encrypted.contains(content) || content.is_empty());
+            assert_eq!(decrypt(&encrypted), content);
+        }
+    }
+
+    #[test]
+    fn loaded_strings_are_replaced() {
+        let original: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/HelloWorld.class")).unwrap();
+        assert!(utf8_contents(&original).contains(&"Hello world!".to_owned()));
+
+        let bytes: Vec<u8> = write_class_file(
+            &compact_constant_pool(&EncryptStrings::new(0).transform(&original)).0,
+        );
+        let cf: ClassFile = parse_class_file_from_bytes(&bytes).unwrap();
+        assert!(!utf8_contents(&cf).contains(&"Hello world!".to_owned()));
+        assert_eq!(cf.methods.len(), original.methods.len() + 1);
+        let decrypt_method: &MethodInfo = cf
+            .find_method("decrypt$", "(Ljava/lang/String;)Ljava/lang/String;")
+            .unwrap();
+        assert!(
+            decrypt_method
+                .access_flags
+                .contains(MethodAccessFlag::Private)
+        );
+        assert!(
+            decrypt_method
+                .access_flags
+                .contains(MethodAccessFlag::Static)
+        );
+
+        let main: &MethodInfo = cf.find_method("main", "([Ljava/lang/String;)V").unwrap();
+        let code: &[(u32, BytecodeInstruction)] = editable_code(main).unwrap();
+        let string_index: u16 = code.iter().find_map(|(_, i)| loaded_constant(i)).unwrap();
+        let ConstantPoolInfo::String { string_index } = cf.constant_pool[string_index - 1] else {
+            panic!("Expected a String entry.");
+        };
+        assert_eq!(
+            decrypt(&cf.constant_pool.get_utf8_content(string_index)),
+            "Hello world!"
+        );
+        for method in cf.methods.iter() {
+            assert_eq!(verify_method(&cf.constant_pool, method), Ok(()));
+        }
+        assert_eq!(write_class_file(&cf), bytes);
+    }
+
+    #[test]
+    fn jumps_over_loaded_strings_are_fixed() {
+        let original: ClassFile =
+            parse_class_file_from_bytes(include_bytes!("../../e2e/data/Assertions.class")).unwrap();
+        let cf: ClassFile = parse_class_file_from_bytes(&write_class_file(
+            &EncryptStrings::new(0).transform(&original),
+        ))
+        .unwrap();
+        assert_eq!(cf.methods.len(), original.methods.len() + 1);
+        for method in cf.methods.iter() {
+            assert_eq!(verify_method(&cf.constant_pool, method), Ok(()));
+        }
+    }
+
+    #[test]
+    fn interfaces_are_untouched() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Shape.class");
+        let cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        assert!(cf.access_flags.contains(ClassAccessFlag::Interface));
+        assert_eq!(
+            write_class_file(&EncryptStrings::new(0).transform(&cf)),
+            bytes
+        );
+    }
+}
@@ -1,9 +1,16 @@
 #![forbid(unsafe_code)]
 
+mod canonicalize;
+mod compact_constant_pool;
+mod dedup_constant_pool;
+mod encrypt_strings;
 mod make_everything_public;
 mod pipeline;
+mod rename_identifiers;
 mod shuffle_fields;
 mod shuffle_methods;
+mod strip_assertions;
+mod strip_debug;
 mod transformation;
 
 use std::{
@@ -12,17 +19,19 @@ use std::{
     path::Path,
 };
 
-use binary_reader::BinaryReader;
 use classfile::{
-    classfile::{ClassFile, parse_class_file},
+    classfile::{ClassFile, ClassFileError, parse_class_file_from_bytes},
     writer::write_class_file,
 };
 use cli_parser::{CommandLineOption, CommandLineParser, CommandLineType};
 use zip::{CompressionMethod, ZipArchive, ZipWriter, write::FileOptions};
 
 use crate::{
+    canonicalize::Canonicalize, compact_constant_pool::CompactConstantPool,
+    dedup_constant_pool::DedupConstantPool, encrypt_strings::EncryptStrings,
     make_everything_public::MakeEverythingPublic, pipeline::TransformationPipeline,
-    shuffle_fields::ShuffleFields, shuffle_methods::ShuffleMethods,
+    rename_identifiers::RenameIdentifiers, shuffle_fields::ShuffleFields,
+    shuffle_methods::ShuffleMethods, strip_assertions::StripAssertions, strip_debug::StripDebug,
 };
 
 fn is_class_file(bytes: &[u8]) -> bool {
@@ -33,10 +42,13 @@ fn is_zip_file(bytes: &[u8]) -> bool {
     bytes.starts_with(&[0x50, 0x4B, 0x03, 0x04])
 }
 
-fn parse_and_rewrite(reader: &mut BinaryReader, pipeline: &TransformationPipeline) -> Vec<u8> {
-    let in_cf: ClassFile = parse_class_file(reader);
+fn parse_and_rewrite(
+    bytes: &[u8],
+    pipeline: &TransformationPipeline,
+) -> Result<Vec<u8>, ClassFileError> {
+    let in_cf: ClassFile = parse_class_file_from_bytes(bytes)?;
     let out_cf = pipeline.execute(&in_cf);
-    write_class_file(&out_cf)
+    Ok(write_class_file(&out_cf))
 }
 
 macro_rules! log {
@@ -124,6 +136,63 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     default_value: Some(false),
                 },
             ),
+            CommandLineOption::new(
+                None,
+                Some("encrypt-strings".to_owned()),
+                "Encrypts the string constants, decrypting them at run time.".to_owned(),
+                CommandLineType::Boolean {
+                    default_value: Some(false),
+                },
+            ),
+            CommandLineOption::new(
+                None,
+                Some("dedup-constant-pool".to_owned()),
+                "Merges duplicate constant pool entries.".to_owned(),
+                CommandLineType::Boolean {
+                    default_value: Some(false),
+                },
+            ),
+            CommandLineOption::new(
+                None,
+                Some("compact-constant-pool".to_owned()),
+                "Removes the unused constant pool entries.".to_owned(),
+                CommandLineType::Boolean {
+                    default_value: Some(false),
+                },
+            ),
+            CommandLineOption::new(
+                None,
+                Some("strip-assertions".to_owned()),
+                "Removes all assert statements.".to_owned(),
+                CommandLineType::Boolean {
+                    default_value: Some(false),
+                },
+            ),
+            CommandLineOption::new(
+                None,
+                Some("strip-debug".to_owned()),
+                "Removes source file names, line numbers and local variable names.".to_owned(),
+                CommandLineType::Boolean {
+                    default_value: Some(false),
+                },
+            ),
+            CommandLineOption::new(
+                None,
+                Some("rename-identifiers".to_owned()),
+                "Renames the private fields and methods to short meaningless names.".to_owned(),
+                CommandLineType::Boolean {
+                    default_value: Some(false),
+                },
+            ),
+            CommandLineOption::new(
+                None,
+                Some("canonicalize".to_owned()),
+                "Sorts the constant pool and the attributes, making the output reproducible."
+                    .to_owned(),
+                CommandLineType::Boolean {
+                    default_value: Some(false),
+                },
+            ),
         ],
     );
 
@@ -136,10 +205,27 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let make_everything_public = args.get("make-everything-public").unwrap().as_bool();
     let shuffle_fields = args.get("shuffle-fields").unwrap().as_bool();
     let shuffle_methods = args.get("shuffle-methods").unwrap().as_bool();
+    let encrypt_strings = args.get("encrypt-strings").unwrap().as_bool();
+    let dedup_constant_pool = args.get("dedup-constant-pool").unwrap().as_bool();
+    let compact_constant_pool = args.get("compact-constant-pool").unwrap().as_bool();
+    let strip_assertions = args.get("strip-assertions").unwrap().as_bool();
+    let strip_debug = args.get("strip-debug").unwrap().as_bool();
+    let rename_identifiers = args.get("rename-identifiers").unwrap().as_bool();
+    let canonicalize = args.get("canonicalize").unwrap().as_bool();
     let seed: u64 = args.get("seed").unwrap().as_u64();
 
     let mut pipeline: TransformationPipeline = TransformationPipeline::new();
 
+    if strip_assertions {
+        pipeline.add(Box::new(StripAssertions {}));
+    }
+    if strip_debug {
+        pipeline.add(Box::new(StripDebug {}));
+    }
+    // before making everything public, which would leave no private member to rename
+    if rename_identifiers {
+        pipeline.add(Box::new(RenameIdentifiers {}));
+    }
     if make_everything_public {
         pipeline.add(Box::new(MakeEverythingPublic {}));
     }
@@ -149,6 +235,19 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     if shuffle_methods {
         pipeline.add(Box::new(ShuffleMethods::new(seed)));
     }
+    if encrypt_strings {
+        pipeline.add(Box::new(EncryptStrings::new(seed)));
+    }
+    if dedup_constant_pool {
+        pipeline.add(Box::new(DedupConstantPool {}));
+    }
+    // after the passes which leave unused entries behind
+    if compact_constant_pool {
+        pipeline.add(Box::new(CompactConstantPool {}));
+    }
+    if canonicalize {
+        pipeline.add(Box::new(Canonicalize {}));
+    }
 
     let mut file = File::open(&input_filename)
         .unwrap_or_else(|err| die!("Could not open file '{}' due to: {}.", input_filename, err));
@@ -176,8 +275,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let mut file_bytes = Vec::new();
         file.read_to_end(&mut file_bytes)?;
 
-        let mut reader = BinaryReader::new(&file_bytes, binary_reader::Endianness::Big);
-        let out_bytes = parse_and_rewrite(&mut reader, &pipeline);
+        let out_bytes = parse_and_rewrite(&file_bytes, &pipeline)
+            .unwrap_or_else(|error| die!("Invalid class file '{}': {}", input_filename, error));
         log!(
             quiet,
             "{} -> valid class file ({} bytes)",
@@ -215,8 +314,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             let mut file_bytes = Vec::new();
             entry.read_to_end(&mut file_bytes)?;
 
-            let mut reader = BinaryReader::new(&file_bytes, binary_reader::Endianness::Big);
-            let out_bytes = parse_and_rewrite(&mut reader, &pipeline);
+            let out_bytes = parse_and_rewrite(&file_bytes, &pipeline).unwrap_or_else(|error| {
+                die!(
+                    "Invalid class file '{}' in '{}': {}",
+                    name,
+                    input_filename,
+                    error
+                )
+            });
             log!(quiet, "{} ({} bytes) OK", name, file_bytes.len());
 
             zip_writer.start_file(name, options)?;
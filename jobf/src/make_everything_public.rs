@@ -31,6 +31,10 @@ impl ClassFileTransformation for MakeEverythingPublic {
 }
 
 fn make_class_flags_public(flags: ClassAccessFlags) -> ClassAccessFlags {
+    // a module-info must not have any flag other than ACC_MODULE
+    if flags.contains(ClassAccessFlag::Module) {
+        return flags;
+    }
     ClassAccessFlags::from(flags.to_u16() | (ClassAccessFlag::Public as u16))
 }
 
@@ -96,12 +100,20 @@ fn make_attribute_public(attribute: &AttributeInfo) -> AttributeInfo {
         | AttributeInfo::ConstantValue { .. }
         | AttributeInfo::Exceptions { .. }
         | AttributeInfo::EnclosingMethod { .. }
-        | AttributeInfo::NestHost { .. } => attribute.clone(),
+        | AttributeInfo::NestHost { .. }
+        | AttributeInfo::PermittedSubclasses { .. }
+        | AttributeInfo::Module { .. }
+        | AttributeInfo::ModulePackages { .. }
+        | AttributeInfo::ModuleMainClass { .. }
+        | AttributeInfo::Deprecated { .. }
+        | AttributeInfo::Synthetic { .. }
+        | AttributeInfo::Unknown { .. } => attribute.clone(),
         AttributeInfo::Code {
             name_index,
             max_stack,
             max_locals,
             code,
+            trailing_bytes,
             exception_table,
             attributes,
         } => AttributeInfo::Code {
@@ -109,6 +121,7 @@ fn make_attribute_public(attribute: &AttributeInfo) -> AttributeInfo {
             max_stack: *max_stack,
             max_locals: *max_locals,
             code: code.clone(),
+            trailing_bytes: trailing_bytes.clone(),
             exception_table: exception_table.clone(),
             attributes: make_attributes_public(attributes),
         },
@@ -23,3 +23,57 @@ impl TransformationPipeline {
         tmp
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use classfile::{
+        attributes::{AttributeInfo, AttributeKind, find_attribute},
+        classfile::parse_class_file_from_bytes,
+        constant_pool::ConstantPoolInfo,
+        writer::write_class_file,
+    };
+
+    use super::*;
+    use crate::{dedup_constant_pool::DedupConstantPool, strip_debug::StripDebug};
+
+    #[test]
+    fn unknown_attributes_are_preserved() {
+        let bytes: &[u8] = include_bytes!("../../e2e/data/Employee.class");
+        let mut cf: ClassFile = parse_class_file_from_bytes(bytes).unwrap();
+        let name_index: u16 = cf
+            .constant_pool
+            .add(ConstantPoolInfo::Utf8 {
+                bytes: b"VendorData".to_vec(),
+            })
+            .unwrap();
+        let info: Vec<u8> = vec![0xde, 0xad, 0xbe, 0xef, 0x00];
+        cf.attributes.push(AttributeInfo::Unknown {
+            name_index,
+            info: info.clone(),
+        });
+
+        let mut pipeline: TransformationPipeline = TransformationPipeline::new();
+        pipeline.add(Box::new(StripDebug {}));
+        pipeline.add(Box::new(DedupConstantPool {}));
+        let result: ClassFile = pipeline.execute(&cf);
+        let output: Vec<u8> = write_class_file(&result);
+
+        let Some(AttributeInfo::Unknown {
+            name_index,
+            info: result_info,
+        }) = find_attribute(&result.attributes, AttributeKind::Unknown)
+        else {
+            unreachable!()
+        };
+        assert_eq!(
+            result.constant_pool.get_utf8_content(*name_index),
+            "VendorData"
+        );
+        assert_eq!(*result_info, info);
+        // the class attributes are the last part of the file
+        let mut expected_tail: Vec<u8> = name_index.to_be_bytes().to_vec();
+        expected_tail.extend((info.len() as u32).to_be_bytes());
+        expected_tail.extend(&info);
+        assert!(output.ends_with(&expected_tail));
+    }
+}
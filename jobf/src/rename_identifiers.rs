@@ -0,0 +1,21 @@
+use classfile::{
+    classfile::ClassFile,
+    obfuscate::{SequentialRenamer, rename_identifiers},
+};
+
+use crate::transformation::ClassFileTransformation;
+
+/**
+ * Renames the private fields and methods of the class to short meaningless names (`a`, `b`, ..., `z`, `aa`, ...) not
+ * used by its other members.
+ */
+pub(crate) struct RenameIdentifiers {}
+
+impl ClassFileTransformation for RenameIdentifiers {
+    fn transform(&self, cf: &ClassFile) -> ClassFile {
+        let mut result: ClassFile = cf.clone();
+        let renamer: SequentialRenamer = SequentialRenamer::new(cf);
+        rename_identifiers(&mut result, &|name| renamer.rename(name));
+        result
+    }
+}
@@ -0,0 +1,17 @@
+use classfile::{classfile::ClassFile, obfuscate::strip_assertions};
+
+use crate::transformation::ClassFileTransformation;
+
+/**
+ * Removes every `assert` statement, together with the synthetic `$assertionsDisabled` field and its initialization
+ * in the static initializer.
+ */
+pub(crate) struct StripAssertions {}
+
+impl ClassFileTransformation for StripAssertions {
+    fn transform(&self, cf: &ClassFile) -> ClassFile {
+        let mut result: ClassFile = cf.clone();
+        strip_assertions(&mut result);
+        result
+    }
+}
@@ -0,0 +1,17 @@
+use classfile::{classfile::ClassFile, obfuscate::strip_debug};
+
+use crate::transformation::ClassFileTransformation;
+
+/**
+ * Removes the attributes only used by debuggers: SourceFile, LineNumberTable, LocalVariableTable and
+ * LocalVariableTypeTable. The constant pool entries they referenced are left untouched.
+ */
+pub(crate) struct StripDebug {}
+
+impl ClassFileTransformation for StripDebug {
+    fn transform(&self, cf: &ClassFile) -> ClassFile {
+        let mut result: ClassFile = cf.clone();
+        strip_debug(&mut result);
+        result
+    }
+}